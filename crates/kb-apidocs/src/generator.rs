@@ -6,6 +6,9 @@ use kb_core::generator::OutputGenerator;
 use kb_core::{Result, Schema};
 use serde_json::json;
 
+/// Output format version; bump whenever the generated specification changes
+const FORMAT_VERSION: &str = "1";
+
 /// API documentation generator
 pub struct ApiDocsGenerator;
 
@@ -223,6 +226,14 @@ impl OutputGenerator for ApiDocsGenerator {
         "openapi.json"
     }
 
+    fn description(&self) -> &str {
+        "OpenAPI 3.0 specifications (.openapi.json)"
+    }
+
+    fn version(&self) -> &str {
+        FORMAT_VERSION
+    }
+
     fn generate(&self, schema: &Schema) -> Result<String> {
         self.generate_openapi(schema)
     }
@@ -277,6 +288,7 @@ mod tests {
                     nodejs: "const f = x**2;".to_string(),
                 },
                 expected_output: Some("2*x".to_string()),
+                doctest: Default::default(),
             }],
             article: None,
             use_cases: vec![],
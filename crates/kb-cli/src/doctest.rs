@@ -0,0 +1,103 @@
+//! `kb doctest` - generate a Cargo test crate from the Rust examples
+//!
+//! With `--test` the crate is built with `cargo test` and compile errors and
+//! failing tests are reported against the schema example they came from.
+
+use crate::{collect_schemas, MessageFormat};
+use anyhow::{Context, Result};
+use kb_core::schema::{DoctestMode, SchemaFile};
+use kb_core::Severity;
+use kb_doctest::{DoctestConfig, DoctestCrate};
+use std::path::PathBuf;
+
+/// How to run `cargo test` on the generated crate
+pub(crate) struct CargoRun {
+    pub cargo: String,
+    pub no_run: bool,
+}
+
+pub(crate) fn doctest_command(
+    schema_path: PathBuf,
+    output_dir: PathBuf,
+    config: DoctestConfig,
+    run: Option<CargoRun>,
+    message_format: MessageFormat,
+) -> Result<()> {
+    let human = message_format == MessageFormat::Human;
+
+    let mut krate = DoctestCrate::new(config);
+    for path in collect_schemas(&schema_path)? {
+        let file = SchemaFile::parse(&path)
+            .with_context(|| format!("Failed to load schema {}", path.display()))?;
+        krate.add_schema(&file);
+    }
+
+    let generated = krate.generate();
+    generated
+        .write(&output_dir)
+        .with_context(|| format!("Failed to write test crate to {}", output_dir.display()))?;
+
+    if human {
+        println!(
+            "🦀 Generated {} test(s) in {} ({} no_run, {} ignored)",
+            krate.len(),
+            output_dir.display(),
+            krate.count(DoctestMode::NoRun),
+            krate.count(DoctestMode::Ignore)
+        );
+    }
+
+    let Some(run) = run else {
+        if human {
+            println!(
+                "   Run `cargo test` in {} or pass --test",
+                output_dir.display()
+            );
+        }
+        return Ok(());
+    };
+
+    if human {
+        println!(
+            "\n🔨 Running cargo test{}...\n",
+            if run.no_run { " --no-run" } else { "" }
+        );
+    }
+    let report = generated
+        .test(&output_dir, &run.cargo, run.no_run)
+        .context("Failed to run cargo test on the generated crate")?;
+
+    for error in &report.errors {
+        match message_format {
+            MessageFormat::Human => println!("{}", error.render(Severity::Error, None)),
+            MessageFormat::Json => println!("{}", error.to_json(Severity::Error)),
+        }
+    }
+    for message in &report.unmapped {
+        eprintln!("{}\n", message);
+    }
+
+    if human {
+        if run.no_run {
+            println!(
+                "📊 {} error(s) while compiling {} example(s)",
+                report.errors.len(),
+                krate.len()
+            );
+        } else {
+            println!(
+                "📊 {} passed, {} failed, {} ignored",
+                report.passed, report.failed, report.ignored
+            );
+        }
+    }
+
+    if !report.success {
+        anyhow::bail!(
+            "cargo test failed ({} example error(s), {} other error(s))",
+            report.errors.len(),
+            report.unmapped.len()
+        );
+    }
+    Ok(())
+}
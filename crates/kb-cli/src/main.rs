@@ -3,21 +3,42 @@ use anyhow::{Context, Result};
 ///
 /// Command-line interface for building, validating, and generating documentation
 /// from KB schemas.
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use kb_apidocs::ApiDocsGenerator;
 use kb_colab::{ColabConfig, ColabGenerator, ColabManifest};
-use kb_core::{generator::OutputGenerator, Schema};
+use kb_core::generator::cache::{BuildCache, SourceFingerprint};
+use kb_core::generator::execute::NotebookExecutor;
+use kb_core::generator::{GeneratorRegistry, OutputGenerator};
+use kb_core::inventory::ApiInventory;
+use kb_core::parser::is_fragment;
+use kb_core::runner::{Language, Normalization, RunnerConfig, DEFAULT_RUST_DEPENDENCIES};
+use kb_core::schema::Strictness;
+use kb_core::{KbError, Schema, Severity};
+use kb_doctest::DoctestConfig;
+use kb_graph::{GraphFormat, TopicGraph};
 use kb_json::JsonGenerator;
-use kb_jupyter::JupyterGenerator;
+use kb_jupyter::{JupyterGenerator, Kernel};
 use kb_latex::LatexGenerator;
 use kb_llm_rag::LlmRagGenerator;
-use kb_mdbook::MdBookGenerator;
+use kb_mdbook::{BookConfig, BookManifest, MdBookGenerator};
 use kb_sitemap::{
     generate_robots_txt_snippet, verify_robots_txt, BingSubmitter, IndexNowSubmitter,
     SitemapConfig, SitemapGenerator,
 };
 use kb_vue::VueGenerator;
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::Duration;
+
+mod doctest;
+mod migrate;
+mod test_examples;
+mod validate;
+mod watch;
+
+use validate::{CrossLanguage, ReportFormat, ValidateOptions};
 
 #[derive(Parser)]
 #[command(name = "kb")]
@@ -40,16 +61,205 @@ enum Commands {
         #[arg(short, long, default_value = "mathhook-docs-site/public/outputs")]
         output: PathBuf,
 
-        /// Generators to run (comma-separated: jupyter,mdbook,llm-rag,vue,api-docs,colab,latex,json,all)
+        /// Generators to run (comma-separated: jupyter,jupyter-rust,jupyter-js,mdbook,llm-rag,vue,api-docs,colab,latex,json,all)
+        #[arg(short, long, default_value = "all")]
+        generators: String,
+
+        /// Number of schemas to process concurrently (0 = one per CPU core)
+        #[arg(short, long, default_value_t = 1)]
+        jobs: usize,
+
+        /// Regenerate every output, ignoring the build cache
+        #[arg(long)]
+        force: bool,
+
+        /// Run Jupyter and Colab notebooks with a local kernel and store their outputs
+        #[arg(long)]
+        execute: bool,
+
+        /// How to print schema errors
+        #[arg(long, value_enum, default_value_t = MessageFormat::Human)]
+        message_format: MessageFormat,
+    },
+
+    /// Watch schemas and rebuild them as they change
+    Watch {
+        /// Path to schema file or directory
+        #[arg(value_name = "SCHEMA")]
+        schema_path: PathBuf,
+
+        /// Output directory (default: mathhook-docs-site/public/outputs)
+        #[arg(short, long, default_value = "mathhook-docs-site/public/outputs")]
+        output: PathBuf,
+
+        /// Generators to run (comma-separated, see `kb list`)
         #[arg(short, long, default_value = "all")]
         generators: String,
+
+        /// Milliseconds to wait for a burst of saves to settle before rebuilding
+        #[arg(long, default_value_t = 300)]
+        debounce_ms: u64,
     },
 
-    /// Validate a schema file
+    /// Validate a schema file or directory
     Validate {
-        /// Path to schema file
+        /// Path to schema file or directory (directories also check links between schemas)
         #[arg(value_name = "SCHEMA")]
         schema_path: PathBuf,
+
+        /// Treat warnings as errors
+        #[arg(long)]
+        deny_warnings: bool,
+
+        /// Report unknown YAML keys as errors instead of warnings
+        #[arg(long)]
+        strict: bool,
+
+        /// How to report snippets that disagree across Rust, Python and Node.js
+        #[arg(long, value_enum, default_value_t = CrossLanguage::Warn)]
+        cross_language: CrossLanguage,
+
+        /// Rustdoc JSON of a crate; code_refs.rust paths must exist in it (repeatable)
+        #[arg(long, value_name = "JSON")]
+        rust_api: Vec<PathBuf>,
+
+        /// Python symbol list, one dotted path per line; checks code_refs.python (repeatable)
+        #[arg(long, value_name = "FILE")]
+        python_api: Vec<PathBuf>,
+
+        /// Node.js symbol list, one dotted path per line; checks code_refs.nodejs (repeatable)
+        #[arg(long, value_name = "FILE")]
+        node_api: Vec<PathBuf>,
+
+        /// How to print errors and warnings
+        #[arg(long, value_enum, default_value_t = MessageFormat::Human)]
+        message_format: MessageFormat,
+
+        /// Write a validation report for CI to this file
+        #[arg(long, value_name = "PATH")]
+        report: Option<PathBuf>,
+
+        /// Format of the --report file
+        #[arg(long, value_enum, default_value_t = ReportFormat::Json)]
+        report_format: ReportFormat,
+    },
+
+    /// Run example snippets and compare their output with `expected_output`
+    TestExamples {
+        /// Path to schema file or directory
+        #[arg(value_name = "SCHEMA")]
+        schema_path: PathBuf,
+
+        /// Languages to run (comma-separated: rust,python,nodejs,all)
+        #[arg(short, long, default_value = "all")]
+        languages: String,
+
+        /// Python interpreter
+        #[arg(long, default_value = "python3")]
+        python: String,
+
+        /// Node.js interpreter
+        #[arg(long, default_value = "node")]
+        node: String,
+
+        /// Cargo executable for Rust snippets
+        #[arg(long, default_value = "cargo")]
+        cargo: String,
+
+        /// Dependency line for the scratch Rust crate (repeatable; replaces the
+        /// default `mathhook` and `mathhook-core` lines)
+        #[arg(long = "rust-dependency", value_name = "LINE", default_values = DEFAULT_RUST_DEPENDENCIES)]
+        rust_dependencies: Vec<String>,
+
+        /// Directory for scratch harness files (default: system temp dir)
+        #[arg(long)]
+        scratch_dir: Option<PathBuf>,
+
+        /// Seconds before a snippet is killed (includes Rust compilation)
+        #[arg(long, default_value_t = 60)]
+        timeout: u64,
+
+        /// Collapse runs of whitespace before comparing output
+        #[arg(long)]
+        collapse_whitespace: bool,
+
+        /// Ignore blank lines when comparing output
+        #[arg(long)]
+        ignore_blank_lines: bool,
+
+        /// Round decimal numbers to this many digits before comparing output
+        #[arg(long, value_name = "DIGITS")]
+        float_precision: Option<usize>,
+
+        /// Also run examples without expected_output, checking only that they succeed
+        #[arg(long)]
+        run_all: bool,
+
+        /// How to print mismatches and failures
+        #[arg(long, value_enum, default_value_t = MessageFormat::Human)]
+        message_format: MessageFormat,
+    },
+
+    /// Generate a Cargo test crate from the Rust examples and optionally run it
+    Doctest {
+        /// Path to schema file or directory
+        #[arg(value_name = "SCHEMA")]
+        schema_path: PathBuf,
+
+        /// Output directory for the generated crate
+        #[arg(short, long, default_value = "target/kb-doctests")]
+        output: PathBuf,
+
+        /// Package name of the generated crate
+        #[arg(long, default_value = "mathhook-kb-doctests")]
+        crate_name: String,
+
+        /// Dependency line for the generated crate (repeatable; replaces the
+        /// default `mathhook` and `mathhook-core` lines)
+        #[arg(long = "dependency", value_name = "LINE", default_values = DEFAULT_RUST_DEPENDENCIES)]
+        dependencies: Vec<String>,
+
+        /// Run `cargo test` on the generated crate
+        #[arg(long)]
+        test: bool,
+
+        /// Only compile the tests (implies --test)
+        #[arg(long)]
+        no_run: bool,
+
+        /// Cargo executable
+        #[arg(long, default_value = "cargo")]
+        cargo: String,
+
+        /// How to print compile errors and test failures
+        #[arg(long, value_enum, default_value_t = MessageFormat::Human)]
+        message_format: MessageFormat,
+    },
+
+    /// Export the topic knowledge graph (JSON, GraphViz DOT, Mermaid)
+    Graph {
+        /// Path to schema file or directory
+        #[arg(value_name = "SCHEMA")]
+        schema_path: PathBuf,
+
+        /// Output directory for graph files
+        #[arg(short, long, default_value = "mathhook-docs-site/public/outputs/graph")]
+        output: PathBuf,
+
+        /// Formats to export (comma-separated: json,dot,mermaid,all)
+        #[arg(short, long, default_value = "all")]
+        format: String,
+    },
+
+    /// Upgrade schema files to the current schema version
+    Migrate {
+        /// Path to schema file or directory
+        #[arg(value_name = "SCHEMA")]
+        schema_path: PathBuf,
+
+        /// Rewrite the files in place (default: only list pending migrations)
+        #[arg(long)]
+        write: bool,
     },
 
     /// List available generators
@@ -62,6 +272,19 @@ enum Commands {
     /// Generate sitemap and optionally submit to search engines
     #[command(subcommand)]
     Sitemap(SitemapCommands),
+
+    /// JSON Schema for the KB YAML format
+    #[command(subcommand)]
+    Schema(SchemaCommands),
+}
+
+/// Output format for schema errors and warnings
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum MessageFormat {
+    /// Compiler-style messages with a source excerpt
+    Human,
+    /// One JSON object per line, for editor integrations
+    Json,
 }
 
 #[derive(Subcommand)]
@@ -77,6 +300,16 @@ enum ColabCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum SchemaCommands {
+    /// Export the JSON Schema (draft 2020-12) of KB schema files for editors
+    Export {
+        /// Write the schema to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
 #[derive(Subcommand)]
 enum SitemapCommands {
     /// Generate sitemap.xml from schemas
@@ -175,293 +408,536 @@ fn main() -> Result<()> {
             schema_path,
             output,
             generators,
-        } => build_command(schema_path, output, generators),
-        Commands::Validate { schema_path } => validate_command(schema_path),
-        Commands::List => {
-            list_command();
-            Ok(())
+            jobs,
+            force,
+            execute,
+            message_format,
+        } => build_command(
+            schema_path,
+            output,
+            generators,
+            jobs,
+            force,
+            execute,
+            message_format,
+        ),
+        Commands::Watch {
+            schema_path,
+            output,
+            generators,
+            debounce_ms,
+        } => watch::watch_command(schema_path, output, generators, debounce_ms),
+        Commands::Validate {
+            schema_path,
+            deny_warnings,
+            strict,
+            cross_language,
+            rust_api,
+            python_api,
+            node_api,
+            message_format,
+            report,
+            report_format,
+        } => validate::validate_command(
+            schema_path,
+            ValidateOptions {
+                deny_warnings,
+                strictness: if strict {
+                    Strictness::Strict
+                } else {
+                    Strictness::Lenient
+                },
+                cross_language,
+                inventory: load_inventory(&rust_api, &python_api, &node_api)?,
+                message_format,
+                report,
+                report_format,
+            },
+        ),
+        Commands::TestExamples {
+            schema_path,
+            languages,
+            python,
+            node,
+            cargo,
+            rust_dependencies,
+            scratch_dir,
+            timeout,
+            collapse_whitespace,
+            ignore_blank_lines,
+            float_precision,
+            run_all,
+            message_format,
+        } => {
+            let defaults = RunnerConfig::default();
+            let config = RunnerConfig {
+                python,
+                node,
+                cargo,
+                rust_dependencies,
+                scratch_dir: scratch_dir.unwrap_or(defaults.scratch_dir),
+                timeout: Duration::from_secs(timeout),
+                normalization: Normalization {
+                    collapse_whitespace,
+                    ignore_blank_lines,
+                    float_precision,
+                },
+                run_all,
+            };
+            test_examples::test_examples_command(schema_path, &languages, config, message_format)
+        }
+        Commands::Doctest {
+            schema_path,
+            output,
+            crate_name,
+            dependencies,
+            test,
+            no_run,
+            cargo,
+            message_format,
+        } => {
+            let config = DoctestConfig {
+                crate_name,
+                dependencies,
+            };
+            let run = (test || no_run).then_some(doctest::CargoRun { cargo, no_run });
+            doctest::doctest_command(schema_path, output, config, run, message_format)
         }
+        Commands::Graph {
+            schema_path,
+            output,
+            format,
+        } => graph_command(schema_path, output, format),
+        Commands::Migrate { schema_path, write } => migrate::migrate_command(schema_path, write),
+        Commands::List => list_command(),
         Commands::Colab(colab_cmd) => handle_colab_command(colab_cmd),
         Commands::Sitemap(sitemap_cmd) => handle_sitemap_command(sitemap_cmd),
+        Commands::Schema(schema_cmd) => handle_schema_command(schema_cmd),
+    }
+}
+
+/// Load the API inventory for `kb validate`, if any inventory file was given
+fn load_inventory(
+    rust: &[PathBuf],
+    python: &[PathBuf],
+    node: &[PathBuf],
+) -> Result<Option<ApiInventory>> {
+    if rust.is_empty() && python.is_empty() && node.is_empty() {
+        return Ok(None);
+    }
+
+    let mut inventory = ApiInventory::new();
+    for path in rust {
+        inventory
+            .load_rustdoc(path)
+            .with_context(|| format!("Failed to load rustdoc JSON {}", path.display()))?;
     }
+    for (language, paths) in [(Language::Python, python), (Language::NodeJs, node)] {
+        for path in paths {
+            inventory
+                .load_symbol_list(language, path)
+                .with_context(|| format!("Failed to load symbol list {}", path.display()))?;
+        }
+    }
+    Ok(Some(inventory))
+}
+
+/// Build the registry of every available output generator
+///
+/// Adding a new output format only requires registering its generator here.
+/// With an executor, notebook generators run each notebook and keep its outputs.
+fn build_registry(
+    colab_config: &ColabConfig,
+    executor: Option<&NotebookExecutor>,
+) -> Result<GeneratorRegistry> {
+    let mut registry = GeneratorRegistry::new();
+    for kernel in Kernel::ALL {
+        let generator = JupyterGenerator::with_kernel(kernel);
+        registry.register(match executor {
+            Some(executor) => generator.with_executor(executor.clone()),
+            None => generator,
+        });
+    }
+    registry.register(MdBookGenerator::new()?);
+    registry.register(LlmRagGenerator::new());
+    registry.register(VueGenerator::new()?);
+    registry.register(ApiDocsGenerator::new()?);
+    let colab = ColabGenerator::with_config(colab_config.clone());
+    registry.register(match executor {
+        Some(executor) => colab.with_executor(executor.clone()),
+        None => colab,
+    });
+    registry.register(LatexGenerator::new()?);
+    registry.register(JsonGenerator::new());
+    Ok(registry)
 }
 
 fn build_command(
     schema_path: PathBuf,
     output_dir: PathBuf,
     generators_str: String,
+    jobs: usize,
+    force: bool,
+    execute: bool,
+    message_format: MessageFormat,
 ) -> Result<()> {
-    println!("🔨 Building documentation...\n");
-
-    // Collect all schemas to process
-    let schemas = collect_schemas(&schema_path)?;
-
-    // Create output subdirectories for each type
-    let jupyter_dir = output_dir.join("jupyter");
-    let mdbook_dir = output_dir.join("mdbook");
-    let llm_rag_dir = output_dir.join("llm-rag");
-    let vue_dir = output_dir.join("vue");
-    let api_docs_dir = output_dir.join("api-docs");
-    let colab_dir = output_dir.join("colab");
-    let latex_dir = output_dir.join("latex");
-    let json_dir = output_dir.join("json");
-
-    // Parse generators to run
-    let generators_list: Vec<&str> = generators_str.split(',').collect();
-    let run_all = generators_list.contains(&"all");
-    let run_jupyter = run_all || generators_list.contains(&"jupyter");
-    let run_mdbook = run_all || generators_list.contains(&"mdbook");
-    let run_llm_rag = run_all || generators_list.contains(&"llm-rag");
-    let run_vue = run_all || generators_list.contains(&"vue");
-    let run_api_docs = run_all || generators_list.contains(&"api-docs");
-    let run_colab = run_all || generators_list.contains(&"colab");
-    let run_latex = run_all || generators_list.contains(&"latex");
-    let run_json = run_all || generators_list.contains(&"json");
-
-    // Create directories for enabled generators
-    if run_jupyter {
-        std::fs::create_dir_all(&jupyter_dir).context("Failed to create jupyter directory")?;
-    }
-    if run_mdbook {
-        std::fs::create_dir_all(&mdbook_dir).context("Failed to create mdbook directory")?;
-    }
-    if run_llm_rag {
-        std::fs::create_dir_all(&llm_rag_dir).context("Failed to create llm-rag directory")?;
-    }
-    if run_vue {
-        std::fs::create_dir_all(&vue_dir).context("Failed to create vue directory")?;
-    }
-    if run_api_docs {
-        std::fs::create_dir_all(&api_docs_dir).context("Failed to create api-docs directory")?;
-    }
-    if run_colab {
-        std::fs::create_dir_all(&colab_dir).context("Failed to create colab directory")?;
-    }
-    if run_latex {
-        std::fs::create_dir_all(&latex_dir).context("Failed to create latex directory")?;
-    }
-    if run_json {
-        std::fs::create_dir_all(&json_dir).context("Failed to create json directory")?;
+    // With --message-format json stdout carries only diagnostics, so progress is suppressed
+    let human = message_format == MessageFormat::Human;
+    macro_rules! progress {
+        ($($arg:tt)*) => {
+            if human {
+                println!($($arg)*);
+            }
+        };
     }
 
-    let mut total_generated = 0;
+    progress!("🔨 Building documentation...\n");
 
-    // Setup Colab manifest if we're generating Colab notebooks
+    // Resolve generators to run before touching the filesystem
     let colab_config = ColabConfig::default();
-    let mut colab_manifest = ColabManifest::new(colab_config.clone());
+    let executor = execute.then(NotebookExecutor::default);
+    let registry = build_registry(&colab_config, executor.as_ref())?;
+    let generators = registry.select(&generators_str)?;
 
-    for schema_path in &schemas {
-        println!("📄 Loading schema: {}", schema_path.display());
-        let schema = Schema::load_from_file(schema_path).context("Failed to load schema")?;
+    // Collect all schemas to process
+    let schemas = collect_schemas(&schema_path)?;
 
-        println!("   Topic: {}", schema.topic);
-        println!("   Title: {}\n", schema.title);
+    // Create output subdirectories for enabled generators
+    for generator in &generators {
+        let generator_dir = output_dir.join(generator.name());
+        std::fs::create_dir_all(&generator_dir)
+            .with_context(|| format!("Failed to create {} directory", generator.name()))?;
+    }
 
-        let mut generated_count = 0;
+    // Outputs whose schema, generator and generator version are unchanged are skipped
+    let mut cache = BuildCache::load(&output_dir).with_force(force);
 
-        // Extract category from topic for organization
-        let category = schema.topic.split('.').next().unwrap_or("misc");
+    let mut total_generated = 0;
+    let mut total_skipped = 0;
+    let mut cache_records = Vec::new();
+
+    // Schemas are built concurrently but reported (and added to the manifest)
+    // in sorted schema order, so the output never depends on thread scheduling
+    process_in_order(
+        &schemas,
+        resolve_jobs(jobs),
+        |schema_path| build_schema(schema_path, &generators, &output_dir, &cache),
+        |schema_path, result| {
+            let built =
+                result.map_err(|err| report_schema_error(err, schema_path, message_format))?;
+
+            progress!("📄 Loading schema: {}", schema_path.display());
+            progress!("   Topic: {}", built.topic);
+            progress!("   Title: {}\n", built.title);
+
+            for output in &built.outputs {
+                if output.regenerated {
+                    progress!("⚙️  Generating {} output...", output.generator.name());
+                    progress!("   ✅ {}", output.path.display());
+                    total_generated += 1;
+                } else {
+                    progress!("   ⏭️  {} (unchanged)", output.path.display());
+                    total_skipped += 1;
+                }
+                cache_records.push((
+                    output.generator,
+                    built.source.clone(),
+                    built.topic.clone(),
+                    built.title.clone(),
+                    output.path.clone(),
+                ));
+            }
 
-        // Run Jupyter generator
-        if run_jupyter {
-            println!("📓 Generating Jupyter notebook...");
-            let generator = JupyterGenerator::new();
-            let filename = generator.get_output_filename(&schema);
+            progress!();
+            Ok(())
+        },
+    )?;
 
-            // Organize by category
-            let category_dir = jupyter_dir.join(category);
-            std::fs::create_dir_all(&category_dir)?;
-            let output_path = category_dir.join(&filename);
+    for (generator, source, topic, title, path) in &cache_records {
+        cache.record(*generator, source, topic, title, path);
+    }
 
-            generator
-                .generate_to_file(&schema, &output_path)
-                .context("Failed to generate Jupyter notebook")?;
+    // Remove outputs of schemas that were deleted or renamed since the last build
+    let generator_names: Vec<&str> = generators.iter().map(|g| g.name()).collect();
+    let removed = cache
+        .remove_stale(&generator_names)
+        .context("Failed to remove stale outputs")?;
+    for path in &removed {
+        progress!("   🗑️  Removed stale {}", path.display());
+    }
 
-            println!("   ✅ {}", output_path.display());
-            generated_count += 1;
+    // Save the Colab manifest and READMEs for every notebook in the output directory
+    let written = if generator_names.contains(&"colab") {
+        rewrite_colab_manifest(&cache, &colab_config, &output_dir)
+            .context("Failed to save Colab manifest")?
+    } else {
+        Vec::new()
+    };
+    if !written.is_empty() {
+        let colab_dir = output_dir.join("colab");
+        progress!("📋 Generating Colab manifest and READMEs...");
+        for path in &written {
+            progress!("   ✅ {}", path.display());
         }
+        progress!();
+        progress!(
+            "🔗 Colab notebooks available locally at: {}",
+            colab_dir.display()
+        );
+        progress!("   (Also hosted on GitHub for direct Colab access)");
+        progress!();
+    }
 
-        // Run mdBook generator
-        if run_mdbook {
-            println!("📚 Generating mdBook markdown...");
-            let generator = MdBookGenerator::new()?;
-            let filename = generator.get_output_filename(&schema);
-
-            // Organize by category
-            let category_dir = mdbook_dir.join(category);
-            std::fs::create_dir_all(&category_dir)?;
-            let output_path = category_dir.join(&filename);
-
-            generator
-                .generate_to_file(&schema, &output_path)
-                .context("Failed to generate mdBook markdown")?;
-
-            println!("   ✅ {}", output_path.display());
-            generated_count += 1;
+    // Write SUMMARY.md, book.toml and index pages so `mdbook build` works on the output
+    let written = if generator_names.contains(&"mdbook") {
+        rewrite_book(&cache, &output_dir).context("Failed to write mdBook project files")?
+    } else {
+        Vec::new()
+    };
+    if !written.is_empty() {
+        let book_dir = output_dir.join("mdbook");
+        progress!("📚 Generating mdBook SUMMARY.md and book.toml...");
+        for path in &written {
+            progress!("   ✅ {}", path.display());
         }
+        progress!();
+        progress!(
+            "📖 Build the book with: mdbook build {}",
+            book_dir.display()
+        );
+        progress!();
+    }
 
-        // Run LLM-RAG generator
-        if run_llm_rag {
-            println!("🤖 Generating LLM-RAG markdown...");
-            let generator = LlmRagGenerator::from_schema(&schema);
-            let filename = format!("{}.rag.md", schema.topic.replace('.', "-"));
+    cache.save().context("Failed to save build cache")?;
 
-            // Organize by category
-            let category_dir = llm_rag_dir.join(category);
-            std::fs::create_dir_all(&category_dir)?;
-            let output_path = category_dir.join(&filename);
+    progress!(
+        "🎉 Successfully generated {} output file(s) from {} schema(s)",
+        total_generated,
+        schemas.len()
+    );
+    if total_skipped > 0 || !removed.is_empty() {
+        progress!(
+            "   ⏭️  {} unchanged output(s) skipped, {} stale output(s) removed (use --force to rebuild all)",
+            total_skipped,
+            removed.len()
+        );
+    }
+    progress!("   📁 Output directory: {}", output_dir.display());
 
-            generator
-                .generate_to_file(&schema, &output_path)
-                .context("Failed to generate LLM-RAG markdown")?;
+    Ok(())
+}
 
-            println!("   ✅ {}", output_path.display());
-            generated_count += 1;
-        }
+/// A single output file produced (or found up to date) for a schema
+struct GeneratedOutput<'a> {
+    generator: &'a dyn OutputGenerator,
+    path: PathBuf,
+    regenerated: bool,
+}
 
-        // Run Vue generator
-        if run_vue {
-            println!("🎨 Generating Vue SSR component...");
-            let generator = VueGenerator::new()?;
-            let filename = generator.get_output_filename(&schema);
+/// Result of running every selected generator on one schema
+struct SchemaBuild<'a> {
+    topic: String,
+    title: String,
+    source: SourceFingerprint,
+    outputs: Vec<GeneratedOutput<'a>>,
+}
 
-            // Organize by category
-            let category_dir = vue_dir.join(category);
-            std::fs::create_dir_all(&category_dir)?;
-            let output_path = category_dir.join(&filename);
+/// Load one schema and run every selected generator on it, skipping fresh outputs
+fn build_schema<'a>(
+    schema_path: &Path,
+    generators: &[&'a dyn OutputGenerator],
+    output_dir: &Path,
+    cache: &BuildCache,
+) -> Result<SchemaBuild<'a>> {
+    let source = SourceFingerprint::from_file(schema_path)
+        .with_context(|| format!("Failed to read schema {}", schema_path.display()))?;
+    let schema = Schema::load_from_file(schema_path)
+        .with_context(|| format!("Failed to load schema {}", schema_path.display()))?;
+
+    // Extract category from topic for organization
+    let category = schema.topic.split('.').next().unwrap_or("misc");
+
+    let mut outputs = Vec::with_capacity(generators.len());
+    for generator in generators {
+        let filename = generator.get_output_filename(&schema);
+
+        // Organize by category
+        let output_path = output_dir
+            .join(generator.name())
+            .join(category)
+            .join(&filename);
+
+        let regenerated = cache
+            .generate_to_file(*generator, &schema, &source, &output_path)
+            .with_context(|| format!("Failed to generate {} output", generator.name()))?;
+
+        outputs.push(GeneratedOutput {
+            generator: *generator,
+            path: output_path,
+            regenerated,
+        });
+    }
 
-            generator
-                .generate_to_file(&schema, &output_path)
-                .context("Failed to generate Vue component")?;
+    Ok(SchemaBuild {
+        topic: schema.topic,
+        title: schema.title,
+        source,
+        outputs,
+    })
+}
 
-            println!("   ✅ {}", output_path.display());
-            generated_count += 1;
+/// Print a schema error in the requested format
+///
+/// Errors that point into a schema file are rendered with a source excerpt and
+/// replaced by a short failure message, so the details are not printed twice.
+fn report_schema_error(
+    err: anyhow::Error,
+    schema_path: &Path,
+    message_format: MessageFormat,
+) -> anyhow::Error {
+    let Some(kb_error) = err.downcast_ref::<KbError>() else {
+        return err;
+    };
+
+    match message_format {
+        MessageFormat::Json => println!("{}", kb_error.to_json(Severity::Error)),
+        MessageFormat::Human if kb_error.location().is_some() => {
+            eprintln!("{}", kb_error.render(Severity::Error, None))
         }
+        MessageFormat::Human => return err,
+    }
+    anyhow::anyhow!("Failed to build {}", schema_path.display())
+}
 
-        // Run API docs generator
-        if run_api_docs {
-            println!("📡 Generating API documentation...");
-            let generator = ApiDocsGenerator::new()?;
-            let filename = generator.get_output_filename(&schema);
-
-            // Organize by category
-            let category_dir = api_docs_dir.join(category);
-            std::fs::create_dir_all(&category_dir)?;
-            let output_path = category_dir.join(&filename);
-
-            generator
-                .generate_to_file(&schema, &output_path)
-                .context("Failed to generate API documentation")?;
-
-            println!("   ✅ {}", output_path.display());
-            generated_count += 1;
+/// Write the Colab manifest.json and READMEs for every notebook in the cache
+///
+/// Like [`rewrite_book`], this covers notebooks built by earlier runs too.
+/// Returns the written paths.
+fn rewrite_colab_manifest(
+    cache: &BuildCache,
+    config: &ColabConfig,
+    output_dir: &Path,
+) -> std::io::Result<Vec<PathBuf>> {
+    let mut manifest = ColabManifest::new(config.clone());
+    for (key, entry) in cache.outputs_of("colab") {
+        if entry.topic.is_empty() {
+            continue;
         }
+        let filename = key.rsplit('/').next().unwrap_or(key);
+        manifest.add(&entry.topic, &entry.title, filename);
+    }
+    if manifest.total_notebooks == 0 {
+        return Ok(Vec::new());
+    }
 
-        // Run Google Colab generator (organized by category)
-        if run_colab {
-            println!("📊 Generating Google Colab notebook...");
-            let generator = ColabGenerator::with_config(colab_config.clone());
-            let filename = generator.get_output_filename(&schema);
-
-            // Create category directory inside colab
-            let category_dir = colab_dir.join(category);
-            std::fs::create_dir_all(&category_dir)?;
-
-            let output_path = category_dir.join(&filename);
-
-            generator
-                .generate_to_file(&schema, &output_path)
-                .context("Failed to generate Google Colab notebook")?;
-
-            println!("   ✅ {}", output_path.display());
-            generated_count += 1;
-
-            // Add to manifest
-            colab_manifest.add(&schema.topic, &schema.title, &filename);
+    let colab_dir = output_dir.join("colab");
+    let manifest_path = colab_dir.join("manifest.json");
+    manifest.save(&manifest_path)?;
+
+    let readme_path = colab_dir.join("README.md");
+    std::fs::write(&readme_path, manifest.generate_readme())?;
+    let mut written = vec![manifest_path, readme_path];
+
+    for category in manifest.sorted_categories() {
+        if let Some(category_readme) = manifest.generate_category_readme(category) {
+            let category_readme_path = colab_dir.join(category).join("README.md");
+            std::fs::write(&category_readme_path, category_readme)?;
+            written.push(category_readme_path);
         }
+    }
+    Ok(written)
+}
 
-        // Run LaTeX generator
-        if run_latex {
-            println!("📄 Generating LaTeX documentation...");
-            let generator = LatexGenerator::new()?;
-            let filename = generator.get_output_filename(&schema);
-
-            // Organize by category
-            let category_dir = latex_dir.join(category);
-            std::fs::create_dir_all(&category_dir)?;
-            let output_path = category_dir.join(&filename);
-
-            generator
-                .generate_to_file(&schema, &output_path)
-                .context("Failed to generate LaTeX documentation")?;
-
-            println!("   ✅ {}", output_path.display());
-            generated_count += 1;
+/// Write SUMMARY.md, book.toml and the index pages for every page in the cache
+///
+/// A build or watch rebuild may cover only some schemas, so the cache is the
+/// one place that knows every page of the book. Returns the written paths.
+fn rewrite_book(cache: &BuildCache, output_dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut book = BookManifest::new(BookConfig::default());
+    for (key, entry) in cache.outputs_of("mdbook") {
+        // Entries written before topics were recorded are picked up by the next full build
+        if entry.topic.is_empty() {
+            continue;
         }
+        let filename = key.rsplit('/').next().unwrap_or(key);
+        book.add(&entry.topic, &entry.title, filename);
+    }
+    if book.is_empty() {
+        return Ok(Vec::new());
+    }
 
-        // Run JSON schema data generator
-        if run_json {
-            println!("📋 Generating JSON schema data...");
-            let generator = JsonGenerator::new();
-            let filename = generator.get_output_filename(&schema);
-
-            // Organize by category
-            let category_dir = json_dir.join(category);
-            std::fs::create_dir_all(&category_dir)?;
-            let output_path = category_dir.join(&filename);
+    book.save(&output_dir.join("mdbook"))
+}
 
-            generator
-                .generate_to_file(&schema, &output_path)
-                .context("Failed to generate JSON schema data")?;
+/// Turn the `--jobs` flag into a worker count (0 means one per CPU core)
+fn resolve_jobs(jobs: usize) -> usize {
+    if jobs == 0 {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    } else {
+        jobs
+    }
+}
 
-            println!("   ✅ {}", output_path.display());
-            generated_count += 1;
+/// Run `work` over `items` on up to `jobs` threads, handing results to `report` in input order
+///
+/// Results are buffered until every earlier item has been reported, so `report`
+/// sees exactly the sequence a serial run would produce. If `report` returns an
+/// error, no new items are started and the error is returned once running work finishes.
+fn process_in_order<T, R, W, F>(items: &[T], jobs: usize, work: W, mut report: F) -> Result<()>
+where
+    T: Sync,
+    R: Send,
+    W: Fn(&T) -> R + Sync,
+    F: FnMut(&T, R) -> Result<()>,
+{
+    let workers = jobs.clamp(1, items.len().max(1));
+    if workers == 1 {
+        for item in items {
+            report(item, work(item))?;
         }
+        return Ok(());
+    }
 
-        total_generated += generated_count;
-        println!();
-    }
-
-    // Save Colab manifest and generate READMEs if we generated Colab notebooks
-    if run_colab && colab_manifest.total_notebooks > 0 {
-        println!("📋 Generating Colab manifest and READMEs...");
-
-        // Save manifest
-        let manifest_path = colab_dir.join("manifest.json");
-        colab_manifest
-            .save(&manifest_path)
-            .context("Failed to save Colab manifest")?;
-        println!("   ✅ {}", manifest_path.display());
-
-        // Generate main README
-        let readme_content = colab_manifest.generate_readme();
-        let readme_path = colab_dir.join("README.md");
-        std::fs::write(&readme_path, readme_content)?;
-        println!("   ✅ {}", readme_path.display());
-
-        // Generate category READMEs
-        for category in colab_manifest.sorted_categories() {
-            if let Some(category_readme) = colab_manifest.generate_category_readme(category) {
-                let category_readme_path = colab_dir.join(category).join("README.md");
-                std::fs::write(&category_readme_path, category_readme)?;
-                println!("   ✅ {}", category_readme_path.display());
+    let next_index = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+
+    std::thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+
+        for _ in 0..workers {
+            let sender = sender.clone();
+            let (next_index, stop, work) = (&next_index, &stop, &work);
+            scope.spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let index = next_index.fetch_add(1, Ordering::Relaxed);
+                    let Some(item) = items.get(index) else {
+                        break;
+                    };
+                    if sender.send((index, work(item))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending = BTreeMap::new();
+        let mut next_to_report = 0;
+        for (index, result) in receiver {
+            pending.insert(index, result);
+            while let Some(result) = pending.remove(&next_to_report) {
+                if let Err(e) = report(&items[next_to_report], result) {
+                    stop.store(true, Ordering::Relaxed);
+                    return Err(e);
+                }
+                next_to_report += 1;
             }
         }
 
-        println!();
-        println!("🔗 Colab notebooks available locally at: {}", colab_dir.display());
-        println!("   (Also hosted on GitHub for direct Colab access)");
-        println!();
-    }
-
-    println!(
-        "🎉 Successfully generated {} output file(s) from {} schema(s)",
-        total_generated,
-        schemas.len()
-    );
-    println!("   📁 Output directory: {}", output_dir.display());
-
-    Ok(())
+        Ok(())
+    })
 }
 
 /// Collect all schema files from path (file or directory, recursive)
@@ -479,7 +955,8 @@ fn collect_schemas(path: &PathBuf) -> Result<Vec<PathBuf>> {
         anyhow::bail!("No schema files found at {:?}", path);
     }
 
-    println!("📂 Found {} schema files\n", schemas.len());
+    // stderr, so commands run with --message-format json keep stdout machine-readable
+    eprintln!("📂 Found {} schema files\n", schemas.len());
     Ok(schemas)
 }
 
@@ -488,6 +965,10 @@ fn collect_schemas_recursive(dir: &PathBuf, schemas: &mut Vec<PathBuf>) -> Resul
     for entry in std::fs::read_dir(dir)? {
         let entry = entry?;
         let path = entry.path();
+        if is_fragment(&path) {
+            // Shared `$include` fragments, not schemas
+            continue;
+        }
         if path.is_file() {
             if let Some(ext) = path.extension() {
                 if ext == "yaml" || ext == "yml" {
@@ -501,50 +982,77 @@ fn collect_schemas_recursive(dir: &PathBuf, schemas: &mut Vec<PathBuf>) -> Resul
     Ok(())
 }
 
-fn validate_command(schema_path: PathBuf) -> Result<()> {
-    println!("🔍 Validating schema: {}\n", schema_path.display());
-
-    let schema = Schema::load_from_file(&schema_path).context("Failed to load schema")?;
-
-    println!("✅ Schema is valid!");
-    println!("\n📋 Schema Summary:");
-    println!("   Topic: {}", schema.topic);
-    println!("   Title: {}", schema.title);
-    println!("   Examples: {}", schema.examples.len());
+fn graph_command(schema_path: PathBuf, output_dir: PathBuf, format: String) -> Result<()> {
+    println!("🕸️  Building topic graph...\n");
+
+    let formats: Vec<GraphFormat> = if format.trim() == "all" {
+        GraphFormat::ALL.to_vec()
+    } else {
+        format
+            .split(',')
+            .map(|name| {
+                GraphFormat::from_name(name.trim()).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Unknown graph format '{}' (expected json, dot, mermaid or all)",
+                        name.trim()
+                    )
+                })
+            })
+            .collect::<Result<_>>()?
+    };
+
+    let schemas = collect_schemas(&schema_path)?
+        .iter()
+        .map(|path| {
+            Schema::load_from_file(path)
+                .with_context(|| format!("Failed to load schema {}", path.display()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let graph = TopicGraph::from_schemas(&schemas);
+
+    std::fs::create_dir_all(&output_dir)
+        .with_context(|| format!("Failed to create {}", output_dir.display()))?;
+    for format in formats {
+        let path = output_dir.join(format!("topic-graph.{}", format.file_extension()));
+        std::fs::write(&path, graph.render(format))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        println!("   ✅ {}", path.display());
+    }
 
-    if let Some(article) = &schema.article {
-        use kb_core::schema::Article;
-        match article {
-            Article::Simple(_) => {
-                println!("   Article: Simple content");
-            }
-            Article::Structured(structured) => {
-                println!("   Article sections: {}", structured.sections.len());
-                println!("   Sidebars: {}", structured.sidebars.len());
-                if let Some(conclusion) = &structured.conclusion {
-                    println!("   Exercises: {}", conclusion.exercises.len());
-                }
-            }
+    let orphans = graph.orphans();
+    println!(
+        "\n🎉 Topic graph: {} topic(s), {} edge(s), {} category(ies)",
+        graph.len(),
+        graph.edges().count(),
+        graph.clusters().len()
+    );
+    if !orphans.is_empty() {
+        println!(
+            "\n🏝️  {} orphan topic(s) (no related_topics link points to them):",
+            orphans.len()
+        );
+        for orphan in orphans {
+            println!("   - {}", orphan);
         }
     }
-
-    if !schema.related_topics.is_empty() {
-        println!("   Related topics: {}", schema.related_topics.len());
+    let dangling = graph.dangling().count();
+    if dangling > 0 {
+        println!(
+            "\n⚠️  {} related_topics reference(s) point to missing topics (see `kb validate`)",
+            dangling
+        );
     }
 
     Ok(())
 }
 
-fn list_command() {
+fn list_command() -> Result<()> {
+    let registry = build_registry(&ColabConfig::default(), None)?;
+
     println!("📦 Available Generators:\n");
-    println!("   jupyter    - Interactive Jupyter notebooks (.ipynb)");
-    println!("   mdbook     - mdBook markdown documentation (.md)");
-    println!("   llm-rag    - LLM-optimized RAG markdown (.rag.md)");
-    println!("   vue        - Vue SSR site components (.vue)");
-    println!("   api-docs   - OpenAPI 3.0 specifications (.openapi.json)");
-    println!("   colab      - Google Colab notebooks (.colab.ipynb)");
-    println!("   latex      - LaTeX documentation (.tex)");
-    println!("   json       - Schema data for Vue site (.json)");
+    for generator in registry.all() {
+        println!("   {:<12} - {}", generator.name(), generator.description());
+    }
     println!("\nUse 'all' to run all available generators.");
     println!("\n📓 Colab Notebooks:\n");
     println!("   Colab notebooks are organized by category and hosted on GitHub.");
@@ -557,6 +1065,14 @@ fn list_command() {
     println!("\n🔧 Colab Commands:\n");
     println!("   kb colab config   Show current Colab/GitHub configuration");
     println!("   kb colab info     Show info about generated notebooks");
+    println!("\n🕸️  Topic Graph:\n");
+    println!("   kb graph <SCHEMA>             Export topic graph as JSON, DOT and Mermaid");
+    println!("\n🦀 Rust Doctests:\n");
+    println!("   kb doctest <SCHEMA> --test    Compile and run every Rust example as a test");
+    println!("\n🧳 Schema Versions:\n");
+    println!("   kb migrate <SCHEMA> --write   Upgrade schemas to the current schema_version");
+    println!("\n🧩 Editor Support:\n");
+    println!("   kb schema export -o docs/kb-schema.json  Export the JSON Schema for YAML language servers");
     println!("\n🗺️  Sitemap Commands:\n");
     println!("   kb sitemap generate <SCHEMA>  Generate sitemap.xml from schemas");
     println!("   kb sitemap indexnow           Submit URLs via IndexNow (Bing, Yandex)");
@@ -565,6 +1081,25 @@ fn list_command() {
     println!("\n   Full workflow:");
     println!("     kb sitemap generate schemas/ --robots-txt");
     println!("     kb sitemap indexnow --from-sitemap sitemap.xml -a YOUR_KEY");
+
+    Ok(())
+}
+
+fn handle_schema_command(cmd: SchemaCommands) -> Result<()> {
+    match cmd {
+        SchemaCommands::Export { output } => {
+            let json = serde_json::to_string_pretty(&kb_core::schema::json_schema())? + "\n";
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, json)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    eprintln!("✅ JSON Schema written to {}", path.display());
+                }
+                None => print!("{}", json),
+            }
+        }
+    }
+    Ok(())
 }
 
 fn handle_colab_command(cmd: ColabCommands) -> Result<()> {
@@ -911,5 +1446,51 @@ fn extract_urls_from_sitemap(content: &str) -> Vec<String> {
 
 #[cfg(test)]
 mod tests {
-    // CLI tests would go here
+    use super::*;
+
+    #[test]
+    fn test_process_in_order_reports_in_input_order() {
+        let items: Vec<usize> = (0..64).collect();
+        let mut reported = Vec::new();
+
+        process_in_order(
+            &items,
+            8,
+            |n| {
+                // Make later items finish first to exercise reordering
+                std::thread::sleep(std::time::Duration::from_micros((64 - *n as u64) * 20));
+                n * 2
+            },
+            |item, doubled| {
+                assert_eq!(*item * 2, doubled);
+                reported.push(*item);
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(reported, items);
+    }
+
+    #[test]
+    fn test_process_in_order_stops_on_error() {
+        let items: Vec<usize> = (0..16).collect();
+        let mut reported = Vec::new();
+
+        let result = process_in_order(
+            &items,
+            4,
+            |n| *n,
+            |item, _| {
+                if *item == 3 {
+                    anyhow::bail!("boom");
+                }
+                reported.push(*item);
+                Ok(())
+            },
+        );
+
+        assert!(result.is_err());
+        assert_eq!(reported, vec![0, 1, 2]);
+    }
 }
@@ -0,0 +1,57 @@
+//! `kb migrate` - upgrade schema files to the current schema version
+//!
+//! Without `--write` the pending migrations are only listed. Files are edited in
+//! place line by line, so comments and block-scalar formatting survive.
+
+use crate::collect_schemas;
+use anyhow::{Context, Result};
+use kb_core::schema::Migrator;
+use std::path::PathBuf;
+
+pub(crate) fn migrate_command(schema_path: PathBuf, write: bool) -> Result<()> {
+    let migrator = Migrator::new();
+    println!(
+        "🧳 Migrating schemas to schema_version {}...\n",
+        migrator.latest_version()
+    );
+
+    let mut pending = 0;
+    for path in collect_schemas(&schema_path)? {
+        let source = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let migrated = migrator
+            .migrate_source(&source, &path.to_string_lossy())
+            .with_context(|| format!("Failed to migrate {}", path.display()))?;
+        let Some(migrated) = migrated else {
+            continue;
+        };
+
+        pending += 1;
+        println!(
+            "   {} {} ({} → {})",
+            if write { "✅" } else { "📝" },
+            path.display(),
+            migrated.from_version,
+            migrator.latest_version()
+        );
+        for description in &migrated.applied {
+            println!("      - {}", description);
+        }
+        if write {
+            std::fs::write(&path, &migrated.source)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+    }
+
+    if pending == 0 {
+        println!("🎉 All schemas are up to date");
+    } else if write {
+        println!("\n🎉 Migrated {} schema(s)", pending);
+    } else {
+        println!(
+            "\n{} schema(s) need migrating; rerun with --write to update them",
+            pending
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,150 @@
+//! `kb test-examples` - run example snippets and check `expected_output`
+//!
+//! Each snippet is run with a locally installed interpreter or toolchain (see
+//! `kb_core::runner`). Mismatches and failures are reported per schema and
+//! language; missing interpreters are reported once and their examples skipped.
+
+use crate::{collect_schemas, MessageFormat};
+use anyhow::{Context, Result};
+use kb_core::runner::{ExampleRunner, Language, Outcome, RunnerConfig};
+use kb_core::schema::SchemaFile;
+use kb_core::Severity;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+/// Per-language result counts
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Counts {
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+}
+
+pub(crate) fn test_examples_command(
+    schema_path: PathBuf,
+    languages: &str,
+    config: RunnerConfig,
+    message_format: MessageFormat,
+) -> Result<()> {
+    let human = message_format == MessageFormat::Human;
+    let languages = parse_languages(languages)?;
+    let paths = collect_schemas(&schema_path)?;
+
+    let runner = ExampleRunner::new(config);
+    let mut counts: BTreeMap<(String, Language), Counts> = BTreeMap::new();
+    let mut unavailable = BTreeSet::new();
+
+    for path in &paths {
+        let file = SchemaFile::parse(path)
+            .with_context(|| format!("Failed to load schema {}", path.display()))?;
+        let runs = runner
+            .run_schema(&file.schema, &languages)
+            .with_context(|| format!("Failed to run examples of {}", path.display()))?;
+
+        for run in runs {
+            let entry = counts.entry((file.path.clone(), run.language)).or_default();
+            match &run.outcome {
+                Outcome::Passed => entry.passed += 1,
+                Outcome::Skipped => entry.skipped += 1,
+                Outcome::Unavailable { program } => {
+                    entry.skipped += 1;
+                    unavailable.insert((run.language, program.clone()));
+                }
+                Outcome::Mismatch { .. } | Outcome::Failed { .. } => entry.failed += 1,
+            }
+
+            if let Some(error) = run.to_error(&file) {
+                match message_format {
+                    MessageFormat::Human => println!("{}", error.render(Severity::Error, None)),
+                    MessageFormat::Json => println!("{}", error.to_json(Severity::Error)),
+                }
+            }
+        }
+    }
+
+    let total = counts.values().fold(Counts::default(), |acc, c| Counts {
+        passed: acc.passed + c.passed,
+        failed: acc.failed + c.failed,
+        skipped: acc.skipped + c.skipped,
+    });
+
+    if human {
+        for (language, program) in &unavailable {
+            println!(
+                "⚠️  '{}' not found; {} examples were not run",
+                program, language
+            );
+        }
+        if !unavailable.is_empty() {
+            println!();
+        }
+
+        println!(
+            "{:<10} {:>6} {:>6} {:>7}  Schema",
+            "Language", "Passed", "Failed", "Skipped"
+        );
+        for ((path, language), c) in counts.iter().filter(|(_, c)| c.passed + c.failed > 0) {
+            let icon = if c.failed > 0 { "❌" } else { "✅" };
+            println!(
+                "{} {:<7} {:>6} {:>6} {:>7}  {}",
+                icon,
+                language.key(),
+                c.passed,
+                c.failed,
+                c.skipped,
+                path
+            );
+        }
+        println!(
+            "\n📊 {} example run(s): {} passed, {} failed, {} skipped",
+            total.passed + total.failed + total.skipped,
+            total.passed,
+            total.failed,
+            total.skipped
+        );
+        if total.passed + total.failed == 0 {
+            println!("   No example was run; add `expected_output` to examples or pass --run-all");
+        }
+    }
+
+    if total.failed > 0 {
+        anyhow::bail!("{} example run(s) failed", total.failed);
+    }
+    Ok(())
+}
+
+/// Parse `--languages` (comma-separated, or `all`)
+fn parse_languages(spec: &str) -> Result<Vec<Language>> {
+    if spec.trim() == "all" {
+        return Ok(Language::ALL.to_vec());
+    }
+
+    let mut languages = Vec::new();
+    for name in spec.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        let language = Language::from_name(name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown language '{}' (expected rust, python, nodejs or all)",
+                name
+            )
+        })?;
+        if !languages.contains(&language) {
+            languages.push(language);
+        }
+    }
+    Ok(languages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_languages() {
+        assert_eq!(parse_languages("all").unwrap(), Language::ALL.to_vec());
+        assert_eq!(
+            parse_languages("python, node,python").unwrap(),
+            vec![Language::Python, Language::NodeJs]
+        );
+        assert!(parse_languages("cobol").is_err());
+    }
+}
@@ -0,0 +1,549 @@
+//! `kb validate` - check schema files and report every problem
+//!
+//! Accepts a single schema or a directory. Directories are validated
+//! recursively; failures do not stop the run, references between schemas are
+//! checked across the whole directory, and a per-file summary table is printed
+//! at the end. Results can also be written as a JSON or JUnit XML report
+//! for CI dashboards.
+
+use crate::{collect_schemas_recursive, MessageFormat};
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use kb_core::inventory::ApiInventory;
+use kb_core::schema::{
+    check_code_refs, check_consistency, check_links, check_unknown_keys, diagnose_schema, Article,
+    SchemaFile, Strictness,
+};
+use kb_core::{Diagnostics, Severity};
+use std::path::{Path, PathBuf};
+
+/// File format of `kb validate --report`
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ReportFormat {
+    /// JSON document with per-file diagnostics and totals
+    Json,
+    /// JUnit XML, one test case per schema file
+    Junit,
+}
+
+/// How `kb validate` reports cross-language inconsistencies between snippets
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum CrossLanguage {
+    /// Do not compare snippets
+    Off,
+    /// Report inconsistencies as warnings
+    Warn,
+    /// Report inconsistencies as errors
+    Error,
+}
+
+impl CrossLanguage {
+    fn severity(self) -> Option<Severity> {
+        match self {
+            CrossLanguage::Off => None,
+            CrossLanguage::Warn => Some(Severity::Warning),
+            CrossLanguage::Error => Some(Severity::Error),
+        }
+    }
+}
+
+/// Options for `kb validate`
+pub(crate) struct ValidateOptions {
+    pub deny_warnings: bool,
+    /// Whether unknown YAML keys are warnings or errors
+    pub strictness: Strictness,
+    pub cross_language: CrossLanguage,
+    /// Known API symbols for checking `code_refs`
+    pub inventory: Option<ApiInventory>,
+    pub message_format: MessageFormat,
+    pub report: Option<PathBuf>,
+    pub report_format: ReportFormat,
+}
+
+/// Outcome of validating one schema file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    fn as_str(self) -> &'static str {
+        match self {
+            Status::Pass => "pass",
+            Status::Warn => "warn",
+            Status::Fail => "fail",
+        }
+    }
+
+    fn icon(self) -> &'static str {
+        match self {
+            Status::Pass => "✅",
+            Status::Warn => "⚠️",
+            Status::Fail => "❌",
+        }
+    }
+}
+
+/// Validation result for one schema file
+struct FileReport {
+    path: PathBuf,
+    source: Option<String>,
+    file: Option<SchemaFile>,
+    diagnostics: Diagnostics,
+    status: Status,
+}
+
+impl FileReport {
+    /// Validate a schema file; read and parse failures become error diagnostics
+    fn check(path: &Path, options: &ValidateOptions) -> Self {
+        let source = std::fs::read_to_string(path).ok();
+        let mut diagnostics = Diagnostics::new();
+
+        let file = match SchemaFile::parse(path) {
+            Ok(file) => {
+                diagnostics.extend(diagnose_schema(&file.schema, &file.path, &file.spans));
+                diagnostics.extend(check_unknown_keys(&file, options.strictness.severity()));
+                if let Some(severity) = options.cross_language.severity() {
+                    diagnostics.extend(check_consistency(&file, severity));
+                }
+                if let Some(inventory) = &options.inventory {
+                    diagnostics.extend(check_code_refs(
+                        &file.schema,
+                        &file.path,
+                        &file.spans,
+                        inventory,
+                    ));
+                }
+                Some(file)
+            }
+            Err(err) => {
+                diagnostics.error(err);
+                None
+            }
+        };
+
+        Self {
+            path: path.to_path_buf(),
+            source,
+            file,
+            diagnostics,
+            status: Status::Pass,
+        }
+    }
+
+    /// Derive pass/warn/fail once all diagnostics have been collected
+    fn update_status(&mut self, deny_warnings: bool) {
+        let warnings = self.diagnostics.warning_count();
+        self.status = if self.diagnostics.has_errors() || (deny_warnings && warnings > 0) {
+            Status::Fail
+        } else if warnings > 0 {
+            Status::Warn
+        } else {
+            Status::Pass
+        };
+    }
+
+    fn print_diagnostics(&self, message_format: MessageFormat) {
+        for diagnostic in self.diagnostics.iter() {
+            match message_format {
+                MessageFormat::Human => println!("{}", diagnostic.render(self.source.as_deref())),
+                MessageFormat::Json => println!("{}", diagnostic.to_json()),
+            }
+        }
+    }
+
+    /// All diagnostics of one severity, rendered
+    fn rendered(&self, severity: Severity) -> String {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .map(|d| d.render(self.source.as_deref()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Totals across all validated files
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Totals {
+    files: usize,
+    passed: usize,
+    warned: usize,
+    failed: usize,
+    errors: usize,
+    warnings: usize,
+}
+
+impl Totals {
+    fn of(reports: &[FileReport]) -> Self {
+        let mut totals = Totals {
+            files: reports.len(),
+            ..Totals::default()
+        };
+        for report in reports {
+            match report.status {
+                Status::Pass => totals.passed += 1,
+                Status::Warn => totals.warned += 1,
+                Status::Fail => totals.failed += 1,
+            }
+            totals.errors += report.diagnostics.error_count();
+            totals.warnings += report.diagnostics.warning_count();
+        }
+        totals
+    }
+}
+
+pub(crate) fn validate_command(schema_path: PathBuf, options: ValidateOptions) -> Result<()> {
+    let human = options.message_format == MessageFormat::Human;
+
+    let paths = if schema_path.is_dir() {
+        let mut paths = Vec::new();
+        collect_schemas_recursive(&schema_path, &mut paths)?;
+        paths.sort();
+        paths
+    } else {
+        vec![schema_path.clone()]
+    };
+    if paths.is_empty() {
+        anyhow::bail!("No schema files found at {:?}", schema_path);
+    }
+
+    if human {
+        if schema_path.is_dir() {
+            println!(
+                "🔍 Validating {} schema(s) in {}\n",
+                paths.len(),
+                schema_path.display()
+            );
+        } else {
+            println!("🔍 Validating schema: {}\n", schema_path.display());
+        }
+    }
+
+    let mut reports: Vec<FileReport> = paths
+        .iter()
+        .map(|path| FileReport::check(path, &options))
+        .collect();
+    if schema_path.is_dir() {
+        add_link_diagnostics(&mut reports);
+    }
+    for report in &mut reports {
+        report.update_status(options.deny_warnings);
+    }
+    for report in &reports {
+        report.print_diagnostics(options.message_format);
+    }
+
+    let totals = Totals::of(&reports);
+    if let Some(report_path) = &options.report {
+        let content = match options.report_format {
+            ReportFormat::Json => serde_json::to_string_pretty(&json_report(&reports, &totals))?,
+            ReportFormat::Junit => junit_report(&reports, &totals),
+        };
+        std::fs::write(report_path, content)
+            .with_context(|| format!("Failed to write report {}", report_path.display()))?;
+        if human {
+            println!("📝 Report written to {}\n", report_path.display());
+        }
+    }
+
+    if human {
+        if schema_path.is_dir() {
+            print_table(&reports, &totals);
+        } else if let [report] = reports.as_slice() {
+            print_summary(report);
+        }
+    }
+
+    if totals.failed > 0 {
+        let denied = if totals.errors == 0 {
+            " (warnings denied)"
+        } else {
+            ""
+        };
+        if reports.len() == 1 {
+            anyhow::bail!(
+                "Schema validation failed: {} error(s), {} warning(s){}",
+                totals.errors,
+                totals.warnings,
+                denied
+            );
+        }
+        anyhow::bail!(
+            "Schema validation failed for {} of {} schema(s): {} error(s), {} warning(s){}",
+            totals.failed,
+            totals.files,
+            totals.errors,
+            totals.warnings,
+            denied
+        );
+    }
+
+    Ok(())
+}
+
+/// Check references between all parsed schemas and attach results to their files
+fn add_link_diagnostics(reports: &mut [FileReport]) {
+    let files: Vec<SchemaFile> = reports.iter().filter_map(|r| r.file.clone()).collect();
+
+    for diagnostic in check_links(&files) {
+        let file = diagnostic
+            .error
+            .location()
+            .map(|(file, _)| file.to_string());
+        if let Some(report) = reports
+            .iter_mut()
+            .find(|r| r.file.as_ref().map(|f| &f.path) == file.as_ref())
+        {
+            report
+                .diagnostics
+                .push(diagnostic.severity, diagnostic.error);
+        }
+    }
+}
+
+/// Per-file pass/fail table followed by totals
+fn print_table(reports: &[FileReport], totals: &Totals) {
+    println!("{:<8} {:>6} {:>8}  Schema", "Status", "Errors", "Warnings");
+    for report in reports {
+        println!(
+            "{} {:<5} {:>6} {:>8}  {}",
+            report.status.icon(),
+            report.status.as_str(),
+            report.diagnostics.error_count(),
+            report.diagnostics.warning_count(),
+            report.path.display()
+        );
+    }
+
+    println!(
+        "\n📊 {} schema(s): {} passed, {} with warnings, {} failed ({} error(s), {} warning(s))",
+        totals.files, totals.passed, totals.warned, totals.failed, totals.errors, totals.warnings
+    );
+}
+
+/// Summary of a single valid schema
+fn print_summary(report: &FileReport) {
+    let Some(SchemaFile { schema, .. }) = &report.file else {
+        return;
+    };
+    if report.status == Status::Fail {
+        return;
+    }
+
+    let warnings = report.diagnostics.warning_count();
+    if warnings > 0 {
+        println!("✅ Schema is valid with {} warning(s)", warnings);
+    } else {
+        println!("✅ Schema is valid!");
+    }
+    println!("\n📋 Schema Summary:");
+    println!("   Topic: {}", schema.topic);
+    println!("   Title: {}", schema.title);
+    println!("   Examples: {}", schema.examples.len());
+
+    if let Some(article) = &schema.article {
+        match article {
+            Article::Simple(_) => {
+                println!("   Article: Simple content");
+            }
+            Article::Structured(structured) => {
+                println!("   Article sections: {}", structured.sections.len());
+                println!("   Sidebars: {}", structured.sidebars.len());
+                if let Some(conclusion) = &structured.conclusion {
+                    println!("   Exercises: {}", conclusion.exercises.len());
+                }
+            }
+        }
+    }
+
+    if !schema.related_topics.is_empty() {
+        println!("   Related topics: {}", schema.related_topics.len());
+    }
+}
+
+fn json_report(reports: &[FileReport], totals: &Totals) -> serde_json::Value {
+    let files: Vec<serde_json::Value> = reports
+        .iter()
+        .map(|report| {
+            serde_json::json!({
+                "path": report.path.to_string_lossy(),
+                "status": report.status.as_str(),
+                "errors": report.diagnostics.error_count(),
+                "warnings": report.diagnostics.warning_count(),
+                "diagnostics": report.diagnostics.iter().map(|d| d.to_json()).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "files": files,
+        "totals": {
+            "files": totals.files,
+            "passed": totals.passed,
+            "warned": totals.warned,
+            "failed": totals.failed,
+            "errors": totals.errors,
+            "warnings": totals.warnings,
+        },
+    })
+}
+
+/// JUnit XML with one test case per schema file
+///
+/// Failing files get a `<failure>` with their rendered errors; warnings go to
+/// `<system-out>` so they show up without failing the test case.
+fn junit_report(reports: &[FileReport], totals: &Totals) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"kb validate\" tests=\"{}\" failures=\"{}\">\n",
+        totals.files, totals.failed
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"kb validate\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\">\n",
+        totals.files, totals.failed
+    ));
+
+    for report in reports {
+        let path = report.path.to_string_lossy();
+        let classname = report
+            .path
+            .parent()
+            .map(|parent| parent.to_string_lossy().replace(['/', '\\'], "."))
+            .unwrap_or_default();
+        xml.push_str(&format!(
+            "    <testcase classname=\"{}\" name=\"{}\">\n",
+            escape_xml(&classname),
+            escape_xml(&path)
+        ));
+
+        if report.status == Status::Fail {
+            let errors = report.diagnostics.error_count();
+            let warnings = report.diagnostics.warning_count();
+            let details = if errors > 0 {
+                report.rendered(Severity::Error)
+            } else {
+                report.rendered(Severity::Warning)
+            };
+            xml.push_str(&format!(
+                "      <failure message=\"{} error(s), {} warning(s)\" type=\"validation\">{}</failure>\n",
+                errors,
+                warnings,
+                escape_xml(&details)
+            ));
+        }
+        if report.diagnostics.warning_count() > 0 {
+            xml.push_str(&format!(
+                "      <system-out>{}</system-out>\n",
+                escape_xml(&report.rendered(Severity::Warning))
+            ));
+        }
+        xml.push_str("    </testcase>\n");
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+/// Escape XML special characters
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kb_core::schema::Span;
+    use kb_core::KbError;
+
+    fn report(path: &str, errors: usize, warnings: usize) -> FileReport {
+        let mut diagnostics = Diagnostics::new();
+        for _ in 0..errors {
+            diagnostics.error(KbError::validation(
+                path,
+                Span::new(2, 1),
+                "Bad <value>",
+                "Fix it",
+            ));
+        }
+        for _ in 0..warnings {
+            diagnostics.warning(KbError::validation(
+                path,
+                Span::new(3, 1),
+                "Odd & unusual",
+                "Check it",
+            ));
+        }
+        let status = match (errors, warnings) {
+            (0, 0) => Status::Pass,
+            (0, _) => Status::Warn,
+            _ => Status::Fail,
+        };
+
+        FileReport {
+            path: PathBuf::from(path),
+            source: Some("topic: x\ntitle: y\ndescription: z\n".to_string()),
+            file: None,
+            diagnostics,
+            status,
+        }
+    }
+
+    #[test]
+    fn test_totals() {
+        let reports = vec![
+            report("schemas/a.yaml", 0, 0),
+            report("schemas/b.yaml", 2, 1),
+            report("schemas/c.yaml", 0, 3),
+        ];
+
+        assert_eq!(
+            Totals::of(&reports),
+            Totals {
+                files: 3,
+                passed: 1,
+                warned: 1,
+                failed: 1,
+                errors: 2,
+                warnings: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn test_json_report() {
+        let reports = vec![
+            report("schemas/a.yaml", 0, 0),
+            report("schemas/b.yaml", 1, 0),
+        ];
+        let json = json_report(&reports, &Totals::of(&reports));
+
+        assert_eq!(json["totals"]["failed"], 1);
+        assert_eq!(json["files"][0]["status"], "pass");
+        assert_eq!(json["files"][1]["status"], "fail");
+        assert_eq!(json["files"][1]["diagnostics"][0]["line"], 2);
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let reports = vec![
+            report("schemas/calculus/a.yaml", 0, 1),
+            report("schemas/calculus/b.yaml", 1, 0),
+        ];
+        let xml = junit_report(&reports, &Totals::of(&reports));
+
+        assert!(xml.contains("<testsuites name=\"kb validate\" tests=\"2\" failures=\"1\">"));
+        assert!(xml.contains(
+            "<testcase classname=\"schemas.calculus\" name=\"schemas/calculus/a.yaml\">"
+        ));
+        assert_eq!(xml.matches("<failure ").count(), 1);
+        assert!(xml.contains("error: Bad &lt;value&gt;"));
+        assert!(xml.contains("<system-out>warning: Odd &amp; unusual"));
+    }
+}
@@ -0,0 +1,280 @@
+//! `kb watch` - rebuild schemas as they are edited
+//!
+//! Watches a schema file or directory and re-validates and regenerates only the
+//! schemas that changed. Editing a shared `$include` fragment inside the watched
+//! directory rebuilds the schemas that include it. The mdBook SUMMARY.md and
+//! index pages and the Colab manifest are rewritten after every rebuild. Errors
+//! are printed inline and the watcher keeps running.
+
+use crate::{
+    build_registry, build_schema, collect_schemas_recursive, rewrite_book, rewrite_colab_manifest,
+};
+use anyhow::{Context, Result};
+use kb_colab::ColabConfig;
+use kb_core::generator::cache::BuildCache;
+use kb_core::generator::OutputGenerator;
+use kb_core::parser::{included_files, is_fragment};
+use kb_core::{KbError, Severity};
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+
+pub(crate) fn watch_command(
+    schema_path: PathBuf,
+    output_dir: PathBuf,
+    generators_str: String,
+    debounce_ms: u64,
+) -> Result<()> {
+    let registry = build_registry(&ColabConfig::default(), None)?;
+    let generators = registry.select(&generators_str)?;
+
+    if !schema_path.exists() {
+        anyhow::bail!("No schema files found at {:?}", schema_path);
+    }
+
+    // Editors often save by replacing the file, so a single file is watched via its directory
+    let (watch_root, only_file) = if schema_path.is_file() {
+        let file = schema_path
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve {}", schema_path.display()))?;
+        let parent = file.parent().map(Path::to_path_buf).unwrap_or_default();
+        (parent, Some(file))
+    } else {
+        (schema_path.clone(), None)
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(sender).context("Failed to start file watcher")?;
+    watcher
+        .watch(&watch_root, RecursiveMode::Recursive)
+        .with_context(|| format!("Failed to watch {}", watch_root.display()))?;
+
+    let generator_names: Vec<&str> = generators.iter().map(|g| g.name()).collect();
+    println!(
+        "👀 Watching {} for schema changes...",
+        schema_path.display()
+    );
+    println!("   Generators: {}", generator_names.join(", "));
+    println!("   Output directory: {}", output_dir.display());
+    println!("   Press Ctrl+C to stop.\n");
+
+    let debounce = Duration::from_millis(debounce_ms);
+    while let Ok(first) = receiver.recv() {
+        // Editors save in bursts; keep collecting until things have been quiet for `debounce`
+        let mut changed = BTreeSet::new();
+        collect_changes(first, &watch_root, only_file.as_deref(), &mut changed);
+        loop {
+            match receiver.recv_timeout(debounce) {
+                Ok(result) => {
+                    collect_changes(result, &watch_root, only_file.as_deref(), &mut changed)
+                }
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+        }
+        let changed = expand_fragments(changed, &watch_root, only_file.as_deref());
+
+        if !changed.is_empty() {
+            rebuild_changed(&changed, &generators, &output_dir);
+        }
+    }
+
+    Ok(())
+}
+
+/// Add schema files touched by a content-changing event to `changed`
+///
+/// Access events are ignored; otherwise reading a schema during a rebuild would
+/// trigger another rebuild.
+fn collect_changes(
+    result: notify::Result<Event>,
+    watch_root: &Path,
+    only_file: Option<&Path>,
+    changed: &mut BTreeSet<PathBuf>,
+) {
+    let event = match result {
+        Ok(event) => event,
+        Err(err) => {
+            eprintln!("❌ Watch error: {}", err);
+            return;
+        }
+    };
+
+    let is_content_change = match event.kind {
+        EventKind::Create(_) | EventKind::Remove(_) => true,
+        EventKind::Modify(kind) => !matches!(kind, ModifyKind::Metadata(_)),
+        _ => false,
+    };
+    if !is_content_change {
+        return;
+    }
+
+    changed.extend(
+        event
+            .paths
+            .into_iter()
+            .filter(|path| is_schema_file(path))
+            .filter(|path| {
+                only_file.is_none_or(|file| file == path) || in_fragment(path, watch_root)
+            }),
+    );
+}
+
+/// Replace changed `$include` fragments with the schemas that include them
+fn expand_fragments(
+    changed: BTreeSet<PathBuf>,
+    watch_root: &Path,
+    only_file: Option<&Path>,
+) -> BTreeSet<PathBuf> {
+    let (fragments, mut schemas): (BTreeSet<PathBuf>, BTreeSet<PathBuf>) = changed
+        .into_iter()
+        .partition(|path| in_fragment(path, watch_root));
+    if fragments.is_empty() {
+        return schemas;
+    }
+
+    let candidates = match only_file {
+        Some(file) => vec![file.to_path_buf()],
+        None => {
+            let mut paths = Vec::new();
+            if let Err(err) = collect_schemas_recursive(&watch_root.to_path_buf(), &mut paths) {
+                eprintln!("❌ Failed to list schemas: {}", err);
+            }
+            paths
+        }
+    };
+    let fragments: Vec<PathBuf> = fragments
+        .iter()
+        .map(|path| path.canonicalize().unwrap_or_else(|_| path.clone()))
+        .collect();
+
+    for schema in candidates {
+        // A schema whose includes no longer resolve is rebuilt so the error is shown
+        let includes_fragment = included_files(&schema).map_or(true, |files| {
+            files.iter().any(|file| fragments.contains(file))
+        });
+        if includes_fragment {
+            schemas.insert(schema);
+        }
+    }
+    schemas
+}
+
+/// Whether a path lies in a fragment file or directory below the watch root
+fn in_fragment(path: &Path, watch_root: &Path) -> bool {
+    match path.strip_prefix(watch_root) {
+        Ok(relative) => relative
+            .components()
+            .any(|component| is_fragment(Path::new(component.as_os_str()))),
+        Err(_) => is_fragment(path),
+    }
+}
+
+/// Rebuild changed schemas, printing diagnostics instead of stopping on errors
+fn rebuild_changed(
+    changed: &BTreeSet<PathBuf>,
+    generators: &[&dyn OutputGenerator],
+    output_dir: &Path,
+) {
+    let mut cache = BuildCache::load(output_dir);
+
+    for path in changed {
+        if !path.exists() {
+            println!("🗑️  {} was removed", path.display());
+            continue;
+        }
+
+        println!("🔄 {} changed", path.display());
+        match build_schema(path, generators, output_dir, &cache) {
+            Ok(built) => {
+                for output in &built.outputs {
+                    if output.regenerated {
+                        println!("   ✅ {}", output.path.display());
+                    } else {
+                        println!("   ⏭️  {} (unchanged)", output.path.display());
+                    }
+                    cache.record(
+                        output.generator,
+                        &built.source,
+                        &built.topic,
+                        &built.title,
+                        &output.path,
+                    );
+                }
+            }
+            Err(err) => {
+                match err
+                    .downcast_ref::<KbError>()
+                    .filter(|e| e.location().is_some())
+                {
+                    Some(kb_error) => eprintln!("{}", kb_error.render(Severity::Error, None)),
+                    None => eprintln!("   ❌ {:#}", err),
+                }
+            }
+        }
+    }
+
+    let generator_names: Vec<&str> = generators.iter().map(|g| g.name()).collect();
+    match cache.remove_stale(&generator_names) {
+        Ok(removed) => {
+            for path in removed {
+                println!("   🗑️  Removed stale {}", path.display());
+            }
+        }
+        Err(err) => eprintln!("   ❌ Failed to remove stale outputs: {}", err),
+    }
+
+    if generator_names.contains(&"mdbook") {
+        if let Err(err) = rewrite_book(&cache, output_dir) {
+            eprintln!("   ❌ Failed to write mdBook project files: {}", err);
+        }
+    }
+    if generator_names.contains(&"colab") {
+        if let Err(err) = rewrite_colab_manifest(&cache, &ColabConfig::default(), output_dir) {
+            eprintln!("   ❌ Failed to save Colab manifest: {}", err);
+        }
+    }
+
+    if let Err(err) = cache.save() {
+        eprintln!("   ❌ Failed to save build cache: {}", err);
+    }
+    println!();
+}
+
+fn is_schema_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == "yaml" || ext == "yml")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_schema_file() {
+        assert!(is_schema_file(Path::new(
+            "schemas/calculus/derivative.yaml"
+        )));
+        assert!(is_schema_file(Path::new("derivative.yml")));
+        assert!(!is_schema_file(Path::new("derivative.yaml.swp")));
+        assert!(!is_schema_file(Path::new("schemas/calculus")));
+    }
+
+    #[test]
+    fn test_in_fragment() {
+        let root = Path::new("schemas");
+        assert!(in_fragment(Path::new("schemas/_shared/install.yaml"), root));
+        assert!(in_fragment(
+            Path::new("schemas/calculus/_sidebars.yaml"),
+            root
+        ));
+        assert!(!in_fragment(
+            Path::new("schemas/calculus/derivative.yaml"),
+            root
+        ));
+    }
+}
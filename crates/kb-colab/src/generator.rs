@@ -5,26 +5,42 @@
 //! opened via Colab's native GitHub integration.
 
 use crate::manifest::ColabConfig;
+use kb_core::generator::execute::{NotebookExecutor, SKIP_EXECUTION_TAG};
 use kb_core::generator::OutputGenerator;
+use kb_core::schema::Example;
 use kb_core::{Result, Schema};
 use serde_json::{json, Value};
 
+/// Output format version; bump whenever the generated cells or Colab metadata change
+const FORMAT_VERSION: &str = "2";
+
+/// Format version of notebooks whose outputs were filled in by an executor
+const EXECUTED_FORMAT_VERSION: &str = "2+executed";
+
 /// Google Colab notebook generator
 pub struct ColabGenerator {
     config: ColabConfig,
+    executor: Option<NotebookExecutor>,
 }
 
 impl ColabGenerator {
     /// Create a new Colab generator with default config
     pub fn new() -> Result<Self> {
-        Ok(Self {
-            config: ColabConfig::default(),
-        })
+        Ok(Self::with_config(ColabConfig::default()))
     }
 
     /// Create a new Colab generator with custom config
     pub fn with_config(config: ColabConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            executor: None,
+        }
+    }
+
+    /// Execute each notebook after generating it, storing the cell outputs
+    pub fn with_executor(mut self, executor: NotebookExecutor) -> Self {
+        self.executor = Some(executor);
+        self
     }
 
     /// Get the config
@@ -56,6 +72,21 @@ impl ColabGenerator {
             ]
         }));
 
+        // Install cell, only run in Colab; `--execute` uses the local install
+        cells.push(json!({
+            "cell_type": "code",
+            "execution_count": null,
+            "metadata": {
+                "id": "install",
+                "tags": [SKIP_EXECUTION_TAG]
+            },
+            "outputs": [],
+            "source": [
+                "# Install MathHook (if not already installed)\n",
+                "!pip install -q mathhook\n"
+            ]
+        }));
+
         // Setup cell
         cells.push(json!({
             "cell_type": "code",
@@ -154,9 +185,6 @@ impl ColabGenerator {
 
     fn generate_setup_source(&self, schema: &Schema) -> Vec<String> {
         let mut source = vec![
-            "# Install MathHook (if not already installed)\n".to_string(),
-            "!pip install -q mathhook\n".to_string(),
-            "\n".to_string(),
             "# Import MathHook\n".to_string(),
             "from mathhook import symbol, expr\n".to_string(),
         ];
@@ -267,6 +295,43 @@ impl ColabGenerator {
     }
 }
 
+/// Run a generated notebook and store the outputs of its code cells
+///
+/// Example code cells are found by their `example-<idx>-code` ids.
+fn execute_notebook(
+    executor: &NotebookExecutor,
+    notebook: &mut Value,
+    schema: &Schema,
+) -> Result<()> {
+    let executed = executor.execute(notebook)?;
+
+    let cells = notebook["cells"]
+        .as_array_mut()
+        .expect("notebook cells are an array");
+    let example_cells: Vec<(usize, &Example)> = cells
+        .iter()
+        .enumerate()
+        .filter_map(|(cell, value)| {
+            let idx: usize = value["metadata"]["id"]
+                .as_str()?
+                .strip_prefix("example-")?
+                .strip_suffix("-code")?
+                .parse()
+                .ok()?;
+            Some((cell, schema.examples.get(idx)?))
+        })
+        .collect();
+    executor.check(&executed, &example_cells)?;
+
+    for (cell, result) in cells.iter_mut().zip(executed) {
+        if cell["cell_type"] == "code" {
+            cell["outputs"] = Value::Array(result.outputs);
+            cell["execution_count"] = result.execution_count;
+        }
+    }
+    Ok(())
+}
+
 impl Default for ColabGenerator {
     fn default() -> Self {
         Self::new().expect("Failed to initialize Colab generator")
@@ -282,10 +347,21 @@ impl OutputGenerator for ColabGenerator {
         "colab.ipynb"
     }
 
+    fn description(&self) -> &str {
+        "Google Colab notebooks (.colab.ipynb)"
+    }
+
+    fn version(&self) -> &str {
+        match self.executor {
+            Some(_) => EXECUTED_FORMAT_VERSION,
+            None => FORMAT_VERSION,
+        }
+    }
+
     fn generate(&self, schema: &Schema) -> Result<String> {
         let cells = self.generate_cells(schema);
 
-        let notebook = json!({
+        let mut notebook = json!({
             "nbformat": 4,
             "nbformat_minor": 0,
             "metadata": {
@@ -307,6 +383,10 @@ impl OutputGenerator for ColabGenerator {
             "cells": cells
         });
 
+        if let Some(executor) = &self.executor {
+            execute_notebook(executor, &mut notebook, schema)?;
+        }
+
         Ok(serde_json::to_string_pretty(&notebook)?)
     }
 
@@ -349,6 +429,8 @@ impl OutputGenerator for ColabGenerator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(unix)]
+    use kb_core::generator::execute::fake_jupyter;
     use kb_core::schema::*;
 
     fn create_test_schema() -> Schema {
@@ -371,6 +453,7 @@ mod tests {
                     nodejs: "const f = x**2;".to_string(),
                 },
                 expected_output: Some("2*x".to_string()),
+                doctest: Default::default(),
             }],
             article: None,
             use_cases: vec![],
@@ -430,4 +513,55 @@ mod tests {
         let filename = generator.get_output_filename(&schema);
         assert_eq!(filename, "calculus-derivative.colab.ipynb");
     }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_execute_skips_install_cell() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut schema = create_test_schema();
+        schema.examples[0].expected_output = Some("ran 2".to_string());
+
+        let plain = ColabGenerator::new().unwrap().generate(&schema).unwrap();
+        let plain: Value = serde_json::from_str(&plain).unwrap();
+        let sent: Vec<Value> = plain["cells"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|cell| cell["metadata"]["id"] != "install")
+            .cloned()
+            .collect();
+
+        let generator = ColabGenerator::new()
+            .unwrap()
+            .with_executor(fake_jupyter(dir.path(), sent));
+        assert_eq!(generator.version(), EXECUTED_FORMAT_VERSION);
+        let output: Value = serde_json::from_str(&generator.generate(&schema).unwrap()).unwrap();
+
+        let input = std::fs::read_to_string(dir.path().join("input.ipynb")).unwrap();
+        assert!(!input.contains("pip install"));
+
+        let cell = |id: &str| {
+            output["cells"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|cell| cell["metadata"]["id"] == id)
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(
+            cell("install")["metadata"]["tags"],
+            json!([SKIP_EXECUTION_TAG])
+        );
+        assert_eq!(cell("install")["execution_count"], Value::Null);
+        assert_eq!(cell("install")["outputs"], json!([]));
+        assert_eq!(cell("setup")["execution_count"], 1);
+        assert_eq!(cell("setup")["outputs"][0]["text"], json!(["ran 1\n"]));
+        assert_eq!(cell("example-0-code")["execution_count"], 2);
+        assert_eq!(
+            cell("example-0-code")["outputs"][0]["text"],
+            json!(["ran 2\n"])
+        );
+        assert!(cell("header").get("outputs").is_none());
+    }
 }
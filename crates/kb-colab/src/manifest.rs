@@ -3,7 +3,7 @@
 //! Generates manifest files tracking all notebooks with their GitHub-based Colab URLs.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::path::Path;
 
 /// Configuration for GitHub-based Colab URLs
@@ -126,8 +126,8 @@ pub struct ColabManifest {
     pub config: ColabConfig,
     /// Total number of notebooks
     pub total_notebooks: usize,
-    /// Notebooks organized by category
-    pub categories: HashMap<String, Vec<NotebookEntry>>,
+    /// Notebooks organized by category (sorted, so the saved manifest is stable)
+    pub categories: BTreeMap<String, Vec<NotebookEntry>>,
 }
 
 impl ColabManifest {
@@ -136,7 +136,7 @@ impl ColabManifest {
         Self {
             config,
             total_notebooks: 0,
-            categories: HashMap::new(),
+            categories: BTreeMap::new(),
         }
     }
 
@@ -300,6 +300,34 @@ mod tests {
         assert_eq!(manifest.categories["algebra"].len(), 1);
     }
 
+    #[test]
+    fn test_manifest_categories_serialize_sorted() {
+        let config = ColabConfig::new("test", "repo", "main", "notebooks");
+        let mut manifest = ColabManifest::new(config);
+
+        manifest.add(
+            "ode.separable",
+            "Separable ODEs",
+            "ode-separable.colab.ipynb",
+        );
+        manifest.add(
+            "algebra.simplify",
+            "Simplification",
+            "algebra-simplify.colab.ipynb",
+        );
+        manifest.add(
+            "calculus.derivative",
+            "Derivatives",
+            "calculus-derivative.colab.ipynb",
+        );
+
+        let json = serde_json::to_string(&manifest).unwrap();
+        let algebra = json.find("\"algebra\"").unwrap();
+        let calculus = json.find("\"calculus\"").unwrap();
+        let ode = json.find("\"ode\"").unwrap();
+        assert!(algebra < calculus && calculus < ode);
+    }
+
     #[test]
     fn test_readme_generation() {
         let config = ColabConfig::new("test", "repo", "main", "notebooks");
@@ -1,3 +1,4 @@
+use crate::schema::Span;
 use std::fmt;
 use thiserror::Error;
 
@@ -9,6 +10,8 @@ pub type Result<T> = std::result::Result<T, KbError>;
 pub struct CrossLanguageInconsistencyError {
     pub file: String,
     pub example: String,
+    pub line: usize,
+    pub column: usize,
     pub message: String,
     pub rust_output: String,
     pub python_output: String,
@@ -19,14 +22,38 @@ impl fmt::Display for CrossLanguageInconsistencyError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Cross-language consistency error in '{}' example '{}':\n  {}\n\n  Rust output: {}\n  Python output: {}\n  JavaScript output: {}",
-            self.file, self.example, self.message, self.rust_output, self.python_output, self.js_output
+            "Cross-language consistency error in '{}' example '{}' at line {}:\n  {}\n\n  Rust output: {}\n  Python output: {}\n  JavaScript output: {}",
+            self.file, self.example, self.line, self.message, self.rust_output, self.python_output, self.js_output
         )
     }
 }
 
 impl std::error::Error for CrossLanguageInconsistencyError {}
 
+/// Example output mismatch error (boxed to reduce enum size)
+#[derive(Debug)]
+pub struct ExampleOutputMismatchError {
+    pub language: String,
+    pub example: String,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for ExampleOutputMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Output mismatch in {} example '{}' in '{}' at line {}:\n\n  Expected:\n{}\n\n  Actual:\n{}",
+            self.language, self.example, self.file, self.line, self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for ExampleOutputMismatchError {}
+
 /// Errors that can occur in the Knowledge Base engine
 #[derive(Error, Debug)]
 pub enum KbError {
@@ -34,6 +61,7 @@ pub enum KbError {
     ValidationError {
         file: String,
         line: usize,
+        column: usize,
         message: String,
         suggestion: String,
     },
@@ -43,6 +71,7 @@ pub enum KbError {
         field: String,
         file: String,
         line: usize,
+        column: usize,
         suggestion: String,
     },
 
@@ -51,6 +80,7 @@ pub enum KbError {
         reference: String,
         file: String,
         line: usize,
+        column: usize,
         suggestion: String,
     },
 
@@ -59,13 +89,53 @@ pub enum KbError {
         language: String,
         file: String,
         line: usize,
+        column: usize,
         error: String,
         code: String,
     },
 
+    #[error("{0}")]
+    ExampleOutputMismatch(#[from] Box<ExampleOutputMismatchError>),
+
+    #[error(
+        "Failed to run {language} example '{example}' in '{file}' at line {line}:\n  {message}"
+    )]
+    ExampleExecutionFailed {
+        language: String,
+        example: String,
+        file: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    #[error("Rust example '{example}' in '{file}' does not compile (line {line}):\n  {message}")]
+    ExampleCompileFailed {
+        example: String,
+        file: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
     #[error("{0}")]
     CrossLanguageInconsistency(#[from] Box<CrossLanguageInconsistencyError>),
 
+    #[error("Notebook cell {cell} ({label}) failed when executed:\n  {message}")]
+    NotebookCellFailed {
+        cell: usize,
+        label: String,
+        message: String,
+    },
+
+    #[error("Notebook cell {cell} for example '{example}' printed unexpected output:\n\n  Expected:\n{expected}\n\n  Actual:\n{actual}")]
+    NotebookOutputMismatch {
+        cell: usize,
+        example: String,
+        expected: String,
+        actual: String,
+    },
+
     #[error("Generated {format} output is invalid:\n  {error}\n\n  Output file: {file}")]
     InvalidOutput {
         format: String,
@@ -73,18 +143,39 @@ pub enum KbError {
         error: String,
     },
 
-    #[error("Output validation failed for {generator} generator:\n  {message}")]
-    OutputValidationError {
-        generator: String,
+    #[error("Failed to include '{included}' from '{file}' at line {line}:\n  {message}")]
+    IncludeError {
+        file: String,
+        line: usize,
+        column: usize,
+        included: String,
         message: String,
     },
 
+    #[error("Output validation failed for {generator} generator:\n  {message}")]
+    OutputValidationError { generator: String, message: String },
+
+    #[error("Unknown generator '{name}'\n\n  Available generators: {available}\n  Suggestion: {suggestion}")]
+    UnknownGenerator {
+        name: String,
+        available: String,
+        suggestion: String,
+    },
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
     #[error("YAML parsing error: {0}")]
     Yaml(#[from] serde_yaml::Error),
 
+    #[error("Invalid YAML in '{file}' at line {line}:\n  {message}")]
+    YamlSyntax {
+        file: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
@@ -100,32 +191,497 @@ pub enum KbError {
 
 impl KbError {
     /// Create a validation error with context
-    pub fn validation(file: impl Into<String>, line: usize, message: impl Into<String>, suggestion: impl Into<String>) -> Self {
+    pub fn validation(
+        file: impl Into<String>,
+        span: Span,
+        message: impl Into<String>,
+        suggestion: impl Into<String>,
+    ) -> Self {
         Self::ValidationError {
             file: file.into(),
-            line,
+            line: span.line,
+            column: span.column,
             message: message.into(),
             suggestion: suggestion.into(),
         }
     }
 
     /// Create a missing field error
-    pub fn missing_field(field: impl Into<String>, file: impl Into<String>, line: usize, suggestion: impl Into<String>) -> Self {
+    pub fn missing_field(
+        field: impl Into<String>,
+        file: impl Into<String>,
+        span: Span,
+        suggestion: impl Into<String>,
+    ) -> Self {
         Self::MissingField {
             field: field.into(),
             file: file.into(),
-            line,
+            line: span.line,
+            column: span.column,
             suggestion: suggestion.into(),
         }
     }
 
     /// Create an invalid code reference error
-    pub fn invalid_code_ref(reference: impl Into<String>, file: impl Into<String>, line: usize, suggestion: impl Into<String>) -> Self {
+    pub fn invalid_code_ref(
+        reference: impl Into<String>,
+        file: impl Into<String>,
+        span: Span,
+        suggestion: impl Into<String>,
+    ) -> Self {
         Self::InvalidCodeRef {
             reference: reference.into(),
             file: file.into(),
-            line,
+            line: span.line,
+            column: span.column,
             suggestion: suggestion.into(),
         }
     }
+
+    /// Create an error for an `$include` in `file` that could not be resolved
+    pub fn include(
+        file: impl Into<String>,
+        span: Span,
+        included: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self::IncludeError {
+            file: file.into(),
+            line: span.line,
+            column: span.column,
+            included: included.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Attach the file a YAML error came from, keeping serde_yaml's position
+    ///
+    /// Errors serde_yaml reports without a position point at the start of the file.
+    pub fn yaml(file: impl Into<String>, error: &serde_yaml::Error) -> Self {
+        let span = error.location().map_or_else(Span::default, |location| {
+            Span::new(location.line(), location.column())
+        });
+        Self::YamlSyntax {
+            file: file.into(),
+            line: span.line,
+            column: span.column,
+            message: error.to_string(),
+        }
+    }
+
+    /// Schema file and position the error points at, if any
+    pub fn location(&self) -> Option<(&str, Span)> {
+        match self {
+            Self::ValidationError {
+                file, line, column, ..
+            }
+            | Self::MissingField {
+                file, line, column, ..
+            }
+            | Self::InvalidCodeRef {
+                file, line, column, ..
+            }
+            | Self::CodeSyntaxError {
+                file, line, column, ..
+            }
+            | Self::ExampleExecutionFailed {
+                file, line, column, ..
+            }
+            | Self::ExampleCompileFailed {
+                file, line, column, ..
+            }
+            | Self::IncludeError {
+                file, line, column, ..
+            }
+            | Self::YamlSyntax {
+                file, line, column, ..
+            } => Some((file, Span::new(*line, *column))),
+            Self::ExampleOutputMismatch(mismatch) => {
+                Some((&mismatch.file, Span::new(mismatch.line, mismatch.column)))
+            }
+            Self::CrossLanguageInconsistency(error) => {
+                Some((&error.file, Span::new(error.line, error.column)))
+            }
+            _ => None,
+        }
+    }
+
+    /// One-line description of the error, without location or suggestion
+    pub fn summary(&self) -> String {
+        match self {
+            Self::ValidationError { message, .. } => message.clone(),
+            Self::MissingField { field, .. } => format!("Missing required field '{}'", field),
+            Self::InvalidCodeRef { reference, .. } => {
+                format!(
+                    "Invalid code reference '{}': function not found in mathhook codebase",
+                    reference
+                )
+            }
+            Self::CodeSyntaxError {
+                language, error, ..
+            } => {
+                format!("Syntax error in {} code example: {}", language, error)
+            }
+            Self::ExampleOutputMismatch(mismatch) => {
+                format!(
+                    "{} example '{}' printed unexpected output",
+                    mismatch.language, mismatch.example
+                )
+            }
+            Self::ExampleExecutionFailed {
+                language,
+                example,
+                message,
+                ..
+            } => {
+                format!(
+                    "Failed to run {} example '{}': {}",
+                    language, example, message
+                )
+            }
+            Self::ExampleCompileFailed {
+                example, message, ..
+            } => {
+                format!("Rust example '{}' does not compile: {}", example, message)
+            }
+            Self::CrossLanguageInconsistency(error) => {
+                format!(
+                    "Example '{}' is inconsistent across languages: {}",
+                    error.example, error.message
+                )
+            }
+            Self::IncludeError {
+                included, message, ..
+            } => format!("Failed to include '{}': {}", included, message),
+            Self::YamlSyntax { message, .. } => format!("Invalid YAML: {}", message),
+            Self::NotebookCellFailed {
+                cell,
+                label,
+                message,
+            } => {
+                format!("Notebook cell {} ({}) failed: {}", cell, label, message)
+            }
+            Self::NotebookOutputMismatch { example, .. } => {
+                format!(
+                    "Notebook output of example '{}' differs from expected_output",
+                    example
+                )
+            }
+            other => other.to_string(),
+        }
+    }
+
+    /// Suggested fix, if the error carries one
+    pub fn help(&self) -> Option<String> {
+        match self {
+            Self::ValidationError { suggestion, .. } | Self::MissingField { suggestion, .. } => {
+                Some(suggestion.clone())
+            }
+            Self::InvalidCodeRef { suggestion, .. } => {
+                Some(format!("Did you mean '{}'?", suggestion))
+            }
+            Self::UnknownGenerator { suggestion, .. } => Some(suggestion.clone()),
+            Self::ExampleOutputMismatch(mismatch) => Some(format!(
+                "expected output:\n{}\n\nactual output:\n{}",
+                indent(&mismatch.expected),
+                indent(&mismatch.actual)
+            )),
+            Self::NotebookOutputMismatch {
+                expected, actual, ..
+            } => Some(format!(
+                "expected output:\n{}\n\nactual output:\n{}",
+                indent(expected),
+                indent(actual)
+            )),
+            Self::CrossLanguageInconsistency(error) => Some(format!(
+                "Rust: {}; Python: {}; JavaScript: {}",
+                error.rust_output, error.python_output, error.js_output
+            )),
+            _ => None,
+        }
+    }
+
+    /// Render the error compiler-style: location, source excerpt, caret and help
+    ///
+    /// `source` is the content of the schema file; when `None` the file is read
+    /// from disk. Errors without a location (or whose file cannot be read) are
+    /// rendered without an excerpt.
+    pub fn render(&self, severity: Severity, source: Option<&str>) -> String {
+        let mut out = format!("{}: {}\n", severity, self.summary());
+
+        if let Some((file, span)) = self.location() {
+            let loaded;
+            let source = match source {
+                Some(source) => Some(source),
+                None => {
+                    loaded = std::fs::read_to_string(file).ok();
+                    loaded.as_deref()
+                }
+            };
+            let excerpt = source.and_then(|source| source.lines().nth(span.line.saturating_sub(1)));
+
+            let gutter = " ".repeat(span.line.to_string().len());
+            out.push_str(&format!(
+                "{}--> {}:{}:{}\n",
+                gutter, file, span.line, span.column
+            ));
+            if let Some(text) = excerpt {
+                let caret_pad: String = text
+                    .chars()
+                    .take(span.column.saturating_sub(1))
+                    .map(|c| if c == '\t' { '\t' } else { ' ' })
+                    .collect();
+                out.push_str(&format!("{} |\n", gutter));
+                out.push_str(&format!("{} | {}\n", span.line, text));
+                out.push_str(&format!("{} | {}^\n", gutter, caret_pad));
+            }
+            if let Some(help) = self.help() {
+                out.push_str(&format!("{} |\n", gutter));
+                out.push_str(&format!("{} = help: {}\n", gutter, help));
+            }
+        } else if let Some(help) = self.help() {
+            out.push_str(&format!("  = help: {}\n", help));
+        }
+
+        out
+    }
+
+    /// Machine-readable form of the error for editor integrations
+    ///
+    /// `rendered` holds the same text as [`KbError::render`].
+    pub fn to_json(&self, severity: Severity) -> serde_json::Value {
+        let (file, span) = match self.location() {
+            Some((file, span)) => (Some(file), Some(span)),
+            None => (None, None),
+        };
+
+        serde_json::json!({
+            "severity": severity.to_string(),
+            "message": self.summary(),
+            "file": file,
+            "line": span.map(|s| s.line),
+            "column": span.map(|s| s.column),
+            "help": self.help(),
+            "rendered": self.render(severity, None),
+        })
+    }
+}
+
+/// Indent every line of a multi-line block for display under a help note
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|line| format!("    {}", line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Severity of a diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Worth fixing, but the schema is still usable
+    Warning,
+
+    /// The schema is invalid
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A single error or warning reported for a schema
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub error: KbError,
+}
+
+impl Diagnostic {
+    /// Render compiler-style (see [`KbError::render`])
+    pub fn render(&self, source: Option<&str>) -> String {
+        self.error.render(self.severity, source)
+    }
+
+    /// Machine-readable form (see [`KbError::to_json`])
+    pub fn to_json(&self) -> serde_json::Value {
+        self.error.to_json(self.severity)
+    }
+}
+
+/// All errors and warnings collected while checking a schema
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    items: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    /// Create an empty diagnostics collection
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an error
+    pub fn error(&mut self, error: KbError) {
+        self.push(Severity::Error, error);
+    }
+
+    /// Record a warning
+    pub fn warning(&mut self, error: KbError) {
+        self.push(Severity::Warning, error);
+    }
+
+    /// Record a diagnostic with the given severity
+    pub fn push(&mut self, severity: Severity, error: KbError) {
+        self.items.push(Diagnostic { severity, error });
+    }
+
+    /// Append all diagnostics from `other`
+    pub fn extend(&mut self, other: Diagnostics) {
+        self.items.extend(other.items);
+    }
+
+    /// Iterate over all diagnostics in the order they were reported
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.items.iter()
+    }
+
+    /// Iterate over errors only
+    pub fn errors(&self) -> impl Iterator<Item = &KbError> {
+        self.with_severity(Severity::Error)
+    }
+
+    /// Iterate over warnings only
+    pub fn warnings(&self) -> impl Iterator<Item = &KbError> {
+        self.with_severity(Severity::Warning)
+    }
+
+    /// Number of errors
+    pub fn error_count(&self) -> usize {
+        self.errors().count()
+    }
+
+    /// Number of warnings
+    pub fn warning_count(&self) -> usize {
+        self.warnings().count()
+    }
+
+    /// Check whether any error was reported
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    /// Number of diagnostics
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Check whether nothing was reported
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Convert into a `Result`, failing with the first error (warnings are ignored)
+    pub fn into_result(self) -> Result<()> {
+        match self
+            .items
+            .into_iter()
+            .find(|d| d.severity == Severity::Error)
+        {
+            Some(diagnostic) => Err(diagnostic.error),
+            None => Ok(()),
+        }
+    }
+
+    fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &KbError> {
+        self.items
+            .iter()
+            .filter(move |d| d.severity == severity)
+            .map(|d| &d.error)
+    }
+}
+
+impl IntoIterator for Diagnostics {
+    type Item = Diagnostic;
+    type IntoIter = std::vec::IntoIter<Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "topic: \"test.function\"\ntitle: \"\"\ndescription: \"Test\"\n";
+
+    #[test]
+    fn test_render_with_excerpt_and_help() {
+        let error = KbError::missing_field("title", "test.yaml", Span::new(2, 1), "Add a title");
+
+        assert_eq!(
+            error.render(Severity::Error, Some(SOURCE)),
+            "error: Missing required field 'title'\n --> test.yaml:2:1\n  |\n2 | title: \"\"\n  | ^\n  |\n  = help: Add a title\n"
+        );
+    }
+
+    #[test]
+    fn test_render_caret_under_column() {
+        let error = KbError::CodeSyntaxError {
+            language: "Rust".to_string(),
+            file: "test.yaml".to_string(),
+            line: 12,
+            column: 17,
+            error: "Unclosed delimiter: '('".to_string(),
+            code: String::new(),
+        };
+        let source = format!("{}        let x = (1;\n", "\n".repeat(11));
+
+        let rendered = error.render(Severity::Warning, Some(&source));
+        assert!(rendered
+            .starts_with("warning: Syntax error in Rust code example: Unclosed delimiter: '('\n"));
+        assert!(rendered.contains("12 |         let x = (1;\n   |                 ^\n"));
+        assert!(!rendered.contains("help"));
+    }
+
+    #[test]
+    fn test_to_json() {
+        let error = KbError::validation(
+            "test.yaml",
+            Span::new(3, 5),
+            "Bad value",
+            "Use a good value",
+        );
+        let json = error.to_json(Severity::Error);
+
+        assert_eq!(json["severity"], "error");
+        assert_eq!(json["message"], "Bad value");
+        assert_eq!(json["file"], "test.yaml");
+        assert_eq!(json["line"], 3);
+        assert_eq!(json["column"], 5);
+        assert_eq!(json["help"], "Use a good value");
+
+        let json = KbError::Other("boom".to_string()).to_json(Severity::Error);
+        assert!(json["file"].is_null());
+        assert_eq!(json["rendered"], "error: boom\n");
+    }
+
+    #[test]
+    fn test_yaml_error_location() {
+        let source = "topic: test.function\ntitle: [unclosed\n";
+        let yaml_error = serde_yaml::from_str::<serde_yaml::Value>(source).unwrap_err();
+        let error = KbError::yaml("test.yaml", &yaml_error);
+
+        let (file, span) = error.location().unwrap();
+        assert_eq!(file, "test.yaml");
+        assert_eq!(span.line, yaml_error.location().unwrap().line());
+        assert!(error
+            .render(Severity::Error, Some(source))
+            .contains("--> test.yaml:"));
+    }
 }
@@ -0,0 +1,418 @@
+//! Incremental build cache
+//!
+//! Records which schema content, generator and generator version produced each
+//! output file, so unchanged outputs can be skipped and outputs whose schema was
+//! deleted or renamed can be removed.
+
+use super::OutputGenerator;
+use crate::{Result, Schema};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Name of the cache file stored in the output directory
+pub const CACHE_FILE_NAME: &str = ".kb-build-cache.json";
+
+/// Identity of a schema source file at build time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFingerprint {
+    /// Canonical path of the schema file
+    pub path: String,
+
+    /// Hash of the schema file content and every file it includes
+    pub hash: String,
+}
+
+impl SourceFingerprint {
+    /// Fingerprint a schema file by path and content
+    ///
+    /// Included fragments are hashed too, so editing a shared fragment
+    /// invalidates the outputs of every schema that includes it.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let mut content = std::fs::read(path)?;
+        let canonical = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+
+        for included in crate::parser::included_files(path)? {
+            content.extend_from_slice(included.to_string_lossy().as_bytes());
+            content.extend(std::fs::read(&included)?);
+        }
+
+        Ok(Self {
+            path: canonical.to_string_lossy().to_string(),
+            hash: content_hash(&content),
+        })
+    }
+}
+
+/// Cache record for one generated output file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CacheEntry {
+    /// Schema file the output was generated from
+    pub source: String,
+
+    /// Content hash of the schema file
+    pub source_hash: String,
+
+    /// Generator name (e.g., "jupyter")
+    pub generator: String,
+
+    /// Generator version at generation time
+    pub generator_version: String,
+
+    /// Topic of the schema, so indexes over all outputs can be rebuilt from the cache
+    #[serde(default)]
+    pub topic: String,
+
+    /// Title of the schema
+    #[serde(default)]
+    pub title: String,
+}
+
+/// Build cache persisted as JSON in the output directory
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BuildCache {
+    /// Entries keyed by output path relative to the output directory
+    entries: BTreeMap<String, CacheEntry>,
+
+    /// Output directory the cache belongs to
+    #[serde(skip)]
+    output_dir: PathBuf,
+
+    /// Treat every entry as stale (`kb build --force`)
+    #[serde(skip)]
+    force: bool,
+
+    /// Outputs generated or confirmed fresh during this build
+    #[serde(skip)]
+    touched: BTreeSet<String>,
+}
+
+impl BuildCache {
+    /// Load the cache for an output directory
+    ///
+    /// A missing or unreadable cache file yields an empty cache, which simply
+    /// causes every output to be regenerated.
+    pub fn load(output_dir: &Path) -> Self {
+        let mut cache = std::fs::read_to_string(output_dir.join(CACHE_FILE_NAME))
+            .ok()
+            .and_then(|content| serde_json::from_str::<BuildCache>(&content).ok())
+            .unwrap_or_default();
+        cache.output_dir = output_dir.to_path_buf();
+        cache
+    }
+
+    /// Regenerate every output regardless of cached state
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Number of cached outputs
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether the cache has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Check whether `output_path` is up to date for this source and generator
+    pub fn is_fresh(
+        &self,
+        generator: &dyn OutputGenerator,
+        source: &SourceFingerprint,
+        output_path: &Path,
+    ) -> bool {
+        if self.force || !output_path.exists() {
+            return false;
+        }
+
+        self.entries
+            .get(&self.key(output_path))
+            .is_some_and(|entry| {
+                entry.source == source.path
+                    && entry.source_hash == source.hash
+                    && entry.generator == generator.name()
+                    && entry.generator_version == generator.version()
+            })
+    }
+
+    /// Generate and save output unless the cached file is still fresh
+    ///
+    /// Wraps [`OutputGenerator::generate_to_file`]. Returns `true` if the file was
+    /// (re)generated and `false` if it was skipped. The cache itself is not
+    /// modified; call [`BuildCache::record`] afterwards.
+    pub fn generate_to_file(
+        &self,
+        generator: &dyn OutputGenerator,
+        schema: &Schema,
+        source: &SourceFingerprint,
+        output_path: &Path,
+    ) -> Result<bool> {
+        if self.is_fresh(generator, source, output_path) {
+            return Ok(false);
+        }
+
+        generator.generate_to_file(schema, output_path)?;
+        Ok(true)
+    }
+
+    /// Record that `output_path` was produced from `source` during this build
+    ///
+    /// `topic` and `title` are those of the schema `source` holds.
+    pub fn record(
+        &mut self,
+        generator: &dyn OutputGenerator,
+        source: &SourceFingerprint,
+        topic: &str,
+        title: &str,
+        output_path: &Path,
+    ) {
+        let key = self.key(output_path);
+        self.entries.insert(
+            key.clone(),
+            CacheEntry {
+                source: source.path.clone(),
+                source_hash: source.hash.clone(),
+                generator: generator.name().to_string(),
+                generator_version: generator.version().to_string(),
+                topic: topic.to_string(),
+                title: title.to_string(),
+            },
+        );
+        self.touched.insert(key);
+    }
+
+    /// Cached outputs of a generator, keyed by path relative to the output directory
+    pub fn outputs_of<'a>(
+        &'a self,
+        generator: &'a str,
+    ) -> impl Iterator<Item = (&'a str, &'a CacheEntry)> + 'a {
+        self.entries
+            .iter()
+            .filter(move |(_, entry)| entry.generator == generator)
+            .map(|(key, entry)| (key.as_str(), entry))
+    }
+
+    /// Remove outputs left behind by deleted or renamed schemas
+    ///
+    /// Only entries belonging to `generators` are considered. An untouched entry is
+    /// stale when its schema no longer exists, or when its schema was built in this
+    /// run but now produces a different output (e.g., its topic changed).
+    ///
+    /// Returns the paths of the removed files.
+    pub fn remove_stale(&mut self, generators: &[&str]) -> Result<Vec<PathBuf>> {
+        let built_sources: BTreeSet<&str> = self
+            .touched
+            .iter()
+            .filter_map(|key| self.entries.get(key))
+            .map(|entry| entry.source.as_str())
+            .collect();
+
+        let stale: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|(key, entry)| {
+                !self.touched.contains(*key)
+                    && generators.contains(&entry.generator.as_str())
+                    && (built_sources.contains(entry.source.as_str())
+                        || !Path::new(&entry.source).exists())
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        let mut removed = Vec::new();
+        for key in stale {
+            self.entries.remove(&key);
+
+            let path = self.output_dir.join(&key);
+            if path.exists() {
+                std::fs::remove_file(&path)?;
+
+                // Drop the category directory too once it is empty
+                if let Some(parent) = path.parent() {
+                    let _ = std::fs::remove_dir(parent);
+                }
+                removed.push(path);
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Save the cache to the output directory
+    pub fn save(&self) -> Result<()> {
+        std::fs::create_dir_all(&self.output_dir)?;
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(self.output_dir.join(CACHE_FILE_NAME), json)?;
+        Ok(())
+    }
+
+    /// Cache key for an output path (relative to the output directory, `/`-separated)
+    fn key(&self, output_path: &Path) -> String {
+        let relative = output_path
+            .strip_prefix(&self.output_dir)
+            .unwrap_or(output_path);
+        relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+/// Stable 64-bit FNV-1a hash of file content, as hex
+///
+/// Stable across Rust versions and platforms, unlike `std`'s `DefaultHasher`.
+pub fn content_hash(content: &[u8]) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let hash = content.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    });
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::*;
+    use tempfile::TempDir;
+
+    struct CountingGenerator {
+        version: &'static str,
+    }
+
+    impl OutputGenerator for CountingGenerator {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        fn file_extension(&self) -> &str {
+            "txt"
+        }
+
+        fn version(&self) -> &str {
+            self.version
+        }
+
+        fn generate(&self, schema: &Schema) -> Result<String> {
+            Ok(schema.title.clone())
+        }
+
+        fn validate_output(&self, _output: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn schema(topic: &str) -> Schema {
+        Schema {
+            topic: topic.to_string(),
+            title: "Title".to_string(),
+            description: "Description".to_string(),
+            mathematical_definition: None,
+            code_refs: None,
+            examples: vec![],
+            article: None,
+            use_cases: vec![],
+            related_topics: vec![],
+            performance: None,
+            interactive_playground: None,
+            outputs: OutputHints::default(),
+            metadata: None,
+            seo: None,
+        }
+    }
+
+    fn write_source(dir: &Path, name: &str, content: &str) -> SourceFingerprint {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        SourceFingerprint::from_file(&path).unwrap()
+    }
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        assert_eq!(content_hash(b""), "cbf29ce484222325");
+        assert_eq!(content_hash(b"a"), "af63dc4c8601ec8c");
+        assert_ne!(content_hash(b"topic: a"), content_hash(b"topic: b"));
+    }
+
+    #[test]
+    fn test_skips_unchanged_and_rebuilds_on_change() {
+        let sources = TempDir::new().unwrap();
+        let out = TempDir::new().unwrap();
+        let generator = CountingGenerator { version: "1" };
+        let output_path = out.path().join("counting/test/test-a.txt");
+
+        let source = write_source(sources.path(), "a.yaml", "topic: test.a");
+        let mut cache = BuildCache::load(out.path());
+        assert!(cache
+            .generate_to_file(&generator, &schema("test.a"), &source, &output_path)
+            .unwrap());
+        cache.record(&generator, &source, "test.a", "Title", &output_path);
+        cache.save().unwrap();
+
+        // Unchanged source is skipped after reloading the cache
+        let cache = BuildCache::load(out.path());
+        assert_eq!(cache.len(), 1);
+        let (key, entry) = cache.outputs_of("counting").next().unwrap();
+        assert_eq!(
+            (key, entry.topic.as_str()),
+            ("counting/test/test-a.txt", "test.a")
+        );
+        assert_eq!(cache.outputs_of("other").count(), 0);
+        assert!(!cache
+            .generate_to_file(&generator, &schema("test.a"), &source, &output_path)
+            .unwrap());
+
+        // Changed content, new generator version and --force all regenerate
+        let changed = write_source(sources.path(), "a.yaml", "topic: test.a\n# edit");
+        assert!(!cache.is_fresh(&generator, &changed, &output_path));
+        assert!(!cache.is_fresh(&CountingGenerator { version: "2" }, &source, &output_path));
+        let forced = BuildCache::load(out.path()).with_force(true);
+        assert!(!forced.is_fresh(&generator, &source, &output_path));
+    }
+
+    #[test]
+    fn test_removes_outputs_of_deleted_and_renamed_schemas() {
+        let sources = TempDir::new().unwrap();
+        let out = TempDir::new().unwrap();
+        let generator = CountingGenerator { version: "1" };
+
+        let a = write_source(sources.path(), "a.yaml", "topic: test.a");
+        let b = write_source(sources.path(), "b.yaml", "topic: test.b");
+        let a_out = out.path().join("counting/test/test-a.txt");
+        let b_out = out.path().join("counting/test/test-b.txt");
+
+        let mut cache = BuildCache::load(out.path());
+        for (source, path, topic) in [(&a, &a_out, "test.a"), (&b, &b_out, "test.b")] {
+            cache
+                .generate_to_file(&generator, &schema(topic), source, path)
+                .unwrap();
+            cache.record(&generator, source, topic, "Title", path);
+        }
+        cache.save().unwrap();
+
+        // b.yaml is deleted, a.yaml is renamed to topic test.renamed
+        std::fs::remove_file(sources.path().join("b.yaml")).unwrap();
+        let renamed_out = out.path().join("counting/test/test-renamed.txt");
+        let mut cache = BuildCache::load(out.path());
+        cache
+            .generate_to_file(&generator, &schema("test.renamed"), &a, &renamed_out)
+            .unwrap();
+        cache.record(&generator, &a, "test.renamed", "Title", &renamed_out);
+
+        // Outputs of generators that did not run are left alone
+        assert!(cache.remove_stale(&["other"]).unwrap().is_empty());
+
+        let mut removed = cache.remove_stale(&["counting"]).unwrap();
+        removed.sort();
+        assert_eq!(removed, vec![a_out.clone(), b_out.clone()]);
+        assert!(!a_out.exists());
+        assert!(!b_out.exists());
+        assert!(renamed_out.exists());
+        assert_eq!(cache.len(), 1);
+    }
+}
@@ -0,0 +1,396 @@
+//! Executing generated notebooks
+//!
+//! `kb build --execute` runs each notebook with `jupyter nbconvert --execute`
+//! against the locally installed kernel named in its `kernelspec`, so published
+//! notebooks show real outputs. Cells that raise fail the build, and the output
+//! of each example cell is compared with `Example::expected_output`. Cells
+//! tagged [`SKIP_EXECUTION_TAG`] are left out of the run.
+
+use crate::runner::{run_with_timeout, Execution, Normalization};
+use crate::schema::Example;
+use crate::{KbError, Result};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Cell tag for code that must not run locally, such as `!pip install` cells
+pub const SKIP_EXECUTION_TAG: &str = "skip-execution";
+
+/// Configuration for executing notebooks
+#[derive(Debug, Clone)]
+pub struct ExecuteConfig {
+    /// `jupyter` executable (nbconvert must be installed)
+    pub jupyter: String,
+
+    /// Maximum time per notebook, also passed to nbconvert as the per-cell timeout
+    pub timeout: Duration,
+
+    /// Scratch directory for notebooks being executed
+    pub scratch_dir: PathBuf,
+
+    /// Output normalisation used when comparing with `expected_output`
+    pub normalization: Normalization,
+}
+
+impl Default for ExecuteConfig {
+    fn default() -> Self {
+        Self {
+            jupyter: "jupyter".to_string(),
+            timeout: Duration::from_secs(300),
+            scratch_dir: std::env::temp_dir().join("kb-execute-notebooks"),
+            normalization: Normalization::default(),
+        }
+    }
+}
+
+/// Outputs of one cell after execution
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExecutedCell {
+    pub outputs: Vec<Value>,
+    pub execution_count: Value,
+}
+
+impl ExecutedCell {
+    /// Text the cell printed to stdout followed by its `text/plain` results
+    pub fn text(&self) -> String {
+        self.outputs
+            .iter()
+            .filter_map(|output| match output["output_type"].as_str()? {
+                "stream" if output["name"] == "stdout" => Some(source_text(&output["text"])),
+                "execute_result" | "display_data" => {
+                    Some(source_text(&output["data"]["text/plain"]))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// `ename: evalue` of the exception the cell raised, if any
+    pub fn error(&self) -> Option<String> {
+        self.outputs
+            .iter()
+            .find(|output| output["output_type"] == "error")
+            .map(|error| {
+                format!(
+                    "{}: {}",
+                    error["ename"].as_str().unwrap_or("Error"),
+                    error["evalue"].as_str().unwrap_or_default()
+                )
+            })
+    }
+}
+
+/// Runs notebooks with nbconvert and checks their outputs
+#[derive(Debug, Clone, Default)]
+pub struct NotebookExecutor {
+    config: ExecuteConfig,
+}
+
+impl NotebookExecutor {
+    pub fn new(config: ExecuteConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &ExecuteConfig {
+        &self.config
+    }
+
+    /// Execute a notebook and return the outputs of every cell, in cell order
+    ///
+    /// Cells that raise do not stop execution; use [`NotebookExecutor::check`]
+    /// to turn their errors into build failures. Skipped cells are not sent to
+    /// nbconvert and come back without outputs.
+    pub fn execute(&self, notebook: &Value) -> Result<Vec<ExecutedCell>> {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+        let (executable, kept) = executable_cells(notebook);
+
+        std::fs::create_dir_all(&self.config.scratch_dir)?;
+        let path = self.config.scratch_dir.join(format!(
+            "notebook-{}-{}.ipynb",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&path, serde_json::to_string(&executable)?)?;
+
+        let mut command = Command::new(&self.config.jupyter);
+        command.args([
+            "nbconvert",
+            "--to",
+            "notebook",
+            "--execute",
+            "--allow-errors",
+            "--stdout",
+        ]);
+        command.arg(format!(
+            "--ExecutePreprocessor.timeout={}",
+            self.config.timeout.as_secs()
+        ));
+        command.arg(&path);
+        let execution = run_with_timeout(&mut command, self.config.timeout);
+        let _ = std::fs::remove_file(&path);
+
+        match execution? {
+            Execution::Finished(stdout) => {
+                let total = notebook_cells(notebook).len();
+                let mut cells = vec![ExecutedCell::default(); total];
+                for (idx, executed) in kept.into_iter().zip(parse_executed(&stdout)?) {
+                    cells[idx] = executed;
+                }
+                Ok(cells)
+            }
+            Execution::Failed(message) => Err(KbError::Other(format!(
+                "Failed to execute notebook with '{} nbconvert': {}",
+                self.config.jupyter, message
+            ))),
+            Execution::Unavailable(program) => Err(KbError::Other(format!(
+                "'{}' not found; install Jupyter and nbconvert (pip install nbconvert) to use --execute",
+                program
+            ))),
+        }
+    }
+
+    /// Fail on the first cell that raised, then on the first example cell whose
+    /// output differs from its `expected_output`
+    ///
+    /// `examples` pairs the index of each example's code cell with the example.
+    pub fn check(&self, cells: &[ExecutedCell], examples: &[(usize, &Example)]) -> Result<()> {
+        for (idx, cell) in cells.iter().enumerate() {
+            if let Some(message) = cell.error() {
+                let label = match examples.iter().find(|(cell, _)| *cell == idx) {
+                    Some((_, example)) => format!("example '{}'", example.title),
+                    None => "setup".to_string(),
+                };
+                return Err(KbError::NotebookCellFailed {
+                    cell: idx,
+                    label,
+                    message,
+                });
+            }
+        }
+
+        for (idx, example) in examples {
+            let (Some(cell), Some(expected)) = (cells.get(*idx), &example.expected_output) else {
+                continue;
+            };
+            let expected = self.config.normalization.apply(expected);
+            let actual = self.config.normalization.apply(&cell.text());
+            if expected != actual {
+                return Err(KbError::NotebookOutputMismatch {
+                    cell: *idx,
+                    example: example.title.clone(),
+                    expected,
+                    actual,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Copy of `notebook` without the cells tagged [`SKIP_EXECUTION_TAG`], and
+/// the original index of each cell that was kept
+fn executable_cells(notebook: &Value) -> (Value, Vec<usize>) {
+    let (cells, kept) = notebook_cells(notebook)
+        .iter()
+        .enumerate()
+        .filter(|(_, cell)| {
+            !cell["metadata"]["tags"]
+                .as_array()
+                .is_some_and(|tags| tags.iter().any(|tag| tag == SKIP_EXECUTION_TAG))
+        })
+        .map(|(idx, cell)| (cell.clone(), idx))
+        .unzip();
+
+    let mut executable = notebook.clone();
+    executable["cells"] = Value::Array(cells);
+    (executable, kept)
+}
+
+fn notebook_cells(notebook: &Value) -> &[Value] {
+    notebook["cells"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+/// Read the cells of a notebook printed by `nbconvert --stdout`
+fn parse_executed(stdout: &str) -> Result<Vec<ExecutedCell>> {
+    let notebook: Value = serde_json::from_str(stdout)?;
+    let cells = notebook["cells"]
+        .as_array()
+        .ok_or_else(|| KbError::Other("nbconvert returned a notebook without cells".to_string()))?;
+
+    Ok(cells
+        .iter()
+        .map(|cell| ExecutedCell {
+            outputs: cell["outputs"].as_array().cloned().unwrap_or_default(),
+            execution_count: cell["execution_count"].clone(),
+        })
+        .collect())
+}
+
+/// Multi-line notebook text, stored either as a string or a list of lines
+fn source_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(lines) => lines.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+/// `jupyter` stand-in for tests that saves the notebook it is given to
+/// `dir/input.ipynb` and prints `cells` back as the executed notebook, with code
+/// cell N printing "ran N"
+#[cfg(all(unix, any(test, feature = "test-util")))]
+pub fn fake_jupyter(dir: &std::path::Path, mut cells: Vec<Value>) -> NotebookExecutor {
+    use serde_json::json;
+    use std::os::unix::fs::PermissionsExt;
+
+    for (n, cell) in cells
+        .iter_mut()
+        .filter(|cell| cell["cell_type"] == "code")
+        .enumerate()
+    {
+        cell["execution_count"] = json!(n + 1);
+        let text = format!("ran {}\n", n + 1);
+        cell["outputs"] = json!([{ "output_type": "stream", "name": "stdout", "text": [text] }]);
+    }
+    std::fs::write(
+        dir.join("executed.ipynb"),
+        json!({ "cells": cells }).to_string(),
+    )
+    .unwrap();
+
+    let script = dir.join("jupyter");
+    std::fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\nfor notebook; do :; done\ncp \"$notebook\" '{0}/input.ipynb'\ncat '{0}/executed.ipynb'\n",
+            dir.display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    NotebookExecutor::new(ExecuteConfig {
+        jupyter: script.display().to_string(),
+        scratch_dir: dir.join("scratch"),
+        ..ExecuteConfig::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::CodeSnippets;
+    use serde_json::json;
+
+    fn example(title: &str, expected_output: Option<&str>) -> Example {
+        Example {
+            title: title.to_string(),
+            explanation: String::new(),
+            code: CodeSnippets {
+                rust: String::new(),
+                python: String::new(),
+                nodejs: String::new(),
+            },
+            expected_output: expected_output.map(str::to_string),
+            doctest: Default::default(),
+        }
+    }
+
+    fn executed() -> Vec<ExecutedCell> {
+        parse_executed(
+            &json!({
+                "cells": [
+                    { "cell_type": "markdown", "source": ["# Title\n"] },
+                    { "cell_type": "code", "execution_count": 1, "outputs": [] },
+                    {
+                        "cell_type": "code",
+                        "execution_count": 2,
+                        "outputs": [
+                            { "output_type": "stream", "name": "stdout", "text": ["2*x\n"] },
+                            { "output_type": "execute_result", "data": { "text/plain": "x**2" } }
+                        ]
+                    },
+                    {
+                        "cell_type": "code",
+                        "execution_count": 3,
+                        "outputs": [
+                            { "output_type": "error", "ename": "NameError", "evalue": "name 'y' is not defined" }
+                        ]
+                    }
+                ]
+            })
+            .to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cell_text_and_errors() {
+        let cells = executed();
+        assert_eq!(cells.len(), 4);
+        assert_eq!(cells[1].execution_count, json!(1));
+        assert_eq!(cells[2].text(), "2*x\nx**2");
+        assert_eq!(cells[2].error(), None);
+        assert_eq!(
+            cells[3].error().unwrap(),
+            "NameError: name 'y' is not defined"
+        );
+    }
+
+    #[test]
+    fn test_check_reports_failures_and_mismatches() {
+        let executor = NotebookExecutor::default();
+        let cells = executed();
+        let derivative = example("Derivative", Some("2*x\nx**2"));
+        let broken = example("Broken", None);
+
+        let err = executor
+            .check(&cells, &[(2, &derivative), (3, &broken)])
+            .unwrap_err();
+        assert!(
+            matches!(err, KbError::NotebookCellFailed { cell: 3, ref label, .. } if label == "example 'Broken'")
+        );
+
+        assert!(executor.check(&cells[..3], &[(2, &derivative)]).is_ok());
+
+        let wrong = example("Derivative", Some("3*x"));
+        let err = executor.check(&cells[..3], &[(2, &wrong)]).unwrap_err();
+        assert!(
+            matches!(err, KbError::NotebookOutputMismatch { cell: 2, ref actual, .. } if actual == "2*x\nx**2")
+        );
+    }
+
+    #[test]
+    fn test_missing_jupyter_is_reported() {
+        let executor = NotebookExecutor::new(ExecuteConfig {
+            jupyter: "kb-no-such-jupyter".to_string(),
+            ..ExecuteConfig::default()
+        });
+        let err = executor.execute(&json!({ "cells": [] })).unwrap_err();
+        assert!(err.to_string().contains("kb-no-such-jupyter"), "{}", err);
+    }
+
+    #[test]
+    fn test_skipped_cells_are_not_executed() {
+        let notebook = json!({
+            "cells": [
+                { "cell_type": "code", "metadata": { "tags": [SKIP_EXECUTION_TAG] }, "source": ["!pip install mathhook\n"] },
+                { "cell_type": "markdown", "metadata": {}, "source": ["# Title\n"] },
+                { "cell_type": "code", "metadata": { "tags": ["parameters"] }, "source": ["x = 2\n"] }
+            ]
+        });
+        let (executable, kept) = executable_cells(&notebook);
+        assert_eq!(kept, vec![1, 2]);
+        assert_eq!(
+            executable["cells"],
+            json!([notebook["cells"][1], notebook["cells"][2]])
+        );
+    }
+}
@@ -2,9 +2,12 @@
 ///
 /// This module defines the trait that all output generators must implement,
 /// enabling extensibility for new formats.
-use crate::{Result, Schema};
+use crate::{KbError, Result, Schema};
 use std::path::Path;
 
+pub mod cache;
+pub mod execute;
+
 /// Trait for generating documentation output from schemas
 pub trait OutputGenerator: Send + Sync {
     /// Name of this generator (e.g., "jupyter", "mdbook")
@@ -13,6 +16,19 @@ pub trait OutputGenerator: Send + Sync {
     /// File extension for generated files (e.g., "ipynb", "md")
     fn file_extension(&self) -> &str;
 
+    /// One-line human-readable description (shown by `kb list`)
+    fn description(&self) -> &str {
+        ""
+    }
+
+    /// Version of this generator's output format
+    ///
+    /// The build cache regenerates outputs whenever this changes, so generators
+    /// should override it when their output changes independently of the crate version.
+    fn version(&self) -> &str {
+        env!("CARGO_PKG_VERSION")
+    }
+
     /// Generate output from a schema
     ///
     /// # Arguments
@@ -129,6 +145,54 @@ impl GeneratorRegistry {
         &self.generators
     }
 
+    /// Names of all registered generators, in registration order
+    pub fn names(&self) -> Vec<&str> {
+        self.generators.iter().map(|g| g.name()).collect()
+    }
+
+    /// Resolve a comma-separated generator list (e.g., "jupyter,mdbook" or "all")
+    ///
+    /// Generators are returned in registration order without duplicates, so the
+    /// output does not depend on how the list was spelled.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KbError::UnknownGenerator` for the first name that does not match
+    /// any registered generator, suggesting the closest registered name.
+    pub fn select(&self, spec: &str) -> Result<Vec<&dyn OutputGenerator>> {
+        let requested: Vec<&str> = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        if requested.contains(&"all") {
+            return Ok(self.generators.iter().map(|g| &**g).collect());
+        }
+
+        for name in &requested {
+            if self.get(name).is_none() {
+                let names = self.names();
+                let suggestion = match crate::suggest::closest_match(name, names.iter().copied()) {
+                    Some(closest) => format!("Did you mean '{}'?", closest),
+                    None => "Run `kb list` to see available generators".to_string(),
+                };
+                return Err(KbError::UnknownGenerator {
+                    name: name.to_string(),
+                    available: names.join(", "),
+                    suggestion,
+                });
+            }
+        }
+
+        Ok(self
+            .generators
+            .iter()
+            .filter(|g| requested.contains(&g.name()))
+            .map(|g| &**g)
+            .collect())
+    }
+
     /// Generate all formats for a schema
     pub fn generate_all(&self, schema: &Schema, output_dir: &Path) -> Result<Vec<String>> {
         let mut generated_files = Vec::new();
@@ -196,6 +260,7 @@ mod tests {
                     nodejs: "test()".to_string(),
                 },
                 expected_output: None,
+                doctest: Default::default(),
             }],
             article: None,
             use_cases: vec![],
@@ -210,6 +275,7 @@ mod tests {
         let gen = MockGenerator;
         let filename = gen.get_output_filename(&schema);
         assert_eq!(filename, "calculus-derivative.txt");
+        assert_eq!(gen.version(), env!("CARGO_PKG_VERSION"));
     }
 
     #[test]
@@ -221,4 +287,20 @@ mod tests {
         assert!(registry.get("nonexistent").is_none());
         assert_eq!(registry.all().len(), 1);
     }
+
+    #[test]
+    fn test_registry_select() {
+        let mut registry = GeneratorRegistry::new();
+        registry.register(MockGenerator);
+
+        assert_eq!(registry.select("all").unwrap().len(), 1);
+        assert_eq!(registry.select("mock, mock").unwrap().len(), 1);
+
+        let err = registry
+            .select("mocks")
+            .err()
+            .expect("Expected unknown generator");
+        assert!(matches!(err, KbError::UnknownGenerator { .. }));
+        assert!(err.to_string().contains("Did you mean 'mock'?"));
+    }
 }
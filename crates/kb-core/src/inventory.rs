@@ -0,0 +1,475 @@
+//! API symbol inventory for checking `code_refs`
+//!
+//! Rust symbols come from rustdoc JSON (`cargo +nightly rustdoc -- -Z
+//! unstable-options --output-format json`), including public re-exports and
+//! inherent/trait methods. Python and Node.js symbols come from plain symbol
+//! lists: one dotted path per line, `#` starts a comment.
+
+use crate::runner::Language;
+use crate::suggest::closest_match;
+use crate::{KbError, Result};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Known public symbols per language
+#[derive(Debug, Clone, Default)]
+pub struct ApiInventory {
+    symbols: BTreeMap<Language, BTreeSet<String>>,
+}
+
+impl ApiInventory {
+    /// Create an empty inventory
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the public API of a crate from a rustdoc JSON file
+    pub fn load_rustdoc(&mut self, path: &Path) -> Result<()> {
+        let json: Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        let symbols = rustdoc_symbols(&json).map_err(|message| {
+            KbError::Other(format!(
+                "Invalid rustdoc JSON '{}': {}",
+                path.display(),
+                message
+            ))
+        })?;
+        self.symbols
+            .entry(Language::Rust)
+            .or_default()
+            .extend(symbols);
+        Ok(())
+    }
+
+    /// Add a symbol list (one dotted path per line) for Python or Node.js
+    pub fn load_symbol_list(&mut self, language: Language, path: &Path) -> Result<()> {
+        let content = std::fs::read_to_string(path)?;
+        self.extend(language, parse_symbol_list(&content));
+        Ok(())
+    }
+
+    /// Add symbols for a language
+    pub fn extend<I: IntoIterator<Item = String>>(&mut self, language: Language, symbols: I) {
+        self.symbols.entry(language).or_default().extend(symbols);
+    }
+
+    /// Whether any symbols are known for `language`
+    pub fn covers(&self, language: Language) -> bool {
+        self.symbols.get(&language).is_some_and(|s| !s.is_empty())
+    }
+
+    /// Whether the inventory has the crate or package `path` starts with
+    ///
+    /// References into packages the inventory does not describe cannot be
+    /// checked and are skipped.
+    pub fn covers_root(&self, language: Language, path: &str) -> bool {
+        let root = path.split(separator(language)).next().unwrap_or(path);
+        self.contains(language, root)
+    }
+
+    /// Whether a fully qualified path exists
+    pub fn contains(&self, language: Language, path: &str) -> bool {
+        self.symbols
+            .get(&language)
+            .is_some_and(|s| s.contains(path))
+    }
+
+    /// All symbols of a language, sorted
+    pub fn symbols(&self, language: Language) -> impl Iterator<Item = &str> {
+        self.symbols
+            .get(&language)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+
+    /// Best replacement for a path that does not exist
+    ///
+    /// Prefers an item with the same name elsewhere (it was moved), then a
+    /// similarly named sibling (it was renamed), then the nearest existing parent.
+    pub fn suggest(&self, language: Language, path: &str) -> Option<String> {
+        let sep = separator(language);
+        let (parent, name) = path.rsplit_once(sep)?;
+
+        let moved = self
+            .symbols(language)
+            .filter(|symbol| symbol.rsplit_once(sep).is_some_and(|(_, n)| n == name))
+            .min_by_key(|symbol| symbol.len());
+        if let Some(moved) = moved {
+            return Some(moved.to_string());
+        }
+
+        let siblings = self
+            .symbols(language)
+            .filter_map(|symbol| symbol.strip_prefix(parent)?.strip_prefix(sep))
+            .filter(|rest| !rest.contains(sep));
+        if let Some(sibling) = closest_match(name, siblings) {
+            return Some(format!("{}{}{}", parent, sep, sibling));
+        }
+
+        self.nearest_parent(language, path).map(str::to_string)
+    }
+
+    /// Longest existing prefix of `path` (at least the crate or package root)
+    pub fn nearest_parent<'a>(&self, language: Language, path: &'a str) -> Option<&'a str> {
+        let sep = separator(language);
+        let mut parent = path;
+        while let Some((head, _)) = parent.rsplit_once(sep) {
+            if self.contains(language, head) {
+                return Some(head);
+            }
+            parent = head;
+        }
+        None
+    }
+}
+
+/// Path separator of a language (`::` for Rust, `.` otherwise)
+pub fn separator(language: Language) -> &'static str {
+    match language {
+        Language::Rust => "::",
+        Language::Python | Language::NodeJs => ".",
+    }
+}
+
+fn parse_symbol_list(content: &str) -> Vec<String> {
+    let mut symbols = BTreeSet::new();
+    for line in content.lines() {
+        let symbol = line.split('#').next().unwrap_or_default().trim();
+        if symbol.is_empty() {
+            continue;
+        }
+        // Parents of listed symbols are implicitly modules
+        let mut path = String::new();
+        for segment in symbol.split('.') {
+            if !path.is_empty() {
+                path.push('.');
+            }
+            path.push_str(segment);
+            symbols.insert(path.clone());
+        }
+    }
+    symbols.into_iter().collect()
+}
+
+/// Expand a `code_refs` entry into individual paths
+///
+/// Handles comma-separated lists (`a::b, c::d`) and brace groups
+/// (`a::{b, c::d}`, nested), using `::` or `.` as the separator.
+pub fn expand_reference(reference: &str, language: Language) -> Vec<String> {
+    let sep = separator(language);
+    let mut paths = Vec::new();
+    for part in split_top_level(reference) {
+        expand_into(part.trim(), sep, &mut paths);
+    }
+    paths
+}
+
+fn expand_into(reference: &str, sep: &str, out: &mut Vec<String>) {
+    if reference.is_empty() {
+        return;
+    }
+    let Some(open) = reference.find('{') else {
+        out.push(reference.to_string());
+        return;
+    };
+    let close = reference.rfind('}').unwrap_or(reference.len());
+    let prefix = reference[..open].trim().trim_end_matches(sep);
+    let inner = &reference[open + 1..close.max(open + 1)];
+
+    for item in split_top_level(inner) {
+        let item = item.trim();
+        let mut expanded = Vec::new();
+        expand_into(item, sep, &mut expanded);
+        for path in expanded {
+            out.push(match (prefix.is_empty(), path.as_str()) {
+                (true, _) => path,
+                (false, "self") => prefix.to_string(),
+                (false, _) => format!("{}{}{}", prefix, sep, path),
+            });
+        }
+    }
+}
+
+/// Split on commas outside braces
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/// Collect public paths from a rustdoc JSON document
+///
+/// Walks the module tree from the crate root, following `pub use` re-exports
+/// (including globs) and adding enum variants and methods of local types. The
+/// canonical `paths` table of the local crate is merged in as well.
+fn rustdoc_symbols(json: &Value) -> std::result::Result<BTreeSet<String>, String> {
+    let index = json["index"].as_object().ok_or("missing 'index'")?;
+    let root = id(&json["root"]).ok_or("missing 'root'")?;
+    let crate_name = index
+        .get(&root)
+        .and_then(|item| item["name"].as_str())
+        .ok_or("root item has no name")?;
+
+    let mut walker = RustdocWalker {
+        index,
+        symbols: BTreeSet::new(),
+        visited: BTreeSet::new(),
+    };
+    walker.symbols.insert(crate_name.to_string());
+    walker.module(&root, crate_name);
+
+    if let Some(paths) = json["paths"].as_object() {
+        for summary in paths.values() {
+            if summary["crate_id"].as_u64() != Some(0) {
+                continue;
+            }
+            if let Some(segments) = summary["path"].as_array() {
+                let segments: Vec<&str> = segments.iter().filter_map(Value::as_str).collect();
+                walker.symbols.insert(segments.join("::"));
+            }
+        }
+    }
+
+    Ok(walker.symbols)
+}
+
+/// Item ids are strings in older rustdoc formats and integers in newer ones
+fn id(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Kind and payload of an item's `inner` (`{"module": {...}}`)
+fn inner(item: &Value) -> Option<(&str, &Value)> {
+    item["inner"]
+        .as_object()?
+        .iter()
+        .next()
+        .map(|(kind, value)| (kind.as_str(), value))
+}
+
+struct RustdocWalker<'a> {
+    index: &'a serde_json::Map<String, Value>,
+    symbols: BTreeSet<String>,
+    visited: BTreeSet<(String, String)>,
+}
+
+impl RustdocWalker<'_> {
+    fn ids(value: &Value) -> Vec<String> {
+        value
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(id)
+            .collect()
+    }
+
+    fn module(&mut self, module_id: &str, prefix: &str) {
+        if !self
+            .visited
+            .insert((module_id.to_string(), prefix.to_string()))
+        {
+            return;
+        }
+        let Some((_, module)) = self.index.get(module_id).and_then(inner) else {
+            return;
+        };
+
+        for child in Self::ids(&module["items"]) {
+            let Some(item) = self.index.get(&child) else {
+                continue;
+            };
+            if item["visibility"].as_str().is_some_and(|v| v != "public") {
+                continue;
+            }
+            let Some((kind, payload)) = inner(item) else {
+                continue;
+            };
+
+            if kind == "use" || kind == "import" {
+                let target = id(&payload["id"]);
+                if payload["glob"].as_bool() == Some(true)
+                    || payload["is_glob"].as_bool() == Some(true)
+                {
+                    if let Some(target) = target {
+                        self.module(&target, prefix);
+                    }
+                    continue;
+                }
+                let Some(name) = payload["name"].as_str() else {
+                    continue;
+                };
+                let path = format!("{}::{}", prefix, name);
+                self.symbols.insert(path.clone());
+                if let Some(target) = target {
+                    self.item(&target, &path);
+                }
+            } else if let Some(name) = item["name"].as_str() {
+                let path = format!("{}::{}", prefix, name);
+                self.symbols.insert(path.clone());
+                self.item(&child, &path);
+            }
+        }
+    }
+
+    /// Children reachable through an item: module contents, variants, methods
+    fn item(&mut self, item_id: &str, path: &str) {
+        let Some((kind, payload)) = self.index.get(item_id).and_then(inner) else {
+            return;
+        };
+
+        match kind {
+            "module" => self.module(item_id, path),
+            "enum" => {
+                for variant in Self::ids(&payload["variants"]) {
+                    self.named_child(&variant, path);
+                }
+                self.impls(&payload["impls"], path);
+            }
+            "struct" | "union" => self.impls(&payload["impls"], path),
+            "trait" => {
+                for child in Self::ids(&payload["items"]) {
+                    self.named_child(&child, path);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn impls(&mut self, impls: &Value, path: &str) {
+        for impl_id in Self::ids(impls) {
+            let Some((_, imp)) = self.index.get(&impl_id).and_then(inner) else {
+                continue;
+            };
+            // Auto-trait and blanket impls only add noise (`into`, `borrow`, ...)
+            if imp["is_synthetic"].as_bool() == Some(true) || !imp["blanket_impl"].is_null() {
+                continue;
+            }
+            for child in Self::ids(&imp["items"]) {
+                self.named_child(&child, path);
+            }
+        }
+    }
+
+    fn named_child(&mut self, child: &str, path: &str) {
+        if let Some(name) = self.index.get(child).and_then(|item| item["name"].as_str()) {
+            self.symbols.insert(format!("{}::{}", path, name));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Trimmed-down rustdoc JSON: `mathhook_core` with a `calculus` module,
+    /// a `Derivative` trait, an `Expression` struct with a method and a
+    /// `prelude` glob re-export
+    const RUSTDOC: &str = r#"{
+      "root": 0,
+      "format_version": 39,
+      "index": {
+        "0": {"id": 0, "name": "mathhook_core", "visibility": "public", "inner": {"module": {"is_crate": true, "items": [1, 5, 6]}}},
+        "1": {"id": 1, "name": "calculus", "visibility": "public", "inner": {"module": {"items": [2, 3]}}},
+        "2": {"id": 2, "name": "derivative", "visibility": "public", "inner": {"function": {}}},
+        "3": {"id": 3, "name": "Derivative", "visibility": "public", "inner": {"trait": {"items": [4]}}},
+        "4": {"id": 4, "name": "nth_derivative", "visibility": "default", "inner": {"function": {}}},
+        "5": {"id": 5, "name": "Expression", "visibility": "public", "inner": {"struct": {"impls": [7, 8]}}},
+        "6": {"id": 6, "name": "prelude", "visibility": "public", "inner": {"module": {"items": [9]}}},
+        "7": {"id": 7, "name": null, "visibility": "default", "inner": {"impl": {"items": [10], "trait": null, "blanket_impl": null}}},
+        "8": {"id": 8, "name": null, "visibility": "default", "inner": {"impl": {"items": [11], "trait": {}, "blanket_impl": {"generic": "T"}}}},
+        "9": {"id": 9, "name": null, "visibility": "public", "inner": {"use": {"source": "crate::calculus", "name": "calculus", "id": 1, "is_glob": true}}},
+        "10": {"id": 10, "name": "simplify", "visibility": "public", "inner": {"function": {}}},
+        "11": {"id": 11, "name": "into", "visibility": "default", "inner": {"function": {}}}
+      },
+      "paths": {
+        "1": {"crate_id": 0, "path": ["mathhook_core", "calculus"], "kind": "module"},
+        "12": {"crate_id": 1, "path": ["std", "vec", "Vec"], "kind": "struct"}
+      }
+    }"#;
+
+    #[test]
+    fn test_rustdoc_symbols() {
+        let symbols = rustdoc_symbols(&serde_json::from_str(RUSTDOC).unwrap()).unwrap();
+        for expected in [
+            "mathhook_core",
+            "mathhook_core::calculus::derivative",
+            "mathhook_core::calculus::Derivative::nth_derivative",
+            "mathhook_core::Expression::simplify",
+            "mathhook_core::prelude::derivative",
+        ] {
+            assert!(symbols.contains(expected), "missing {}", expected);
+        }
+        assert!(!symbols.contains("mathhook_core::Expression::into"));
+        assert!(!symbols.contains("std::vec::Vec"));
+    }
+
+    #[test]
+    fn test_expand_reference() {
+        assert_eq!(
+            expand_reference(
+                "mathhook_core::calculus::{derivative, integrals::{self, integrate}}",
+                Language::Rust
+            ),
+            vec![
+                "mathhook_core::calculus::derivative",
+                "mathhook_core::calculus::integrals",
+                "mathhook_core::calculus::integrals::integrate",
+            ]
+        );
+        assert_eq!(
+            expand_reference("mathhook.Symbol, mathhook.Number", Language::Python),
+            vec!["mathhook.Symbol", "mathhook.Number"]
+        );
+    }
+
+    #[test]
+    fn test_symbol_list() {
+        let mut inventory = ApiInventory::new();
+        inventory.extend(
+            Language::Python,
+            parse_symbol_list("# generated\nmathhook.calculus.derivative\n\n"),
+        );
+
+        assert!(inventory.contains(Language::Python, "mathhook.calculus"));
+        assert!(inventory.covers_root(Language::Python, "mathhook.missing"));
+        assert!(!inventory.covers_root(Language::Python, "sympy.diff"));
+        assert_eq!(
+            inventory.nearest_parent(Language::Python, "mathhook.calculus.integrate"),
+            Some("mathhook.calculus")
+        );
+        assert!(!inventory.covers(Language::NodeJs));
+
+        inventory.extend(
+            Language::Python,
+            parse_symbol_list("mathhook.Symbol\nmathhook.algebra.solve\n"),
+        );
+        let suggest = |path| inventory.suggest(Language::Python, path);
+        assert_eq!(
+            suggest("mathhook.calculus.derivativ").as_deref(),
+            Some("mathhook.calculus.derivative")
+        );
+        assert_eq!(
+            suggest("mathhook.solve").as_deref(),
+            Some("mathhook.algebra.solve")
+        );
+        assert_eq!(suggest("mathhook.complex").as_deref(), Some("mathhook"));
+    }
+}
@@ -1,11 +1,17 @@
+pub mod error;
+pub mod generator;
+pub mod inventory;
+pub mod parser;
+pub mod runner;
 /// MathHook Knowledge Base - Core library
 ///
 /// This crate provides the foundational schema types and validation logic
 /// for the MathHook Knowledge Base engine.
 pub mod schema;
-pub mod parser;
-pub mod generator;
-pub mod error;
+pub mod suggest;
 
+pub use error::{
+    CrossLanguageInconsistencyError, Diagnostic, Diagnostics, ExampleOutputMismatchError, KbError,
+    Result, Severity,
+};
 pub use schema::Schema;
-pub use error::{CrossLanguageInconsistencyError, KbError, Result};
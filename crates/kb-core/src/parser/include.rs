@@ -0,0 +1,353 @@
+//! Shared schema fragments via `$include`
+//!
+//! A mapping with an `$include` key is replaced by the YAML document it names,
+//! resolved relative to the including file:
+//!
+//! ```yaml
+//! outputs:
+//!   colab:
+//!     $include: ../_shared/colab.yaml
+//! article:
+//!   sidebars:
+//!     - $include: ../_shared/sidebars.yaml#install   # one key of the fragment
+//!     - type: tip
+//!       title: Local tip
+//!       content: ...
+//! ```
+//!
+//! Sibling keys next to `$include` override the included mapping, and an include
+//! inside a sequence that yields a sequence is spliced into it. Fragments may
+//! include other fragments; cycles are reported. Files and directories whose
+//! name starts with `_` are fragments and are not collected as schemas.
+
+use crate::schema::SpanMap;
+use crate::{KbError, Result};
+use serde_yaml::{Mapping, Value};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Key that pulls another YAML file into a mapping
+pub const INCLUDE_KEY: &str = "$include";
+
+/// A document with every `$include` replaced
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedDocument {
+    pub document: Value,
+
+    /// Every file included, directly or through other fragments
+    pub files: Vec<PathBuf>,
+}
+
+/// Replace every `$include` in a document parsed from `file`
+///
+/// `spans` locates `$include` keys in `file` for error messages.
+pub fn resolve_includes(document: Value, file: &Path, spans: &SpanMap) -> Result<ResolvedDocument> {
+    let mut resolver = Resolver {
+        stack: vec![std::fs::canonicalize(file).unwrap_or_else(|_| file.to_path_buf())],
+        files: BTreeSet::new(),
+    };
+    let document = resolver.resolve(document, file, spans, "")?;
+    Ok(ResolvedDocument {
+        document,
+        files: resolver.files.into_iter().collect(),
+    })
+}
+
+/// Files a schema includes, directly or through other fragments
+pub fn included_files(path: &Path) -> Result<Vec<PathBuf>> {
+    let content = std::fs::read_to_string(path)?;
+    let document = serde_yaml::from_str(&content)
+        .map_err(|err| KbError::yaml(path.to_string_lossy(), &err))?;
+    Ok(resolve_includes(document, path, &SpanMap::parse(&content))?.files)
+}
+
+/// Whether a file or directory holds shared fragments rather than schemas
+pub fn is_fragment(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('_'))
+}
+
+struct Resolver {
+    /// Canonical paths of the files being resolved, outermost first
+    stack: Vec<PathBuf>,
+    files: BTreeSet<PathBuf>,
+}
+
+impl Resolver {
+    fn resolve(&mut self, value: Value, file: &Path, spans: &SpanMap, path: &str) -> Result<Value> {
+        match value {
+            Value::Mapping(mut mapping) => {
+                let include = mapping.remove(INCLUDE_KEY);
+                let mut resolved = Mapping::new();
+                for (key, value) in mapping {
+                    let child = match key.as_str() {
+                        Some(name) if path.is_empty() => name.to_string(),
+                        Some(name) => format!("{}.{}", path, name),
+                        None => path.to_string(),
+                    };
+                    let value = self.resolve(value, file, spans, &child)?;
+                    resolved.insert(key, value);
+                }
+
+                let Some(target) = include else {
+                    return Ok(Value::Mapping(resolved));
+                };
+                let included = self.include(&target, file, spans, path)?;
+                if resolved.is_empty() {
+                    return Ok(included);
+                }
+                match included {
+                    Value::Mapping(mut base) => {
+                        base.extend(resolved);
+                        Ok(Value::Mapping(base))
+                    }
+                    _ => Err(include_error(
+                        file,
+                        spans,
+                        path,
+                        &target_name(&target),
+                        "the fragment is not a mapping, so it cannot be combined with sibling keys",
+                    )),
+                }
+            }
+            Value::Sequence(items) => {
+                let mut resolved = Vec::with_capacity(items.len());
+                for (idx, item) in items.into_iter().enumerate() {
+                    let splice = is_bare_include(&item);
+                    match self.resolve(item, file, spans, &format!("{}[{}]", path, idx))? {
+                        Value::Sequence(included) if splice => resolved.extend(included),
+                        item => resolved.push(item),
+                    }
+                }
+                Ok(Value::Sequence(resolved))
+            }
+            other => Ok(other),
+        }
+    }
+
+    /// Load, resolve and select the value named by an `$include` at `path`
+    fn include(
+        &mut self,
+        target: &Value,
+        file: &Path,
+        spans: &SpanMap,
+        path: &str,
+    ) -> Result<Value> {
+        let Some(target) = target.as_str() else {
+            return Err(include_error(
+                file,
+                spans,
+                path,
+                &target_name(target),
+                "`$include` must be a file path",
+            ));
+        };
+        let (relative, selector) = target.split_once('#').unwrap_or((target, ""));
+        let included_path = file.parent().unwrap_or(Path::new("")).join(relative);
+
+        let canonical = std::fs::canonicalize(&included_path).map_err(|err| {
+            include_error(
+                file,
+                spans,
+                path,
+                &included_path.to_string_lossy(),
+                err.to_string(),
+            )
+        })?;
+        if let Some(start) = self.stack.iter().position(|open| *open == canonical) {
+            let chain: Vec<String> = self.stack[start..]
+                .iter()
+                .chain(std::iter::once(&canonical))
+                .map(|open| open.to_string_lossy().to_string())
+                .collect();
+            return Err(include_error(
+                file,
+                spans,
+                path,
+                &included_path.to_string_lossy(),
+                format!("include cycle: {}", chain.join(" -> ")),
+            ));
+        }
+
+        let content = std::fs::read_to_string(&canonical).map_err(|err| {
+            include_error(
+                file,
+                spans,
+                path,
+                &included_path.to_string_lossy(),
+                err.to_string(),
+            )
+        })?;
+        let document: Value = serde_yaml::from_str(&content).map_err(|err| {
+            include_error(
+                file,
+                spans,
+                path,
+                &included_path.to_string_lossy(),
+                err.to_string(),
+            )
+        })?;
+
+        self.stack.push(canonical.clone());
+        let resolved = self.resolve(document, &included_path, &SpanMap::parse(&content), "");
+        self.stack.pop();
+        self.files.insert(canonical);
+
+        let mut value = resolved?;
+        for key in selector.split('.').filter(|key| !key.is_empty()) {
+            value = match value {
+                Value::Mapping(mut mapping) => mapping.remove(key),
+                _ => None,
+            }
+            .ok_or_else(|| {
+                include_error(
+                    file,
+                    spans,
+                    path,
+                    &included_path.to_string_lossy(),
+                    format!("the fragment has no key '{}'", selector),
+                )
+            })?;
+        }
+        Ok(value)
+    }
+}
+
+/// A sequence item that is nothing but an `$include`
+fn is_bare_include(item: &Value) -> bool {
+    item.as_mapping()
+        .is_some_and(|mapping| mapping.len() == 1 && mapping.contains_key(INCLUDE_KEY))
+}
+
+fn target_name(target: &Value) -> String {
+    match target.as_str() {
+        Some(name) => name.to_string(),
+        None => serde_yaml::to_string(target)
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+    }
+}
+
+/// Error at the `$include` of `path` in `file`
+fn include_error(
+    file: &Path,
+    spans: &SpanMap,
+    path: &str,
+    included: &str,
+    message: impl Into<String>,
+) -> KbError {
+    let key = if path.is_empty() {
+        INCLUDE_KEY.to_string()
+    } else {
+        format!("{}.{}", path, INCLUDE_KEY)
+    };
+    KbError::include(
+        file.to_string_lossy(),
+        spans.locate(&key),
+        included,
+        message,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn resolve_file(path: &Path) -> Result<ResolvedDocument> {
+        let content = std::fs::read_to_string(path).unwrap();
+        resolve_includes(
+            serde_yaml::from_str(&content).unwrap(),
+            path,
+            &SpanMap::parse(&content),
+        )
+    }
+
+    #[test]
+    fn test_includes_merge_splice_and_select() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "_shared/colab.yaml",
+            "include_gpu_example: true\ninstall_command: \"!pip install mathhook\"\n",
+        );
+        write(
+            dir.path(),
+            "_shared/sidebars.yaml",
+            "install:\n  - type: tip\n    title: Install\n    content: pip install mathhook\n",
+        );
+        let schema = write(
+            dir.path(),
+            "calculus/derivative.yaml",
+            r#"topic: calculus.derivative
+outputs:
+  colab:
+    $include: ../_shared/colab.yaml
+    include_gpu_example: false
+sidebars:
+  - $include: ../_shared/sidebars.yaml#install
+  - type: note
+"#,
+        );
+
+        let resolved = resolve_file(&schema).unwrap();
+        let expected: Value = serde_yaml::from_str(
+            r#"topic: calculus.derivative
+outputs:
+  colab:
+    include_gpu_example: false
+    install_command: "!pip install mathhook"
+sidebars:
+  - type: tip
+    title: Install
+    content: pip install mathhook
+  - type: note
+"#,
+        )
+        .unwrap();
+        assert_eq!(resolved.document, expected);
+        assert_eq!(resolved.files.len(), 2);
+    }
+
+    #[test]
+    fn test_cycle_cites_both_files() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "_a.yaml", "nested:\n  $include: _b.yaml\n");
+        write(dir.path(), "_b.yaml", "$include: _a.yaml\n");
+        let schema = write(
+            dir.path(),
+            "topic.yaml",
+            "topic: t\nshared:\n  $include: _a.yaml\n",
+        );
+
+        let err = resolve_file(&schema).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("include cycle"), "{}", message);
+        assert!(message.contains("_b.yaml"), "{}", message);
+        assert!(message.contains("_a.yaml"), "{}", message);
+    }
+
+    #[test]
+    fn test_missing_fragment_points_at_include() {
+        let dir = tempfile::tempdir().unwrap();
+        let schema = write(
+            dir.path(),
+            "topic.yaml",
+            "topic: t\nshared:\n  $include: _missing.yaml\n",
+        );
+
+        let err = resolve_file(&schema).unwrap_err();
+        let (file, span) = err.location().unwrap();
+        assert!(file.ends_with("topic.yaml"));
+        assert_eq!(span.line, 3);
+        assert!(err.to_string().contains("_missing.yaml"));
+    }
+}
@@ -4,11 +4,20 @@
 use crate::{Result, Schema};
 use std::path::Path;
 
+mod include;
+
+pub use include::{included_files, is_fragment, resolve_includes, ResolvedDocument, INCLUDE_KEY};
+
 /// Load a single schema from a YAML file
 pub fn load_schema(path: &Path) -> Result<Schema> {
     Schema::load_from_file(path)
 }
 
+/// Parse a single schema from a YAML file without validating it
+pub fn parse_schema(path: &Path) -> Result<Schema> {
+    Schema::parse_file(path)
+}
+
 /// Load all schemas from a directory (recursive)
 pub fn load_schemas_from_directory(path: &Path) -> Result<Vec<Schema>> {
     Schema::load_from_directory(path)
@@ -0,0 +1,647 @@
+//! Executable example verification
+//!
+//! Runs each example snippet with a locally installed interpreter or toolchain
+//! and compares what it prints against `Example::expected_output`:
+//!
+//! - Python: `python3 example.py`
+//! - Node.js: `node example.js`
+//! - Rust: `cargo build` in a scratch crate that depends on `mathhook` and
+//!   `mathhook-core` (see [`DEFAULT_RUST_DEPENDENCIES`]), with the
+//!   snippet wrapped in `fn main() -> Result<..>` so `?` works, then the built
+//!   binary
+//!
+//! Interpreter search paths (`PYTHONPATH`, `NODE_PATH`) are inherited from the
+//! environment.
+
+use crate::schema::{CodeSnippets, Example, SchemaFile};
+use crate::{ExampleOutputMismatchError, KbError, Result};
+use regex::Regex;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// A language examples can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Language {
+    Rust,
+    Python,
+    NodeJs,
+}
+
+impl Language {
+    /// All languages, in the order examples list them
+    pub const ALL: [Language; 3] = [Language::Rust, Language::Python, Language::NodeJs];
+
+    /// Parse a language name as used in schemas (`rust`, `python`, `nodejs`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "rust" => Some(Self::Rust),
+            "python" => Some(Self::Python),
+            "nodejs" | "node" | "javascript" | "js" => Some(Self::NodeJs),
+            _ => None,
+        }
+    }
+
+    /// Schema field name (`rust`, `python`, `nodejs`)
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::Python => "python",
+            Self::NodeJs => "nodejs",
+        }
+    }
+
+    /// Snippet for this language
+    pub fn snippet<'a>(&self, code: &'a CodeSnippets) -> &'a str {
+        match self {
+            Self::Rust => &code.rust,
+            Self::Python => &code.python,
+            Self::NodeJs => &code.nodejs,
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rust => write!(f, "Rust"),
+            Self::Python => write!(f, "Python"),
+            Self::NodeJs => write!(f, "JavaScript"),
+        }
+    }
+}
+
+/// How stdout and `expected_output` are normalised before comparing
+///
+/// Line endings and trailing whitespace are always ignored.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Normalization {
+    /// Collapse runs of whitespace inside each line into a single space
+    pub collapse_whitespace: bool,
+
+    /// Drop empty lines
+    pub ignore_blank_lines: bool,
+
+    /// Round decimal numbers to this many digits (e.g., `0.30000000000000004` -> `0.3`)
+    pub float_precision: Option<usize>,
+}
+
+impl Normalization {
+    /// Normalise program output for comparison
+    pub fn apply(&self, text: &str) -> String {
+        static FLOAT: OnceLock<Regex> = OnceLock::new();
+        let float = FLOAT.get_or_init(|| Regex::new(r"-?\d+\.\d+(?:[eE][-+]?\d+)?").unwrap());
+
+        let lines = text
+            .lines()
+            .map(|line| {
+                let mut line = line.trim_end().to_string();
+                if self.collapse_whitespace {
+                    line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+                }
+                if let Some(precision) = self.float_precision {
+                    line = float
+                        .replace_all(&line, |caps: &regex::Captures| {
+                            round_float(&caps[0], precision)
+                        })
+                        .to_string();
+                }
+                line
+            })
+            .filter(|line| !self.ignore_blank_lines || !line.is_empty())
+            .collect::<Vec<_>>();
+
+        lines.join("\n").trim_matches('\n').to_string()
+    }
+}
+
+fn round_float(number: &str, precision: usize) -> String {
+    match number.parse::<f64>() {
+        Ok(value) => {
+            let rounded = format!("{:.*}", precision, value);
+            let trimmed = if rounded.contains('.') {
+                rounded.trim_end_matches('0').trim_end_matches('.')
+            } else {
+                &rounded
+            };
+            if trimmed == "-0" {
+                "0".to_string()
+            } else {
+                trimmed.to_string()
+            }
+        }
+        Err(_) => number.to_string(),
+    }
+}
+
+/// Default `[dependencies]` lines of crates built from Rust examples
+///
+/// Examples import both the `mathhook` facade and `mathhook_core` directly.
+/// Snippets using other crates (e.g. `mathhook_macros`) need an extra line.
+pub const DEFAULT_RUST_DEPENDENCIES: [&str; 2] = ["mathhook = \"*\"", "mathhook-core = \"*\""];
+
+/// Configuration for running examples
+#[derive(Debug, Clone)]
+pub struct RunnerConfig {
+    /// Python interpreter
+    pub python: String,
+
+    /// Node.js interpreter
+    pub node: String,
+
+    /// Cargo executable used to build and run Rust snippets
+    pub cargo: String,
+
+    /// `[dependencies]` lines of the scratch Rust crate
+    pub rust_dependencies: Vec<String>,
+
+    /// Scratch directory for harness files (the Rust target dir is kept between runs)
+    pub scratch_dir: PathBuf,
+
+    /// Maximum time per snippet (including Rust compilation)
+    pub timeout: Duration,
+
+    /// Output normalisation
+    pub normalization: Normalization,
+
+    /// Also run examples without `expected_output`, checking only that they succeed
+    pub run_all: bool,
+}
+
+impl Default for RunnerConfig {
+    fn default() -> Self {
+        Self {
+            python: "python3".to_string(),
+            node: "node".to_string(),
+            cargo: "cargo".to_string(),
+            rust_dependencies: DEFAULT_RUST_DEPENDENCIES.map(str::to_string).to_vec(),
+            scratch_dir: std::env::temp_dir().join("kb-test-examples"),
+            timeout: Duration::from_secs(60),
+            normalization: Normalization::default(),
+            run_all: false,
+        }
+    }
+}
+
+/// Result of running one snippet
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    /// Output matched (or the snippet succeeded and had no expected output)
+    Passed,
+
+    /// Printed output differs from `expected_output` (both normalised)
+    Mismatch { expected: String, actual: String },
+
+    /// The snippet failed to compile, exited non-zero or timed out
+    Failed { message: String },
+
+    /// The interpreter or toolchain is not installed
+    Unavailable { program: String },
+
+    /// The example has no `expected_output` and `run_all` is off
+    Skipped,
+}
+
+/// Outcome of one example in one language
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExampleRun {
+    /// Index of the example in `Schema::examples`
+    pub index: usize,
+
+    pub title: String,
+
+    pub language: Language,
+
+    pub outcome: Outcome,
+}
+
+impl ExampleRun {
+    /// Convert a mismatch or failure into an error pointing at the example
+    pub fn to_error(&self, file: &SchemaFile) -> Option<KbError> {
+        let example = format!("examples[{}]", self.index);
+        match &self.outcome {
+            Outcome::Mismatch { expected, actual } => {
+                let span = file.spans.locate(&format!("{}.expected_output", example));
+                Some(KbError::from(Box::new(ExampleOutputMismatchError {
+                    language: self.language.to_string(),
+                    example: self.title.clone(),
+                    file: file.path.clone(),
+                    line: span.line,
+                    column: span.column,
+                    expected: expected.clone(),
+                    actual: actual.clone(),
+                })))
+            }
+            Outcome::Failed { message } => {
+                let span = file
+                    .spans
+                    .locate(&format!("{}.code.{}", example, self.language.key()));
+                Some(KbError::ExampleExecutionFailed {
+                    language: self.language.to_string(),
+                    example: self.title.clone(),
+                    file: file.path.clone(),
+                    line: span.line,
+                    column: span.column,
+                    message: message.clone(),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Runs example snippets and checks their output
+pub struct ExampleRunner {
+    config: RunnerConfig,
+}
+
+impl ExampleRunner {
+    pub fn new(config: RunnerConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &RunnerConfig {
+        &self.config
+    }
+
+    /// Run every example of a schema in the given languages
+    ///
+    /// Examples without a snippet for a language are left out of the results.
+    pub fn run_schema(
+        &self,
+        schema: &crate::Schema,
+        languages: &[Language],
+    ) -> Result<Vec<ExampleRun>> {
+        let mut runs = Vec::new();
+
+        for (index, example) in schema.examples.iter().enumerate() {
+            for &language in languages {
+                if language.snippet(&example.code).trim().is_empty() {
+                    continue;
+                }
+                runs.push(ExampleRun {
+                    index,
+                    title: example.title.clone(),
+                    language,
+                    outcome: self.run_example(example, language)?,
+                });
+            }
+        }
+
+        Ok(runs)
+    }
+
+    /// Run one example in one language and compare its output
+    pub fn run_example(&self, example: &Example, language: Language) -> Result<Outcome> {
+        if example.expected_output.is_none() && !self.config.run_all {
+            return Ok(Outcome::Skipped);
+        }
+
+        let stdout = match self.execute(language.snippet(&example.code), language)? {
+            Execution::Finished(stdout) => stdout,
+            Execution::Failed(message) => return Ok(Outcome::Failed { message }),
+            Execution::Unavailable(program) => return Ok(Outcome::Unavailable { program }),
+        };
+
+        let Some(expected) = &example.expected_output else {
+            return Ok(Outcome::Passed);
+        };
+        let normalization = &self.config.normalization;
+        let expected = normalization.apply(expected);
+        let actual = normalization.apply(&stdout);
+
+        if expected == actual {
+            Ok(Outcome::Passed)
+        } else {
+            Ok(Outcome::Mismatch { expected, actual })
+        }
+    }
+
+    /// Write the snippet into a scratch harness and run it
+    fn execute(&self, code: &str, language: Language) -> Result<Execution> {
+        let dir = self.config.scratch_dir.join(language.key());
+        std::fs::create_dir_all(&dir)?;
+
+        let mut command = match language {
+            Language::Python => {
+                std::fs::write(dir.join("example.py"), code)?;
+                let mut command = Command::new(&self.config.python);
+                command.arg("example.py");
+                command
+            }
+            Language::NodeJs => {
+                std::fs::write(dir.join("example.js"), code)?;
+                let mut command = Command::new(&self.config.node);
+                command.arg("example.js");
+                command
+            }
+            Language::Rust => return self.execute_rust(code, &dir),
+        };
+        command.current_dir(&dir);
+
+        run_with_timeout(&mut command, self.config.timeout)
+    }
+
+    /// Build the snippet with cargo, then run the binary itself
+    ///
+    /// `cargo run` would leave the snippet running when cargo is killed on timeout.
+    fn execute_rust(&self, code: &str, dir: &Path) -> Result<Execution> {
+        std::fs::create_dir_all(dir.join("src"))?;
+        write_if_changed(
+            &dir.join("Cargo.toml"),
+            &rust_manifest(&self.config.rust_dependencies),
+        )?;
+        std::fs::write(dir.join("src/main.rs"), rust_harness(code))?;
+
+        let target_dir = dir.join("target");
+        let started = Instant::now();
+        let mut build = Command::new(&self.config.cargo);
+        build
+            .args(["build", "--quiet"])
+            .env("CARGO_TARGET_DIR", &target_dir)
+            .current_dir(dir);
+        match run_with_timeout(&mut build, self.config.timeout)? {
+            Execution::Finished(_) => {}
+            failed => return Ok(failed),
+        }
+
+        let binary = target_dir
+            .join("debug")
+            .join(format!("kb-example{}", std::env::consts::EXE_SUFFIX));
+        let mut command = Command::new(binary);
+        command.current_dir(dir);
+        run_with_timeout(
+            &mut command,
+            self.config.timeout.saturating_sub(started.elapsed()),
+        )
+    }
+}
+
+/// Result of running a harness
+pub(crate) enum Execution {
+    Finished(String),
+    Failed(String),
+    Unavailable(String),
+}
+
+/// Run a command, capturing stdout and stderr, and kill it after `timeout`
+pub(crate) fn run_with_timeout(command: &mut Command, timeout: Duration) -> Result<Execution> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let mut child = match command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(Execution::Unavailable(program))
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    // Drain pipes on separate threads so a chatty snippet cannot block on a full pipe
+    let mut stdout_pipe = child.stdout.take().expect("stdout is piped");
+    let mut stderr_pipe = child.stderr.take().expect("stderr is piped");
+    let stdout_reader = std::thread::spawn(move || {
+        let mut buffer = String::new();
+        let _ = stdout_pipe.read_to_string(&mut buffer);
+        buffer
+    });
+    let stderr_reader = std::thread::spawn(move || {
+        let mut buffer = String::new();
+        let _ = stderr_pipe.read_to_string(&mut buffer);
+        buffer
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() >= timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+
+            // Processes the child started may still hold the pipes open, so the
+            // readers are given a moment to finish and otherwise left behind
+            let grace = Instant::now();
+            while !(stdout_reader.is_finished() && stderr_reader.is_finished())
+                && grace.elapsed() < READER_GRACE
+            {
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            return Ok(Execution::Failed(format!(
+                "timed out after {}s",
+                timeout.as_secs()
+            )));
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+
+    let stdout = stdout_reader.join().unwrap_or_default();
+    let stderr = stderr_reader.join().unwrap_or_default();
+
+    Ok(if status.success() {
+        Execution::Finished(stdout)
+    } else {
+        Execution::Failed(format!("{} ({})", last_lines(&stderr, 20), status))
+    })
+}
+
+/// How long to wait for a killed command's output pipes to close
+const READER_GRACE: Duration = Duration::from_secs(1);
+
+/// Last `count` non-empty lines of a command's stderr
+fn last_lines(text: &str, count: usize) -> String {
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    let start = lines.len().saturating_sub(count);
+    let tail = lines[start..].join("\n  ");
+    if tail.is_empty() {
+        "no error output".to_string()
+    } else {
+        tail
+    }
+}
+
+/// Manifest of the scratch crate used for Rust snippets
+fn rust_manifest(dependencies: &[String]) -> String {
+    format!(
+        "[package]\nname = \"kb-example\"\nversion = \"0.0.0\"\nedition = \"2021\"\npublish = false\n\n[dependencies]\n{}\n",
+        dependencies.join("\n")
+    )
+}
+
+/// Wrap a Rust snippet in `main` unless it defines one itself
+fn rust_harness(code: &str) -> String {
+    if code.contains("fn main(") {
+        return code.to_string();
+    }
+
+    let body: String = code.lines().map(|line| format!("    {}\n", line)).collect();
+    format!(
+        "#[allow(unused_imports, unused_variables, clippy::all)]\nfn main() -> Result<(), Box<dyn std::error::Error>> {{\n{}    #[allow(unreachable_code)]\n    Ok(())\n}}\n",
+        body
+    )
+}
+
+/// Avoid touching the manifest so cargo does not re-resolve on every snippet
+fn write_if_changed(path: &Path, content: &str) -> Result<()> {
+    if std::fs::read_to_string(path).ok().as_deref() != Some(content) {
+        std::fs::write(path, content)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example(python: &str, expected_output: Option<&str>) -> Example {
+        Example {
+            title: "Example".to_string(),
+            explanation: String::new(),
+            code: CodeSnippets {
+                rust: String::new(),
+                python: python.to_string(),
+                nodejs: String::new(),
+            },
+            expected_output: expected_output.map(str::to_string),
+            doctest: Default::default(),
+        }
+    }
+
+    fn runner(scratch: &Path) -> ExampleRunner {
+        ExampleRunner::new(RunnerConfig {
+            scratch_dir: scratch.to_path_buf(),
+            timeout: Duration::from_secs(10),
+            ..RunnerConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_normalization() {
+        let exact = Normalization::default();
+        assert_eq!(exact.apply("x + 1  \r\n\n"), "x + 1");
+        assert_ne!(exact.apply("a  b"), exact.apply("a b"));
+
+        let loose = Normalization {
+            collapse_whitespace: true,
+            ignore_blank_lines: true,
+            float_precision: Some(6),
+        };
+        assert_eq!(
+            loose.apply("  a   b\n\n0.30000000000000004\n-0.0000001"),
+            "a b\n0.3\n0"
+        );
+        assert_eq!(loose.apply("2.50 and 1e3"), "2.5 and 1e3");
+    }
+
+    #[test]
+    fn test_rust_harness_wraps_snippet() {
+        let harness = rust_harness("use mathhook::prelude::*;\nlet x = parse(\"x\")?;");
+        assert!(harness.contains("fn main() -> Result<(), Box<dyn std::error::Error>> {\n    use mathhook::prelude::*;\n"));
+        assert!(harness.ends_with("    Ok(())\n}\n"));
+
+        let own_main = "fn main() {}";
+        assert_eq!(rust_harness(own_main), own_main);
+    }
+
+    #[test]
+    fn test_runs_python_snippets() {
+        let scratch = tempfile::TempDir::new().unwrap();
+        let runner = runner(scratch.path());
+
+        let outcome = runner
+            .run_example(&example("print(1 + 1)", Some("2\n")), Language::Python)
+            .unwrap();
+        if let Outcome::Unavailable { .. } = outcome {
+            // No Python interpreter on this machine
+            return;
+        }
+        assert_eq!(outcome, Outcome::Passed);
+
+        let outcome = runner
+            .run_example(&example("print(3)", Some("2")), Language::Python)
+            .unwrap();
+        assert_eq!(
+            outcome,
+            Outcome::Mismatch {
+                expected: "2".to_string(),
+                actual: "3".to_string()
+            }
+        );
+
+        let outcome = runner
+            .run_example(
+                &example("raise ValueError('boom')", Some("2")),
+                Language::Python,
+            )
+            .unwrap();
+        assert!(
+            matches!(outcome, Outcome::Failed { message } if message.contains("ValueError: boom"))
+        );
+    }
+
+    #[test]
+    fn test_skips_examples_without_expected_output() {
+        let scratch = tempfile::TempDir::new().unwrap();
+        let outcome = runner(scratch.path())
+            .run_example(&example("print(1)", None), Language::Python)
+            .unwrap();
+        assert_eq!(outcome, Outcome::Skipped);
+    }
+
+    #[test]
+    fn test_rust_snippet_times_out() {
+        let scratch = tempfile::TempDir::new().unwrap();
+        let runner = ExampleRunner::new(RunnerConfig {
+            rust_dependencies: Vec::new(),
+            scratch_dir: scratch.path().to_path_buf(),
+            timeout: Duration::from_secs(5),
+            ..RunnerConfig::default()
+        });
+        let mut printing = example("", Some("1"));
+        printing.code.rust = "println!(\"1\");".to_string();
+        let outcome = runner.run_example(&printing, Language::Rust).unwrap();
+        if let Outcome::Unavailable { .. } = outcome {
+            // No cargo on this machine
+            return;
+        }
+        assert_eq!(outcome, Outcome::Passed);
+
+        let mut looping = printing;
+        looping.code.rust = "loop {}".to_string();
+        let started = Instant::now();
+        let outcome = runner.run_example(&looping, Language::Rust).unwrap();
+        assert!(
+            matches!(outcome, Outcome::Failed { ref message } if message.contains("timed out")),
+            "{:?}",
+            outcome
+        );
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_missing_interpreter_is_unavailable() {
+        let scratch = tempfile::TempDir::new().unwrap();
+        let runner = ExampleRunner::new(RunnerConfig {
+            python: "kb-no-such-python".to_string(),
+            scratch_dir: scratch.path().to_path_buf(),
+            ..RunnerConfig::default()
+        });
+
+        let outcome = runner
+            .run_example(&example("print(1)", Some("1")), Language::Python)
+            .unwrap();
+        assert_eq!(
+            outcome,
+            Outcome::Unavailable {
+                program: "kb-no-such-python".to_string()
+            }
+        );
+    }
+}
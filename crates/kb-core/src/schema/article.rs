@@ -2,10 +2,11 @@
 ///
 /// This module defines structures for comprehensive article-style documentation
 /// that can be rendered differently across output formats (tutorial vs reference vs marketing).
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Complete article structure - supports both simple content and structured format
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(untagged)]
 pub enum Article {
     /// Simple markdown content (most common)
@@ -15,14 +16,14 @@ pub enum Article {
 }
 
 /// Simple article with just markdown content
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct SimpleArticle {
     /// Markdown content
     pub content: String,
 }
 
 /// Structured article with introduction, body sections, and conclusion
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct StructuredArticle {
     /// Article introduction (sets context, explains importance)
     pub introduction: Introduction,
@@ -83,7 +84,7 @@ impl Article {
 }
 
 /// Introduction section
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct Introduction {
     /// Hook/opening paragraph (engaging, sets tone)
     pub hook: String,
@@ -102,7 +103,7 @@ pub struct Introduction {
 }
 
 /// Main content section
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct Section {
     /// Section title
     pub title: String,
@@ -124,7 +125,7 @@ pub struct Section {
 }
 
 /// Subsection for hierarchical content
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct SubSection {
     /// Subsection title
     pub title: String,
@@ -134,7 +135,7 @@ pub struct SubSection {
 }
 
 /// Conclusion section
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct Conclusion {
     /// Summary of key takeaways
     pub summary: String,
@@ -153,7 +154,7 @@ pub struct Conclusion {
 }
 
 /// External resource reference
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct Resource {
     /// Resource title
     pub title: String,
@@ -168,7 +169,7 @@ pub struct Resource {
 }
 
 /// Practice exercise
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct Exercise {
     /// Exercise title/prompt
     pub prompt: String,
@@ -186,7 +187,7 @@ pub struct Exercise {
 }
 
 /// Exercise difficulty levels
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ExerciseDifficulty {
     Beginner,
@@ -196,7 +197,7 @@ pub enum ExerciseDifficulty {
 }
 
 /// Sidebar content (tips, warnings, notes)
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct Sidebar {
     /// Sidebar type
     #[serde(rename = "type")]
@@ -210,7 +211,7 @@ pub struct Sidebar {
 }
 
 /// Sidebar types
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum SidebarType {
     /// Helpful tip
@@ -233,7 +234,7 @@ pub enum SidebarType {
 }
 
 /// Format-specific article variations
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct ArticleVariations {
     /// Jupyter-specific content (tutorial style)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -249,7 +250,7 @@ pub struct ArticleVariations {
 }
 
 /// Jupyter-specific article content (conversational, tutorial)
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct JupyterArticle {
     /// Tutorial-style introduction
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -265,7 +266,7 @@ pub struct JupyterArticle {
 }
 
 /// mdBook-specific article content (technical reference)
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct MdBookArticle {
     /// Technical deep dive sections
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
@@ -281,7 +282,7 @@ pub struct MdBookArticle {
 }
 
 /// Technical deep dive
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct DeepDive {
     /// Deep dive title
     pub title: String,
@@ -291,7 +292,7 @@ pub struct DeepDive {
 }
 
 /// Vue site-specific article content (marketing/engagement)
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct VueSiteArticle {
     /// Marketing hook (engaging opening)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -307,7 +308,7 @@ pub struct VueSiteArticle {
 }
 
 /// Use case story (real-world application)
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct UseCaseStory {
     /// Story title
     pub title: String,
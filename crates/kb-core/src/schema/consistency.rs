@@ -0,0 +1,356 @@
+//! Cross-language consistency checking
+//!
+//! The Rust, Python and Node.js snippets of an example are meant to show the
+//! same thing. This pass flags examples that are missing a language the schema
+//! otherwise covers, that call the `code_refs` function in some languages but
+//! not others, or whose numeric literals disagree.
+
+use super::syntax::strip_comments;
+use super::{Example, SchemaFile};
+use crate::error::CrossLanguageInconsistencyError;
+use crate::runner::Language;
+use crate::{Diagnostics, KbError, Severity};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Check every example of a schema for cross-language inconsistencies
+///
+/// Each inconsistency is reported as a [`KbError::CrossLanguageInconsistency`]
+/// with the given severity.
+pub fn check_consistency(file: &SchemaFile, severity: Severity) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    let schema = &file.schema;
+
+    // Languages the schema covers: declared bindings plus any language used by an example
+    let mut covered: BTreeSet<Language> = BTreeSet::new();
+    if let Some(refs) = &schema.code_refs {
+        covered.extend(
+            Language::ALL
+                .into_iter()
+                .filter(|l| !code_ref(refs, *l).is_empty()),
+        );
+    }
+    for example in &schema.examples {
+        covered.extend(present(example));
+    }
+
+    let names: BTreeMap<Language, String> = match &schema.code_refs {
+        Some(refs) => Language::ALL
+            .into_iter()
+            .filter_map(|l| function_name(code_ref(refs, l)).map(|name| (l, name)))
+            .collect(),
+        None => BTreeMap::new(),
+    };
+
+    for (idx, example) in schema.examples.iter().enumerate() {
+        let languages = present(example);
+        if languages.is_empty() {
+            continue;
+        }
+        let mut report =
+            |language: Option<Language>, message: String, values: &dyn Fn(Language) -> String| {
+                let path = match language {
+                    Some(language) => format!("examples[{}].code.{}", idx, language.key()),
+                    None => format!("examples[{}].code", idx),
+                };
+                let span = file.spans.locate(&path);
+                let error = KbError::from(Box::new(CrossLanguageInconsistencyError {
+                    file: file.path.clone(),
+                    example: example.title.clone(),
+                    line: span.line,
+                    column: span.column,
+                    message,
+                    rust_output: values(Language::Rust),
+                    python_output: values(Language::Python),
+                    js_output: values(Language::NodeJs),
+                }));
+                diagnostics.push(severity, error);
+            };
+        let snippet_or_missing = |language: Language| {
+            if languages.contains(&language) {
+                "present".to_string()
+            } else {
+                "missing".to_string()
+            }
+        };
+
+        // Missing languages
+        let missing: Vec<Language> = covered
+            .iter()
+            .copied()
+            .filter(|l| !languages.contains(l))
+            .collect();
+        if !missing.is_empty() && languages.len() < covered.len() {
+            let names: Vec<String> = missing.iter().map(Language::to_string).collect();
+            report(
+                None,
+                format!(
+                    "Missing {} snippet(s); other examples or code_refs cover them",
+                    names.join(", ")
+                ),
+                &snippet_or_missing,
+            );
+        }
+
+        // The code_refs function should be used in every language or none
+        let calls: BTreeMap<Language, bool> = languages
+            .iter()
+            .filter_map(|l| {
+                names
+                    .get(l)
+                    .map(|name| (*l, identifiers(l.snippet(&example.code), *l).contains(name)))
+            })
+            .collect();
+        if calls.values().any(|called| *called) {
+            for (language, _) in calls.iter().filter(|(_, called)| !**called) {
+                let shown = |l: Language| match calls.get(&l) {
+                    Some(true) => format!("uses {}", names[&l]),
+                    Some(false) => format!("does not use {}", names[&l]),
+                    None => "-".to_string(),
+                };
+                report(
+                    Some(*language),
+                    format!(
+                        "{} snippet does not use '{}' (code_refs.{}) while other languages do",
+                        language,
+                        names[language],
+                        language.key()
+                    ),
+                    &shown,
+                );
+            }
+        }
+
+        // Numeric literals should agree. Snippets without any literal are usually
+        // structured differently (e.g. a loop instead of explicit values) and skipped.
+        let literals: BTreeMap<Language, BTreeSet<String>> = languages
+            .iter()
+            .map(|l| (*l, numbers(l.snippet(&example.code), *l)))
+            .collect();
+        let comparable = literals.len() > 1 && literals.values().all(|set| !set.is_empty());
+        if comparable && literals.values().collect::<BTreeSet<_>>().len() > 1 {
+            let all: BTreeSet<String> = literals.values().flatten().cloned().collect();
+            let divergent: BTreeSet<String> = all
+                .into_iter()
+                .filter(|n| literals.values().any(|set| !set.contains(n)))
+                .collect();
+            let shown = |l: Language| match literals.get(&l) {
+                Some(set) => sorted_numerically(set),
+                None => "-".to_string(),
+            };
+            report(
+                None,
+                format!(
+                    "Numeric literals differ between languages: {} not used in every snippet",
+                    sorted_numerically(&divergent)
+                ),
+                &shown,
+            );
+        }
+    }
+
+    diagnostics
+}
+
+/// Languages with a non-empty snippet
+fn present(example: &Example) -> BTreeSet<Language> {
+    Language::ALL
+        .into_iter()
+        .filter(|l| !l.snippet(&example.code).trim().is_empty())
+        .collect()
+}
+
+fn code_ref(refs: &super::CodeReferences, language: Language) -> &str {
+    match language {
+        Language::Rust => &refs.rust,
+        Language::Python => &refs.python,
+        Language::NodeJs => &refs.nodejs,
+    }
+}
+
+/// Last path segment of a code reference, normalised (see [`normalize`])
+///
+/// Bare crate or package names (`mathhook-node`) and grouped imports
+/// (`parser::{Parser, ParserConfig}`) name no single function and are skipped.
+fn function_name(reference: &str) -> Option<String> {
+    let (_, name) = reference.trim().rsplit_once([':', '.'])?;
+    let is_identifier = name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    is_identifier.then(|| normalize(name))
+}
+
+/// Lowercase and drop underscores so `type_inference` matches `typeInference`
+fn normalize(identifier: &str) -> String {
+    identifier
+        .chars()
+        .filter(|c| *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Normalised identifiers used in a snippet
+fn identifiers(code: &str, language: Language) -> BTreeSet<String> {
+    strip_comments(code, language)
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|token| token.starts_with(|c: char| c.is_alphabetic() || c == '_'))
+        .map(normalize)
+        .collect()
+}
+
+fn sorted_numerically(numbers: &BTreeSet<String>) -> String {
+    let mut sorted: Vec<&String> = numbers.iter().collect();
+    sorted.sort_by(|a, b| {
+        let (a, b) = (
+            a.parse::<f64>().unwrap_or(0.0),
+            b.parse::<f64>().unwrap_or(0.0),
+        );
+        a.total_cmp(&b)
+    });
+    sorted.into_iter().cloned().collect::<Vec<_>>().join(", ")
+}
+
+/// Numeric literals in a snippet, formatted canonically (`2.0` and `2_i64` become `2`)
+///
+/// `0` and `1` are left out: they mostly appear as indices, range bounds and
+/// initial values, which differ between languages for idiomatic reasons.
+fn numbers(code: &str, language: Language) -> BTreeSet<String> {
+    let code = strip_comments(code, language);
+    let chars: Vec<char> = code.chars().collect();
+    let mut found = BTreeSet::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let previous = i.checked_sub(1).map(|p| chars[p]);
+        let part_of_word = previous.is_some_and(|p| p.is_alphanumeric() || p == '_' || p == '.');
+        if !chars[i].is_ascii_digit() || part_of_word {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '_') {
+            i += 1;
+        }
+        if i + 1 < chars.len() && chars[i] == '.' && chars[i + 1].is_ascii_digit() {
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '_') {
+                i += 1;
+            }
+        }
+        let literal: String = chars[start..i].iter().filter(|c| **c != '_').collect();
+
+        // Skip type suffixes (`2_i64`, `1.0f64`) and trailing identifier characters
+        while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+            i += 1;
+        }
+
+        if let Ok(value) = literal.parse::<f64>() {
+            if value != 0.0 && value != 1.0 {
+                found.insert(value.to_string());
+            }
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::SpanMap;
+
+    fn file(yaml: &str) -> SchemaFile {
+        SchemaFile {
+            path: "test.yaml".to_string(),
+            schema: serde_yaml::from_str(yaml).unwrap(),
+            spans: SpanMap::parse(yaml),
+            unknown_keys: Vec::new(),
+        }
+    }
+
+    fn messages(diagnostics: &Diagnostics) -> Vec<String> {
+        diagnostics.iter().map(|d| d.error.summary()).collect()
+    }
+
+    #[test]
+    fn test_literal_extraction() {
+        let rust = "let x = expr!(x ^ 2); // 7 in a comment\nlet y = 2.0_f64 + v.0 + log10(x);";
+        assert_eq!(
+            numbers(rust, Language::Rust),
+            BTreeSet::from(["2".to_string()])
+        );
+        let python = "y = x**2.5  # 3\nprint(\"#4\")";
+        assert_eq!(
+            numbers(python, Language::Python),
+            BTreeSet::from(["2.5".to_string(), "4".to_string()])
+        );
+        assert!(identifiers("mh.typeInference(x)", Language::NodeJs).contains("typeinference"));
+    }
+
+    #[test]
+    fn test_consistent_example() {
+        let diagnostics = check_consistency(
+            &file(
+                r#"topic: "calculus.derivative"
+title: "Derivatives"
+description: "Test"
+code_refs:
+  rust: "mathhook_core::calculus::derivative"
+  python: "mathhook.calculus.derivative"
+  nodejs: "mathhook.calculus.derivative"
+examples:
+  - title: "Power"
+    code:
+      rust: "let d = derivative(&expr!(x ^ 3), x);"
+      python: "d = derivative(x**3, x)"
+      nodejs: "const d = derivative(pow(x, 3), x);"
+"#,
+            ),
+            Severity::Warning,
+        );
+        assert!(diagnostics.is_empty(), "{:?}", messages(&diagnostics));
+    }
+
+    #[test]
+    fn test_inconsistencies() {
+        let diagnostics = check_consistency(
+            &file(
+                r#"topic: "calculus.derivative"
+title: "Derivatives"
+description: "Test"
+code_refs:
+  rust: "mathhook_core::calculus::derivative"
+  python: "mathhook.calculus.derivative"
+  nodejs: "mathhook.calculus.derivative"
+examples:
+  - title: "Power"
+    code:
+      rust: "let d = derivative(&expr!(x ^ 3), x);"
+      python: "d = diff(x**2, x)"
+  - title: "Other"
+    code:
+      rust: "let e = 1;"
+      python: "e = 1"
+      nodejs: "const e = 1;"
+"#,
+            ),
+            Severity::Error,
+        );
+
+        assert_eq!(
+            messages(&diagnostics),
+            vec![
+                "Example 'Power' is inconsistent across languages: Missing JavaScript snippet(s); other examples or code_refs cover them",
+                "Example 'Power' is inconsistent across languages: Python snippet does not use 'derivative' (code_refs.python) while other languages do",
+                "Example 'Power' is inconsistent across languages: Numeric literals differ between languages: 2, 3 not used in every snippet",
+            ]
+        );
+        assert_eq!(diagnostics.error_count(), 3);
+
+        let error = &diagnostics.iter().nth(1).unwrap().error;
+        assert_eq!(error.location().unwrap().1.line, 12);
+        assert!(error
+            .help()
+            .unwrap()
+            .contains("Python: does not use derivative"));
+    }
+}
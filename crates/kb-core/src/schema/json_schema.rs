@@ -0,0 +1,51 @@
+//! JSON Schema export for the KB YAML format
+//!
+//! The schema is derived from the Rust types in this module, so it always matches
+//! what `Schema::load_from_file` accepts. YAML language servers can use it for
+//! completion and validation while editing topic files.
+
+use super::Schema;
+use schemars::generate::SchemaSettings;
+
+/// `$id` of the published JSON Schema
+pub const JSON_SCHEMA_ID: &str = "https://mathhook.org/schemas/kb-schema.json";
+
+/// Generate the JSON Schema (draft 2020-12) describing a KB schema file
+pub fn json_schema() -> serde_json::Value {
+    let generator = SchemaSettings::draft2020_12().into_generator();
+    let mut schema = generator.into_root_schema_for::<Schema>();
+    schema.insert("$id".to_string(), JSON_SCHEMA_ID.into());
+    schema.insert("title".to_string(), "MathHook KB schema".into());
+    schema.to_value()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_untagged_article_is_any_of() {
+        let schema = json_schema();
+        let article = &schema["$defs"]["Article"];
+        assert_eq!(article["anyOf"].as_array().map(Vec::len), Some(2));
+    }
+
+    #[test]
+    fn test_enums_and_defaults_are_exported() {
+        let schema = json_schema();
+        let defs = &schema["$defs"];
+        assert!(defs["SidebarType"].to_string().contains("bestpractice"));
+        assert!(defs["ExerciseDifficulty"].to_string().contains("expert"));
+        assert!(defs["RagChunkStrategy"]
+            .to_string()
+            .contains("by_integration_type"));
+        assert_eq!(
+            defs["LlmRagHints"]["properties"]["max_chunk_size"]["default"],
+            512
+        );
+        assert_eq!(
+            schema["required"],
+            serde_json::json!(["topic", "title", "description"])
+        );
+    }
+}
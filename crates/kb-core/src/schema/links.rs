@@ -0,0 +1,190 @@
+//! Cross-schema link checking
+//!
+//! Per-file validation cannot see other schemas, so references between them are
+//! checked here over the whole corpus: `related_topics` must name an existing
+//! topic, section `code_examples` must match an example title, and topics must be
+//! unique across files.
+
+use super::{Article, SchemaFile};
+use crate::suggest::closest_match;
+use crate::{Diagnostics, KbError};
+use std::collections::BTreeMap;
+
+/// Check references between schemas
+///
+/// Dangling `related_topics` are warnings (the link is simply not rendered);
+/// unmatched `code_examples` and duplicate topics are errors, since they silently
+/// drop content or overwrite another schema's outputs.
+pub fn check_links(files: &[SchemaFile]) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+
+    // First definition of each topic wins; later ones are reported
+    let mut topics: BTreeMap<&str, &SchemaFile> = BTreeMap::new();
+    for file in files {
+        let topic = file.schema.topic.as_str();
+        match topics.get(topic) {
+            Some(first) => diagnostics.error(KbError::validation(
+                &file.path,
+                file.spans.locate("topic"),
+                format!(
+                    "Duplicate topic '{}' is already defined in '{}'",
+                    topic, first.path
+                ),
+                "Topics must be unique across schemas; rename one of them",
+            )),
+            None => {
+                topics.insert(topic, file);
+            }
+        }
+    }
+
+    for file in files {
+        for (idx, related) in file.schema.related_topics.iter().enumerate() {
+            if topics.contains_key(related.as_str()) {
+                continue;
+            }
+            let suggestion = match closest_match(related, topics.keys().copied()) {
+                Some(candidate) => format!("Did you mean '{}'?", candidate),
+                None => "Remove the reference or add a schema with this topic".to_string(),
+            };
+            diagnostics.warning(KbError::validation(
+                &file.path,
+                file.spans.locate(&format!("related_topics[{}]", idx)),
+                format!("Related topic '{}' does not exist", related),
+                suggestion,
+            ));
+        }
+
+        let Some(Article::Structured(article)) = &file.schema.article else {
+            continue;
+        };
+        let titles: Vec<&str> = file
+            .schema
+            .examples
+            .iter()
+            .map(|e| e.title.as_str())
+            .collect();
+        for (section_idx, section) in article.sections.iter().enumerate() {
+            for (idx, example_ref) in section.code_examples.iter().enumerate() {
+                if titles.contains(&example_ref.as_str()) {
+                    continue;
+                }
+                let suggestion = match closest_match(example_ref, titles.iter().copied()) {
+                    Some(candidate) => format!("Did you mean '{}'?", candidate),
+                    None => "Code examples must match the title of an entry in `examples` exactly"
+                        .to_string(),
+                };
+                diagnostics.error(KbError::validation(
+                    &file.path,
+                    file.spans.locate(&format!(
+                        "article.sections[{}].code_examples[{}]",
+                        section_idx, idx
+                    )),
+                    format!(
+                        "Section '{}' references unknown example '{}'",
+                        section.title, example_ref
+                    ),
+                    suggestion,
+                ));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::SpanMap;
+
+    fn file(path: &str, yaml: &str) -> SchemaFile {
+        SchemaFile {
+            path: path.to_string(),
+            schema: serde_yaml::from_str(yaml).unwrap(),
+            spans: SpanMap::parse(yaml),
+            unknown_keys: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_valid_links() {
+        let files = vec![
+            file("a.yaml", "topic: calculus.derivative\ntitle: A\ndescription: A\nrelated_topics:\n  - calculus.integral\n"),
+            file("b.yaml", "topic: calculus.integral\ntitle: B\ndescription: B\nrelated_topics:\n  - calculus.derivative\n"),
+        ];
+
+        assert!(check_links(&files).is_empty());
+    }
+
+    #[test]
+    fn test_dangling_related_topic_suggests_closest() {
+        let files = vec![
+            file("a.yaml", "topic: calculus.derivative\ntitle: A\ndescription: A\nrelated_topics:\n  - calculus.integral\n  - calculus.integrals\n"),
+            file("b.yaml", "topic: calculus.integral\ntitle: B\ndescription: B\n"),
+        ];
+
+        let diagnostics = check_links(&files);
+        assert!(!diagnostics.has_errors());
+        let warning = diagnostics.warnings().next().unwrap();
+        assert!(warning
+            .to_string()
+            .contains("'calculus.integrals' does not exist"));
+        assert_eq!(
+            warning.help().as_deref(),
+            Some("Did you mean 'calculus.integral'?")
+        );
+        assert_eq!(warning.location().unwrap().1.line, 6);
+    }
+
+    #[test]
+    fn test_duplicate_topics() {
+        let files = vec![
+            file(
+                "a.yaml",
+                "topic: calculus.derivative\ntitle: A\ndescription: A\n",
+            ),
+            file(
+                "b.yaml",
+                "topic: calculus.derivative\ntitle: B\ndescription: B\n",
+            ),
+        ];
+
+        let diagnostics = check_links(&files);
+        assert_eq!(diagnostics.error_count(), 1);
+        let error = diagnostics.errors().next().unwrap();
+        assert_eq!(error.location().unwrap().0, "b.yaml");
+        assert!(error.to_string().contains("already defined in 'a.yaml'"));
+    }
+
+    #[test]
+    fn test_unmatched_section_code_example() {
+        let yaml = r#"topic: calculus.derivative
+title: A
+description: A
+examples:
+  - title: "Power Rule"
+    explanation: "d/dx x^n"
+    code:
+      python: "diff(x**2, x)"
+article:
+  introduction:
+    hook: "Derivatives"
+  sections:
+    - title: "Basics"
+      content: "Rules"
+      code_examples:
+        - "Power Rule"
+        - "Power rules"
+"#;
+        let diagnostics = check_links(&[file("a.yaml", yaml)]);
+
+        assert_eq!(diagnostics.error_count(), 1);
+        let error = diagnostics.errors().next().unwrap();
+        assert!(error
+            .to_string()
+            .contains("Section 'Basics' references unknown example 'Power rules'"));
+        assert_eq!(error.help().as_deref(), Some("Did you mean 'Power Rule'?"));
+        assert_eq!(error.location().unwrap().1.line, 17);
+    }
+}
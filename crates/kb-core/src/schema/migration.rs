@@ -0,0 +1,501 @@
+//! Schema versioning and migrations
+//!
+//! Every schema file records its format version in `metadata.schema_version`.
+//! When a file is loaded, the migrations registered in a [`Migrator`] upgrade the
+//! parsed YAML document to the current version before it is deserialized, so
+//! older files keep working. `kb migrate --write` applies the same migrations to
+//! the file text: each step edits only the lines it touches, so comments and
+//! block scalars are preserved.
+
+use super::SpanMap;
+use crate::{KbError, Result};
+use serde_yaml::{Mapping, Value};
+
+/// Version assumed for documents without `metadata.schema_version`
+pub const INITIAL_SCHEMA_VERSION: &str = "1.0";
+
+/// Version written by the current release
+pub const CURRENT_SCHEMA_VERSION: &str = "1.0";
+
+/// A single edit made by a migration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationStep {
+    /// Rename `from` to `to` in the mapping at `parent` (`""` is the document root)
+    ///
+    /// Skipped when `from` is absent or `to` already exists.
+    RenameKey {
+        parent: &'static str,
+        from: &'static str,
+        to: &'static str,
+    },
+}
+
+/// Upgrade from one schema version to the next
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    pub from: &'static str,
+    pub to: &'static str,
+
+    /// What the migration changes, shown by `kb migrate`
+    pub description: &'static str,
+
+    pub steps: Vec<MigrationStep>,
+}
+
+/// Result of migrating a schema file's source text
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigratedSource {
+    /// Version the file declared (or [`INITIAL_SCHEMA_VERSION`])
+    pub from_version: String,
+
+    /// Descriptions of the migrations applied, oldest first
+    pub applied: Vec<&'static str>,
+
+    /// Rewritten file content
+    pub source: String,
+}
+
+/// Result of migrating a parsed document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MigratedValue<'a> {
+    /// Migrations the document's version called for, oldest first
+    pub chain: &'a [Migration],
+
+    /// Whether any step changed a field; stamping `schema_version` does not count
+    pub edited: bool,
+}
+
+/// Registry of schema migrations, chained by version
+#[derive(Debug, Clone)]
+pub struct Migrator {
+    migrations: Vec<Migration>,
+}
+
+impl Default for Migrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Migrator {
+    /// Create a migrator with every built-in migration registered
+    ///
+    /// `1.0` is still the only schema version, so none are registered yet; a new
+    /// format registers its upgrade from the previous version here.
+    pub fn new() -> Self {
+        Self {
+            migrations: Vec::new(),
+        }
+    }
+
+    /// Register a migration; it must start from the latest registered version
+    pub fn register(&mut self, migration: Migration) {
+        assert_eq!(
+            migration.from,
+            self.latest_version(),
+            "migrations must be registered in version order"
+        );
+        self.migrations.push(migration);
+    }
+
+    /// Version documents are upgraded to
+    pub fn latest_version(&self) -> &'static str {
+        self.migrations
+            .last()
+            .map_or(INITIAL_SCHEMA_VERSION, |migration| migration.to)
+    }
+
+    /// Migrations needed to upgrade a document at `version`, oldest first
+    ///
+    /// Returns `None` for versions this release does not know, including newer ones.
+    pub fn chain(&self, version: &str) -> Option<&[Migration]> {
+        if version == self.latest_version() {
+            return Some(&[]);
+        }
+        let start = self
+            .migrations
+            .iter()
+            .position(|migration| migration.from == version)?;
+        Some(&self.migrations[start..])
+    }
+
+    /// Upgrade a parsed YAML document in place
+    ///
+    /// `file` and `spans` locate the error for an unsupported version.
+    pub fn migrate_value(
+        &self,
+        document: &mut Value,
+        file: &str,
+        spans: &SpanMap,
+    ) -> Result<MigratedValue<'_>> {
+        let version = document_version(document);
+        let chain = self
+            .chain(&version)
+            .ok_or_else(|| self.unsupported(&version, file, spans))?;
+
+        let mut edited = false;
+        for migration in chain {
+            for step in &migration.steps {
+                edited |= step.apply_to_value(document);
+            }
+        }
+        if !chain.is_empty() {
+            if let Some(metadata) = document.get_mut("metadata").and_then(Value::as_mapping_mut) {
+                metadata.insert("schema_version".into(), self.latest_version().into());
+            }
+        }
+        Ok(MigratedValue { chain, edited })
+    }
+
+    /// Apply the steps of `chain` to source text, leaving `schema_version` as it is
+    ///
+    /// Every step edits within a line, so positions in the result are those of `source`.
+    fn migrate_fields_in_source(&self, source: &str, chain: &[Migration]) -> String {
+        let mut text = source.to_string();
+        for migration in chain {
+            for step in &migration.steps {
+                text = step.apply_to_source(&text, &SpanMap::parse(&text));
+            }
+        }
+        text
+    }
+
+    /// Upgrade a schema file's source text, or `None` if it is already current
+    ///
+    /// The rewritten text is parsed again and must match the in-memory migration
+    /// of the original, so a step that cannot be applied textually fails instead
+    /// of silently changing the document.
+    pub fn migrate_source(&self, source: &str, file: &str) -> Result<Option<MigratedSource>> {
+        let spans = SpanMap::parse(source);
+        let mut expected: Value =
+            serde_yaml::from_str(source).map_err(|err| KbError::yaml(file, &err))?;
+        let version = document_version(&expected);
+        let chain = self.migrate_value(&mut expected, file, &spans)?.chain;
+        if chain.is_empty() {
+            return Ok(None);
+        }
+
+        let text = set_source_version(
+            &self.migrate_fields_in_source(source, chain),
+            self.latest_version(),
+        );
+
+        // A file without metadata gains a `metadata.schema_version` entry
+        if let Some(root) = expected.as_mapping_mut() {
+            if !root.contains_key("metadata") {
+                let mut metadata = Mapping::new();
+                metadata.insert("schema_version".into(), self.latest_version().into());
+                root.insert("metadata".into(), metadata.into());
+            }
+        }
+
+        let rewritten: Value = serde_yaml::from_str(&text)?;
+        if rewritten != expected {
+            return Err(KbError::Other(format!(
+                "Migrating '{}' from schema_version {} changed more than the migrations describe; migrate it by hand",
+                file, version
+            )));
+        }
+
+        Ok(Some(MigratedSource {
+            from_version: version,
+            applied: chain
+                .iter()
+                .map(|migration| migration.description)
+                .collect(),
+            source: text,
+        }))
+    }
+
+    fn unsupported(&self, version: &str, file: &str, spans: &SpanMap) -> KbError {
+        let mut known: Vec<&str> = self
+            .migrations
+            .iter()
+            .map(|migration| migration.from)
+            .collect();
+        known.push(self.latest_version());
+        KbError::validation(
+            file,
+            spans.locate("metadata.schema_version"),
+            format!("Unsupported schema_version '{}'", version),
+            format!(
+                "Supported versions are {}; a newer version needs a newer kb release",
+                known.join(", ")
+            ),
+        )
+    }
+}
+
+impl MigrationStep {
+    /// Apply the step to a parsed document, returning whether it changed anything
+    fn apply_to_value(&self, document: &mut Value) -> bool {
+        match self {
+            Self::RenameKey { parent, from, to } => {
+                let Some(mapping) = value_at_mut(document, parent).and_then(Value::as_mapping_mut)
+                else {
+                    return false;
+                };
+                if mapping.contains_key(*to) {
+                    return false;
+                }
+                match mapping.remove(*from) {
+                    Some(value) => {
+                        mapping.insert((*to).into(), value);
+                        true
+                    }
+                    None => false,
+                }
+            }
+        }
+    }
+
+    fn apply_to_source(&self, source: &str, spans: &SpanMap) -> String {
+        match self {
+            Self::RenameKey { parent, from, to } => {
+                if spans.get(&join_path(parent, to)).is_some() {
+                    return source.to_string();
+                }
+                let Some(span) = spans.get(&join_path(parent, from)) else {
+                    return source.to_string();
+                };
+                edit_line(source, span.line, |line| {
+                    let start = span.column - 1;
+                    let quoted = line[start..].starts_with(['"', '\'']) as usize;
+                    let key_start = start + quoted;
+                    format!(
+                        "{}{}{}",
+                        &line[..key_start],
+                        to,
+                        &line[key_start + from.len()..]
+                    )
+                })
+            }
+        }
+    }
+}
+
+/// `metadata.schema_version` of a parsed document, as a string
+fn document_version(document: &Value) -> String {
+    match document
+        .get("metadata")
+        .and_then(|metadata| metadata.get("schema_version"))
+    {
+        Some(Value::String(version)) => version.clone(),
+        Some(Value::Number(version)) => version.to_string(),
+        _ => INITIAL_SCHEMA_VERSION.to_string(),
+    }
+}
+
+/// Value at a dotted mapping path (`""` is the document itself)
+fn value_at_mut<'a>(document: &'a mut Value, path: &str) -> Option<&'a mut Value> {
+    if path.is_empty() {
+        return Some(document);
+    }
+    path.split('.')
+        .try_fold(document, |value, key| value.get_mut(key))
+}
+
+fn join_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+/// Write `metadata.schema_version` into the source, adding the field if needed
+fn set_source_version(source: &str, version: &str) -> String {
+    let spans = SpanMap::parse(source);
+
+    if let Some(span) = spans.get("metadata.schema_version") {
+        return edit_line(source, span.line, |line| {
+            let colon = span.column - 1 + line[span.column - 1..].find(':').unwrap_or(0);
+            let value_start =
+                colon + 1 + (line.len() - colon - 1 - line[colon + 1..].trim_start().len());
+            let value = &line[value_start..];
+            let (quote, value_end) = match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => (
+                    quote,
+                    value[1..].find(quote).map_or(value.len(), |end| end + 2),
+                ),
+                _ => ('"', value.find(" #").unwrap_or(value.trim_end().len())),
+            };
+            format!(
+                "{} {quote}{}{quote}{}",
+                &line[..=colon],
+                version,
+                &value[value_end..]
+            )
+        });
+    }
+
+    if let Some(span) = spans.get("metadata") {
+        // Match the indentation of the existing metadata fields
+        let indent = source
+            .lines()
+            .skip(span.line)
+            .find(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|line| line.len() - line.trim_start().len())
+            .filter(|&indent| indent >= span.column)
+            .unwrap_or(span.column + 1);
+        return edit_line(source, span.line, |line| {
+            format!(
+                "{}\n{}schema_version: \"{}\"",
+                line,
+                " ".repeat(indent),
+                version
+            )
+        });
+    }
+
+    let mut text = source.to_string();
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text.push_str(&format!("\nmetadata:\n  schema_version: \"{}\"\n", version));
+    text
+}
+
+/// Replace the content of a 1-based line, keeping its line ending
+fn edit_line(source: &str, line: usize, edit: impl FnOnce(&str) -> String) -> String {
+    let mut out = String::with_capacity(source.len() + 32);
+    let mut edit = Some(edit);
+    for (idx, raw) in source.split_inclusive('\n').enumerate() {
+        match (idx + 1 == line).then(|| edit.take()).flatten() {
+            Some(edit) => {
+                let content = raw.trim_end_matches(['\n', '\r']);
+                out.push_str(&edit(content));
+                out.push_str(&raw[content.len()..]);
+            }
+            None => out.push_str(raw),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Migrator with a 1.0 -> 1.1 step renaming `metadata.generated_at`
+    fn legacy_migrator() -> Migrator {
+        let mut migrator = Migrator::new();
+        migrator.register(Migration {
+            from: "1.0",
+            to: "1.1",
+            description: "Rename metadata.generated_at to metadata.last_updated",
+            steps: vec![MigrationStep::RenameKey {
+                parent: "metadata",
+                from: "generated_at",
+                to: "last_updated",
+            }],
+        });
+        migrator
+    }
+
+    #[test]
+    fn test_builtin_migrations_reach_current_version() {
+        assert_eq!(Migrator::new().latest_version(), CURRENT_SCHEMA_VERSION);
+        assert!(Migrator::new()
+            .migrate_source(LEGACY, "legacy.yaml")
+            .unwrap()
+            .is_none());
+    }
+
+    const LEGACY: &str = r#"# Derivative topic
+topic: "calculus.derivative"
+title: Derivative
+description: |
+  Computes derivatives.
+
+  generated_at: not a key, just text
+
+metadata:
+  schema_version: "1.0"   # bumped by kb migrate
+  source_file: "calculus/derivative.md"
+  generated_at: "2025-12-15T00:00:00Z"
+"#;
+
+    #[test]
+    fn test_migrate_value_renames_and_stamps_version() {
+        let mut document: Value = serde_yaml::from_str(LEGACY).unwrap();
+        let migrator = legacy_migrator();
+        let migrated = migrator
+            .migrate_value(&mut document, "legacy.yaml", &SpanMap::parse(LEGACY))
+            .unwrap();
+
+        assert_eq!(migrated.chain.len(), 1);
+        assert!(migrated.edited);
+        assert_eq!(
+            document["metadata"]["last_updated"],
+            Value::from("2025-12-15T00:00:00Z")
+        );
+        assert!(document["metadata"].get("generated_at").is_none());
+        assert_eq!(document["metadata"]["schema_version"], Value::from("1.1"));
+    }
+
+    #[test]
+    fn test_migrate_value_without_legacy_fields_is_not_edited() {
+        let source = "topic: t\nmetadata:\n  author: me\n";
+        let mut document: Value = serde_yaml::from_str(source).unwrap();
+        let migrator = legacy_migrator();
+        let migrated = migrator
+            .migrate_value(&mut document, "t.yaml", &SpanMap::parse(source))
+            .unwrap();
+
+        assert_eq!(migrated.chain.len(), 1);
+        assert!(!migrated.edited);
+    }
+
+    #[test]
+    fn test_migrate_source_preserves_comments_and_block_scalars() {
+        let migrated = legacy_migrator()
+            .migrate_source(LEGACY, "legacy.yaml")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(migrated.from_version, "1.0");
+        assert_eq!(
+            migrated.source,
+            LEGACY
+                .replace("\"1.0\"   # bumped", "\"1.1\"   # bumped")
+                .replace("  generated_at: \"2025", "  last_updated: \"2025")
+        );
+        assert!(legacy_migrator()
+            .migrate_source(&migrated.source, "legacy.yaml")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_migrate_source_adds_missing_version() {
+        let source = "topic: t\nmetadata:\n    author: me\n";
+        let migrated = legacy_migrator()
+            .migrate_source(source, "t.yaml")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            migrated.source,
+            "topic: t\nmetadata:\n    schema_version: \"1.1\"\n    author: me\n"
+        );
+
+        let migrated = legacy_migrator()
+            .migrate_source("topic: t", "t.yaml")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            migrated.source,
+            "topic: t\n\nmetadata:\n  schema_version: \"1.1\"\n"
+        );
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let source = "topic: t\nmetadata:\n  schema_version: \"9.0\"\n";
+        let err = Migrator::new()
+            .migrate_source(source, "t.yaml")
+            .unwrap_err();
+        assert!(err.to_string().contains("Unsupported schema_version '9.0'"));
+        assert_eq!(err.location().map(|(_, span)| span.line), Some(3));
+    }
+}
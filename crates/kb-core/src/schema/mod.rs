@@ -2,17 +2,34 @@
 ///
 /// This module defines the structure of documentation schemas following
 /// the Hybrid Option C approach: Core content (required) + Output hints (optional)
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-mod validation;
 mod article;
+mod consistency;
+mod json_schema;
+mod links;
+mod migration;
+mod spans;
+mod syntax;
+mod unknown_keys;
+mod validation;
 
-pub use validation::validate_schema;
 pub use article::*;
+pub use consistency::check_consistency;
+pub use json_schema::json_schema;
+pub use links::check_links;
+pub use migration::{
+    MigratedSource, MigratedValue, Migration, MigrationStep, Migrator, CURRENT_SCHEMA_VERSION,
+    INITIAL_SCHEMA_VERSION,
+};
+pub use spans::{Span, SpanMap};
+pub use unknown_keys::{check_unknown_keys, find_unknown_keys, Strictness, UnknownKey};
+pub use validation::{check_code_refs, diagnose_schema, validate_schema};
 
 /// Main schema structure for a documentation topic
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct Schema {
     /// Unique topic identifier (e.g., "calculus.derivative")
     pub topic: String,
@@ -69,7 +86,7 @@ pub struct Schema {
 }
 
 /// Code references for different language bindings
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct CodeReferences {
     /// Rust function path (e.g., "mathhook_core::calculus::derivative")
     pub rust: String,
@@ -84,7 +101,7 @@ pub struct CodeReferences {
 }
 
 /// A code example with multi-language support
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct Example {
     /// Example title (e.g., "Power Rule")
     pub title: String,
@@ -99,10 +116,35 @@ pub struct Example {
     /// Expected output (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expected_output: Option<String>,
+
+    /// How the Rust snippet is treated in the generated test crate
+    #[serde(default, skip_serializing_if = "DoctestMode::is_default")]
+    pub doctest: DoctestMode,
+}
+
+/// Doctest-style marker for a Rust example (`doctest: no_run`)
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DoctestMode {
+    /// Compile and run the snippet
+    #[default]
+    Run,
+
+    /// Compile the snippet but do not run it
+    NoRun,
+
+    /// Neither compile nor run the snippet
+    Ignore,
+}
+
+impl DoctestMode {
+    fn is_default(&self) -> bool {
+        *self == Self::Run
+    }
 }
 
 /// Code snippets in supported languages
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct CodeSnippets {
     /// Rust code (optional for non-Rust bindings)
     #[serde(default, skip_serializing_if = "String::is_empty")]
@@ -118,7 +160,7 @@ pub struct CodeSnippets {
 }
 
 /// Performance characteristics
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct Performance {
     /// Time complexity (e.g., "O(n)")
     #[serde(default, skip_serializing_if = "String::is_empty")]
@@ -129,12 +171,19 @@ pub struct Performance {
     pub typical_time: Option<String>,
 
     /// Benchmark data - accepts any format and converts to map
-    #[serde(default, deserialize_with = "deserialize_benchmarks", skip_serializing_if = "HashMap::is_empty")]
+    #[serde(
+        default,
+        deserialize_with = "deserialize_benchmarks",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    #[schemars(with = "serde_json::Value")]
     pub benchmarks: HashMap<String, String>,
 }
 
 /// Deserialize benchmarks flexibly - accepts map, sequence, or anything else
-fn deserialize_benchmarks<'de, D>(deserializer: D) -> std::result::Result<HashMap<String, String>, D::Error>
+fn deserialize_benchmarks<'de, D>(
+    deserializer: D,
+) -> std::result::Result<HashMap<String, String>, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
@@ -161,7 +210,7 @@ where
 }
 
 /// Interactive playground configuration
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct PlaygroundConfig {
     /// Enable playground
     #[serde(default = "default_true")]
@@ -175,7 +224,7 @@ pub struct PlaygroundConfig {
 }
 
 /// Output-specific hints for customization
-#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct OutputHints {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub jupyter: Option<JupyterHints>,
@@ -212,7 +261,7 @@ impl OutputHints {
 }
 
 /// Jupyter notebook specific hints
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct JupyterHints {
     #[serde(default)]
     pub include_interactive_plots: bool,
@@ -225,7 +274,7 @@ pub struct JupyterHints {
 }
 
 /// mdBook specific hints
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct MdBookHints {
     #[serde(default)]
     pub include_mathematical_proof: bool,
@@ -238,7 +287,7 @@ pub struct MdBookHints {
 }
 
 /// Vue SSR site specific hints
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct VueSiteHints {
     #[serde(default = "default_true")]
     pub include_live_demo: bool,
@@ -251,7 +300,7 @@ pub struct VueSiteHints {
 }
 
 /// API documentation specific hints
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct ApiDocsHints {
     #[serde(default = "default_true")]
     pub include_playground: bool,
@@ -261,23 +310,91 @@ pub struct ApiDocsHints {
 }
 
 /// LLM RAG specific hints
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct LlmRagHints {
-    /// Chunking strategy ("by_example", "by_section", "fixed_size")
-    #[serde(default = "default_chunk_strategy")]
-    pub chunk_strategy: String,
+    /// Chunking strategy
+    #[serde(default)]
+    pub chunk_strategy: RagChunkStrategy,
 
     /// Maximum chunk size in tokens
     #[serde(default = "default_chunk_size")]
     pub max_chunk_size: usize,
 
-    /// Embedding priority ("high", "medium", "low")
-    #[serde(default = "default_priority")]
-    pub embedding_priority: String,
+    /// Embedding priority
+    #[serde(default)]
+    pub embedding_priority: EmbeddingPriority,
+}
+
+/// How LLM-RAG output is split into chunks
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(transform = chunk_strategy_aliases)]
+pub enum RagChunkStrategy {
+    /// One chunk per example
+    ///
+    /// `by_category` and `by_integration_type` are older names for it.
+    #[default]
+    #[serde(alias = "by_category", alias = "by_integration_type")]
+    ByExample,
+
+    /// One chunk per article section
+    BySection,
+
+    /// Chunks of `max_chunk_size` tokens
+    FixedSize,
+}
+
+/// Accept the older names of [`RagChunkStrategy::ByExample`] in the JSON Schema too
+fn chunk_strategy_aliases(schema: &mut schemars::Schema) {
+    let variants = schema
+        .get_mut("oneOf")
+        .and_then(|variants| variants.as_array_mut());
+    for variant in variants.into_iter().flatten() {
+        if variant.get("const").and_then(|name| name.as_str()) == Some("by_example") {
+            let variant = variant.as_object_mut().expect("enum variants are objects");
+            variant.remove("const");
+            variant.insert(
+                "enum".to_string(),
+                serde_json::json!(["by_example", "by_category", "by_integration_type"]),
+            );
+        }
+    }
+}
+
+impl RagChunkStrategy {
+    /// Name as written in schema files
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ByExample => "by_example",
+            Self::BySection => "by_section",
+            Self::FixedSize => "fixed_size",
+        }
+    }
+}
+
+/// Retrieval priority of LLM-RAG chunks
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingPriority {
+    High,
+    #[default]
+    Medium,
+    Low,
+}
+
+impl EmbeddingPriority {
+    /// Name as written in schema files
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::High => "high",
+            Self::Medium => "medium",
+            Self::Low => "low",
+        }
+    }
 }
 
 /// Google Colab specific hints
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct ColabHints {
     #[serde(default)]
     pub include_gpu_example: bool,
@@ -287,7 +404,7 @@ pub struct ColabHints {
 }
 
 /// LaTeX specific hints
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct LatexHints {
     #[serde(default = "default_document_class")]
     pub document_class: String,
@@ -297,7 +414,7 @@ pub struct LatexHints {
 }
 
 /// Schema metadata
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct Metadata {
     /// Schema version
     #[serde(default = "default_schema_version")]
@@ -317,7 +434,7 @@ pub struct Metadata {
 }
 
 /// SEO metadata for search engine optimization
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct SeoMetadata {
     /// Primary SEO keywords (most important, 3-5 keywords)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
@@ -347,9 +464,9 @@ pub struct SeoMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub og_image: Option<String>,
 
-    /// Twitter card type ("summary", "summary_large_image")
-    #[serde(default = "default_twitter_card")]
-    pub twitter_card: String,
+    /// Twitter card type
+    #[serde(default)]
+    pub twitter_card: TwitterCard,
 
     /// Structured data type (Schema.org type, e.g., "TechArticle", "HowTo")
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -359,9 +476,9 @@ pub struct SeoMetadata {
     #[serde(default = "default_priority_value")]
     pub priority: f32,
 
-    /// Update frequency for sitemap ("always", "hourly", "daily", "weekly", "monthly", "yearly", "never")
-    #[serde(default = "default_change_frequency")]
-    pub change_frequency: String,
+    /// Update frequency for sitemap
+    #[serde(default)]
+    pub change_frequency: ChangeFrequency,
 
     /// Language code (e.g., "en", "en-US")
     #[serde(default = "default_language")]
@@ -372,23 +489,67 @@ pub struct SeoMetadata {
     pub alternate_languages: HashMap<String, String>,
 }
 
+/// Twitter card layout for social sharing
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TwitterCard {
+    #[default]
+    Summary,
+    SummaryLargeImage,
+    App,
+    Player,
+}
+
+impl TwitterCard {
+    /// Name as written in schema files and `twitter:card` meta tags
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Summary => "summary",
+            Self::SummaryLargeImage => "summary_large_image",
+            Self::App => "app",
+            Self::Player => "player",
+        }
+    }
+}
+
+/// Sitemap `<changefreq>` value
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeFrequency {
+    Always,
+    Hourly,
+    Daily,
+    Weekly,
+    #[default]
+    Monthly,
+    Yearly,
+    Never,
+}
+
+impl ChangeFrequency {
+    /// Name as written in schema files and sitemaps
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::Hourly => "hourly",
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+            Self::Yearly => "yearly",
+            Self::Never => "never",
+        }
+    }
+}
+
 // Default value functions
 fn default_true() -> bool {
     true
 }
 
-fn default_chunk_strategy() -> String {
-    "by_example".to_string()
-}
-
 fn default_chunk_size() -> usize {
     512
 }
 
-fn default_priority() -> String {
-    "medium".to_string()
-}
-
 fn default_install_command() -> String {
     "!pip install mathhook".to_string()
 }
@@ -398,32 +559,160 @@ fn default_document_class() -> String {
 }
 
 fn default_schema_version() -> String {
-    "1.0".to_string()
-}
-
-fn default_twitter_card() -> String {
-    "summary".to_string()
+    CURRENT_SCHEMA_VERSION.to_string()
 }
 
 fn default_priority_value() -> f32 {
     0.5
 }
 
-fn default_change_frequency() -> String {
-    "monthly".to_string()
-}
-
 fn default_language() -> String {
     "en".to_string()
 }
 
+/// A parsed schema together with its file path and field positions
+#[derive(Debug, Clone)]
+pub struct SchemaFile {
+    /// Path of the schema file, as used in diagnostics
+    pub path: String,
+
+    pub schema: Schema,
+
+    pub spans: SpanMap,
+
+    /// Keys no schema field accepts, reported by [`check_unknown_keys`]
+    pub unknown_keys: Vec<UnknownKey>,
+}
+
+impl SchemaFile {
+    /// Parse a schema file without validating it
+    pub fn parse(path: &std::path::Path) -> crate::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Self::parse_str(&content, &path.to_string_lossy())
+    }
+
+    /// Parse schema source text, resolving `$include`s and upgrading older schema versions in memory
+    ///
+    /// `path` locates errors and is the base for relative `$include` paths.
+    pub fn parse_str(content: &str, path: &str) -> crate::Result<Self> {
+        Self::parse_str_with(content, path, &Migrator::new())
+    }
+
+    /// [`SchemaFile::parse_str`] upgrading older schema versions with `migrator`
+    pub(crate) fn parse_str_with(
+        content: &str,
+        path: &str,
+        migrator: &Migrator,
+    ) -> crate::Result<Self> {
+        let spans = SpanMap::parse(content);
+        let document: serde_yaml::Value =
+            serde_yaml::from_str(content).map_err(|err| crate::KbError::yaml(path, &err))?;
+        let resolved =
+            crate::parser::resolve_includes(document, std::path::Path::new(path), &spans)?;
+        let mut document = resolved.document;
+        let migrated = migrator.migrate_value(&mut document, path, &spans)?;
+        let unknown_keys = find_unknown_keys(&document);
+
+        // Deserializing text rather than the document keeps line numbers in type
+        // errors, and lets plain scalars such as `expected_output: 42` fill strings.
+        // A document changed by includes or migrations is written out first.
+        let mut schema: Schema = if resolved.files.is_empty() && !migrated.edited {
+            serde_yaml::from_str(content).map_err(|err| crate::KbError::yaml(path, &err))?
+        } else {
+            let help = if resolved.files.is_empty() {
+                "Fix the value here"
+            } else {
+                "Fix the value here or in the fragment it is included from"
+            };
+            let text = serde_yaml::to_string(&document)?;
+            serde_yaml::from_str(&text)
+                .map_err(|err| locate_resolved_error(&err, &text, path, &spans, help))?
+        };
+        if !migrated.chain.is_empty() {
+            if let Some(metadata) = schema.metadata.as_mut() {
+                metadata.schema_version = migrator.latest_version().to_string();
+            }
+        }
+
+        Ok(Self {
+            path: path.to_string(),
+            schema,
+            spans,
+            unknown_keys,
+        })
+    }
+}
+
+/// Point an error in a rewritten schema document at the field of the schema file it concerns
+///
+/// Documents with `$include`s or migrated fields are deserialized from generated
+/// text; the field at the reported line of that text is located in the schema
+/// file, falling back to its nearest parent there.
+fn locate_resolved_error(
+    error: &serde_yaml::Error,
+    text: &str,
+    path: &str,
+    spans: &SpanMap,
+    help: &str,
+) -> crate::KbError {
+    let mut message = error.to_string();
+    let mut field = None;
+    if let Some(location) = error.location() {
+        message = message.replace(
+            &format!(" at line {} column {}", location.line(), location.column()),
+            "",
+        );
+        field = SpanMap::parse(text)
+            .path_at(location.line())
+            .map(str::to_string);
+    }
+    crate::KbError::validation(
+        path,
+        field.map_or_else(Span::default, |field| spans.locate(&field)),
+        message,
+        help,
+    )
+}
+
 impl Schema {
     /// Load a schema from a YAML file
+    ///
+    /// Unknown keys are tolerated; use [`Schema::load_from_file_with`] to reject them.
     pub fn load_from_file(path: &std::path::Path) -> crate::Result<Self> {
-        let content = std::fs::read_to_string(path)?;
-        let schema: Schema = serde_yaml::from_str(&content)?;
-        validate_schema(&schema, path.to_string_lossy().to_string())?;
-        Ok(schema)
+        Self::load_from_file_with(path, Strictness::Lenient)
+    }
+
+    /// Load and validate a schema, treating unknown keys according to `strictness`
+    pub fn load_from_file_with(
+        path: &std::path::Path,
+        strictness: Strictness,
+    ) -> crate::Result<Self> {
+        let file = SchemaFile::parse(path)?;
+        let mut diagnostics = diagnose_schema(&file.schema, &file.path, &file.spans);
+        diagnostics.extend(check_unknown_keys(&file, strictness.severity()));
+        diagnostics.into_result()?;
+        Ok(file.schema)
+    }
+
+    /// Parse a schema from a YAML file without validating it
+    ///
+    /// Use together with [`diagnose_schema`] to report every problem at once.
+    pub fn parse_file(path: &std::path::Path) -> crate::Result<Self> {
+        Ok(Self::parse_file_with_spans(path)?.0)
+    }
+
+    /// Parse a schema without validating it, keeping the position of each field
+    pub fn parse_file_with_spans(path: &std::path::Path) -> crate::Result<(Self, SpanMap)> {
+        let file = SchemaFile::parse(path)?;
+        Ok((file.schema, file.spans))
+    }
+
+    /// Parse schema source text, resolving `$include`s and upgrading older schema versions in memory
+    ///
+    /// `file` locates errors and is the base for relative `$include` paths.
+    pub fn parse_str_with_spans(content: &str, file: &str) -> crate::Result<(Self, SpanMap)> {
+        let file = SchemaFile::parse_str(content, file)?;
+        Ok((file.schema, file.spans))
     }
 
     /// Load multiple schemas from a directory
@@ -432,8 +721,13 @@ impl Schema {
 
         for entry in walkdir::WalkDir::new(path)
             .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !crate::parser::is_fragment(e.path()))
             .filter_map(|e| e.ok())
-            .filter(|e| e.path().extension().is_some_and(|ext| ext == "yaml" || ext == "yml"))
+            .filter(|e| {
+                e.path()
+                    .extension()
+                    .is_some_and(|ext| ext == "yaml" || ext == "yml")
+            })
         {
             schemas.push(Self::load_from_file(entry.path())?);
         }
@@ -525,4 +819,192 @@ outputs:
             1024
         );
     }
+
+    /// Migrator renaming `metadata.generated_at` to `metadata.last_updated` in 1.1
+    fn legacy_migrator() -> Migrator {
+        let mut migrator = Migrator::new();
+        migrator.register(Migration {
+            from: "1.0",
+            to: "1.1",
+            description: "Rename metadata.generated_at to metadata.last_updated",
+            steps: vec![MigrationStep::RenameKey {
+                parent: "metadata",
+                from: "generated_at",
+                to: "last_updated",
+            }],
+        });
+        migrator
+    }
+
+    #[test]
+    fn test_legacy_schema_is_migrated_on_load() {
+        let yaml = r#"
+topic: "test.function"
+title: "Test Function"
+description: "A test function"
+metadata:
+  schema_version: "1.0"
+  generated_at: "2025-12-15T00:00:00Z"
+"#;
+
+        let file = SchemaFile::parse_str_with(yaml, "test.yaml", &legacy_migrator())
+            .expect("Failed to parse");
+        let metadata = file.schema.metadata.expect("metadata");
+        assert_eq!(metadata.schema_version, "1.1");
+        assert_eq!(
+            metadata.last_updated.as_deref(),
+            Some("2025-12-15T00:00:00Z")
+        );
+
+        let (schema, _) = Schema::parse_str_with_spans(yaml, "test.yaml").expect("Failed to parse");
+        assert_eq!(
+            schema.metadata.expect("metadata").schema_version,
+            CURRENT_SCHEMA_VERSION
+        );
+    }
+
+    #[test]
+    fn test_unversioned_schema_keeps_text_deserialization() {
+        let yaml = r#"topic: "test.function"
+title: "Test Function"
+description: "A test function"
+examples:
+  - title: Answer
+    code:
+      python: "print(42)"
+    expected_output: 42
+"#;
+        let (schema, _) = Schema::parse_str_with_spans(yaml, "test.yaml").expect("Failed to parse");
+        assert_eq!(schema.examples[0].expected_output.as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn test_migrated_schema_is_deserialized_from_the_document() {
+        let yaml = r#"topic: "test.function"
+title: "Test Function"
+description: "A test function"
+examples:
+  - title: Answer
+    code:
+      python: "print(42)"
+    expected_output: 42
+metadata:
+  generated_at: "2025-12-15"
+"#;
+        let file = SchemaFile::parse_str_with(yaml, "test.yaml", &legacy_migrator())
+            .expect("Failed to parse");
+        assert_eq!(
+            file.schema.examples[0].expected_output.as_deref(),
+            Some("42")
+        );
+        assert_eq!(
+            file.schema.metadata.unwrap().last_updated.as_deref(),
+            Some("2025-12-15")
+        );
+
+        let invalid = yaml.replace("title: Answer", "title: {a: b}");
+        let err =
+            SchemaFile::parse_str_with(&invalid, "test.yaml", &legacy_migrator()).unwrap_err();
+        assert_eq!(
+            err.location().map(|(file, span)| (file, span.line)),
+            Some(("test.yaml", 5))
+        );
+    }
+
+    #[test]
+    fn test_yaml_errors_point_at_the_file() {
+        let err = Schema::parse_str_with_spans("topic: test\ntitle: [unclosed\n", "bad.yaml")
+            .unwrap_err();
+        assert!(
+            matches!(err, crate::KbError::YamlSyntax { .. }),
+            "{:?}",
+            err
+        );
+        assert_eq!(
+            err.location().map(|(file, span)| (file, span.line)),
+            Some(("bad.yaml", 3))
+        );
+
+        let yaml = "topic: test\ntitle: Test\ndescription: Test\nexamples: 3\n";
+        let err = Schema::parse_str_with_spans(yaml, "bad.yaml").unwrap_err();
+        assert_eq!(
+            err.location().map(|(file, span)| (file, span.line)),
+            Some(("bad.yaml", 4))
+        );
+    }
+
+    #[test]
+    fn test_included_values_are_coerced_and_errors_located() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("_example.yaml"),
+            "title: Answer\ncode:\n  python: \"print(42)\"\nexpected_output: 42\n",
+        )
+        .unwrap();
+        let path = dir.path().join("test.yaml");
+        let path = path.to_str().unwrap();
+        let yaml = "topic: test.function\ntitle: Test\ndescription: A test\nexamples:\n  - $include: _example.yaml\n";
+
+        let (schema, _) = Schema::parse_str_with_spans(yaml, path).expect("Failed to parse");
+        assert_eq!(schema.examples[0].expected_output.as_deref(), Some("42"));
+
+        let err =
+            Schema::parse_str_with_spans(&format!("{}related_topics: {{a: b}}\n", yaml), path)
+                .unwrap_err();
+        assert_eq!(err.location(), Some((path, Span::new(6, 1))));
+        assert!(
+            err.summary()
+                .starts_with("related_topics: invalid type: map"),
+            "{}",
+            err.summary()
+        );
+    }
+
+    #[test]
+    fn test_strict_loading_rejects_unknown_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("typo.yaml");
+        std::fs::write(
+            &path,
+            "topic: test.function\ntitle: Test\ndescription: A test\nreleated_topics: [a]\nuse_cases: [x]\n",
+        )
+        .unwrap();
+
+        assert!(Schema::load_from_file(&path).is_ok());
+        let err = Schema::load_from_file_with(&path, Strictness::Strict).unwrap_err();
+        assert_eq!(
+            err.help().as_deref(),
+            Some("Did you mean 'related_topics'?")
+        );
+        assert_eq!(err.location().map(|(_, span)| span.line), Some(4));
+    }
+
+    #[test]
+    fn test_invalid_hint_value_lists_allowed_values() {
+        let yaml = r#"
+topic: "test.function"
+title: "Test Function"
+description: "A test function"
+outputs:
+  llm_rag:
+    chunk_strategy: "by_paragraph"
+"#;
+
+        let err = serde_yaml::from_str::<Schema>(yaml)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unknown variant `by_paragraph`"), "{}", err);
+        assert!(
+            err.contains("`by_example`, `by_integration_type`, `by_section`"),
+            "{}",
+            err
+        );
+
+        let hints: LlmRagHints = serde_yaml::from_str("chunk_strategy: by_category").unwrap();
+        assert_eq!(hints.chunk_strategy, RagChunkStrategy::ByExample);
+
+        let hints: LlmRagHints = serde_yaml::from_str("embedding_priority: low").unwrap();
+        assert_eq!(hints.chunk_strategy, RagChunkStrategy::ByExample);
+        assert_eq!(hints.embedding_priority, EmbeddingPriority::Low);
+    }
 }
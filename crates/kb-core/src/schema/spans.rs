@@ -0,0 +1,406 @@
+//! Source positions for schema fields
+//!
+//! `serde_yaml` does not expose node positions, so schema files are scanned a
+//! second time to map field paths (e.g., `examples[0].code.rust`) to the line and
+//! column where they appear. The scanner understands the block-style YAML used by
+//! schema files: nested mappings, `- ` sequences, inline scalars and `|`/`>`
+//! block scalars. Anything it cannot place falls back to the nearest parent field.
+
+use std::collections::HashMap;
+
+/// A 1-based line/column position in a schema file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+}
+
+impl Default for Span {
+    fn default() -> Self {
+        Self::new(1, 1)
+    }
+}
+
+/// Where a field's value starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ValueStart {
+    /// Position of the first character of the value content
+    span: Span,
+
+    /// Block scalar (`|` or `>`), where value line N is file line `span.line + N - 1`
+    block: bool,
+}
+
+/// Map from schema field paths to their positions in the YAML source
+///
+/// Paths use `.` between mapping keys and `[n]` for sequence items, matching
+/// the field names of [`Schema`](super::Schema): `outputs.llm_rag.chunk_strategy`,
+/// `examples[2].code.python`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpanMap {
+    keys: HashMap<String, Span>,
+    values: HashMap<String, ValueStart>,
+}
+
+/// An open mapping key or sequence item while scanning
+struct Node {
+    indent: usize,
+    path: String,
+    is_item: bool,
+    items: usize,
+
+    /// Inline scalar value; deeper lines are continuation lines
+    scalar: bool,
+}
+
+/// A block scalar being skipped over
+struct Block {
+    path: String,
+    parent_indent: usize,
+    header_line: usize,
+}
+
+impl SpanMap {
+    /// Scan YAML source text
+    pub fn parse(source: &str) -> Self {
+        let mut map = Self::default();
+        let mut stack: Vec<Node> = Vec::new();
+        let mut block: Option<Block> = None;
+
+        for (idx, raw) in source.lines().enumerate() {
+            let line = idx + 1;
+            let indent = raw.len() - raw.trim_start_matches(' ').len();
+            let content = raw[indent..].trim_end();
+
+            if let Some(open) = &block {
+                if content.is_empty() {
+                    continue;
+                }
+                if indent > open.parent_indent {
+                    // First content line fixes the block indentation
+                    map.values.entry(open.path.clone()).or_insert(ValueStart {
+                        span: Span::new(open.header_line + 1, indent + 1),
+                        block: true,
+                    });
+                    continue;
+                }
+                block = None;
+            }
+
+            if content.is_empty() || content.starts_with('#') || content == "---" {
+                continue;
+            }
+
+            // Continuation of a multi-line inline scalar
+            if stack
+                .last()
+                .is_some_and(|top| top.scalar && indent > top.indent)
+            {
+                continue;
+            }
+
+            let mut column = indent;
+            let mut rest = content;
+
+            if rest == "-" || rest.starts_with("- ") {
+                while stack
+                    .last()
+                    .is_some_and(|top| top.indent > column || (top.indent == column && top.is_item))
+                {
+                    stack.pop();
+                }
+                let path = match stack.last_mut() {
+                    Some(owner) => {
+                        owner.items += 1;
+                        format!("{}[{}]", owner.path, owner.items - 1)
+                    }
+                    None => continue,
+                };
+                map.keys.insert(path.clone(), Span::new(line, column + 1));
+
+                let after = rest[1..].trim_start();
+                let offset = rest.len() - after.len();
+                column += offset;
+                rest = after;
+
+                let scalar_item = !rest.is_empty() && split_key(rest).is_none();
+                if scalar_item {
+                    map.values.insert(
+                        path.clone(),
+                        ValueStart {
+                            span: Span::new(line, column + 1),
+                            block: false,
+                        },
+                    );
+                }
+                stack.push(Node {
+                    indent: column - offset,
+                    path,
+                    is_item: true,
+                    items: 0,
+                    scalar: scalar_item,
+                });
+                if scalar_item || rest.is_empty() {
+                    continue;
+                }
+            }
+
+            let Some((key, value_offset)) = split_key(rest) else {
+                continue;
+            };
+
+            while stack.last().is_some_and(|top| top.indent >= column) {
+                stack.pop();
+            }
+            let path = match stack.last() {
+                Some(parent) => format!("{}.{}", parent.path, key),
+                None => key.to_string(),
+            };
+            map.keys.insert(path.clone(), Span::new(line, column + 1));
+
+            let value = strip_comment(rest[value_offset..].trim_start());
+            let value_column = column + rest.len() - rest[value_offset..].trim_start().len();
+            let is_block = value.starts_with('|') || value.starts_with('>');
+
+            if is_block {
+                block = Some(Block {
+                    path: path.clone(),
+                    parent_indent: column,
+                    header_line: line,
+                });
+            } else if !value.is_empty() {
+                let quote = usize::from(value.starts_with(['"', '\'']));
+                map.values.insert(
+                    path.clone(),
+                    ValueStart {
+                        span: Span::new(line, value_column + quote + 1),
+                        block: false,
+                    },
+                );
+            }
+
+            stack.push(Node {
+                indent: column,
+                path,
+                is_item: false,
+                items: 0,
+                scalar: !value.is_empty() && !is_block,
+            });
+        }
+
+        map
+    }
+
+    /// Position of a field's key, if it was found
+    pub fn get(&self, path: &str) -> Option<Span> {
+        self.keys.get(path).copied()
+    }
+
+    /// Position of a field, falling back to its nearest located parent
+    pub fn locate(&self, path: &str) -> Span {
+        let mut current = path;
+        loop {
+            if let Some(span) = self.get(current) {
+                return span;
+            }
+            match parent_path(current) {
+                Some(parent) => current = parent,
+                None => return Span::default(),
+            }
+        }
+    }
+
+    /// Path of the innermost field whose key is the last one at or before `line`
+    ///
+    /// Maps a position reported for the source (e.g. by serde_yaml) back to a field.
+    pub fn path_at(&self, line: usize) -> Option<&str> {
+        self.keys
+            .iter()
+            .filter(|(_, span)| span.line <= line)
+            .max_by_key(|(path, span)| (span.line, span.column, path.len()))
+            .map(|(path, _)| path.as_str())
+    }
+
+    /// Line of a field, falling back to its nearest located parent
+    pub fn line(&self, path: &str) -> usize {
+        self.locate(path).line
+    }
+
+    /// Position of a 1-based line/column offset inside a field's value
+    ///
+    /// For block scalars this maps a position inside a multi-line code snippet
+    /// back to the schema file. Quoted inline values are offset past the quote.
+    pub fn locate_in_value(&self, path: &str, line: usize, column: usize) -> Span {
+        let Some(value) = self.values.get(path) else {
+            return self.locate(path);
+        };
+
+        if value.block {
+            Span::new(
+                value.span.line + line.saturating_sub(1),
+                value.span.column + column.saturating_sub(1),
+            )
+        } else {
+            // Escapes and folded lines are not tracked; only the first line is mapped exactly
+            Span::new(
+                value.span.line,
+                value.span.column + column.saturating_sub(1),
+            )
+        }
+    }
+}
+
+/// Split `key: value` into the key and the offset of the value
+fn split_key(content: &str) -> Option<(&str, usize)> {
+    let (key, after_key) = if let Some(quote @ ('"' | '\'')) = content.chars().next() {
+        let end = content[1..].find(quote)? + 1;
+        (&content[1..end], end + 1)
+    } else {
+        if content.starts_with(['[', '{', '&', '*', '!', '|', '>']) {
+            return None;
+        }
+        let end = content
+            .char_indices()
+            .find(|&(i, c)| {
+                c == ':'
+                    && content[i + 1..]
+                        .chars()
+                        .next()
+                        .is_none_or(char::is_whitespace)
+            })
+            .map(|(i, _)| i)?;
+        (content[..end].trim_end(), end)
+    };
+
+    let colon = content[after_key..].strip_prefix(':')?;
+    if !colon.is_empty() && !colon.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some((key, after_key + 1))
+}
+
+/// Strip a trailing `# comment` from an unquoted inline value
+fn strip_comment(value: &str) -> &str {
+    if value.starts_with(['"', '\'']) {
+        return value;
+    }
+    match value.find(" #") {
+        Some(idx) => value[..idx].trim_end(),
+        None if value.starts_with('#') => "",
+        None => value,
+    }
+}
+
+/// Parent of a field path (`a.b[1].c` -> `a.b[1]` -> `a.b` -> `a`)
+fn parent_path(path: &str) -> Option<&str> {
+    if let Some(stripped) = path.strip_suffix(']') {
+        return stripped.rfind('[').map(|idx| &path[..idx]);
+    }
+    path.rfind('.').map(|idx| &path[..idx])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"# Comment header
+topic: "calculus.derivative"
+title: Derivative   # trailing comment
+description: |
+  Multi-line description
+  with a second line
+
+examples:
+  - title: "Power Rule"
+    code:
+      rust: |
+        let x = symbol!(x);
+
+        let f = expr!(x ^ 3;
+      python: "f = x**3"
+  - title: Second
+    explanation: >
+      Folded text
+      continues here
+    code:
+      rust: "let y = 1;"
+
+related_topics:
+- calculus.integral
+- calculus.limits
+
+outputs:
+  llm_rag:
+    chunk_strategy: by_category
+"#;
+
+    #[test]
+    fn test_locates_nested_fields() {
+        let spans = SpanMap::parse(SOURCE);
+
+        assert_eq!(spans.get("topic"), Some(Span::new(2, 1)));
+        assert_eq!(spans.get("title"), Some(Span::new(3, 1)));
+        assert_eq!(spans.get("examples[0]"), Some(Span::new(9, 3)));
+        assert_eq!(spans.get("examples[0].title"), Some(Span::new(9, 5)));
+        assert_eq!(spans.get("examples[0].code.rust"), Some(Span::new(11, 7)));
+        assert_eq!(spans.get("examples[0].code.python"), Some(Span::new(15, 7)));
+        assert_eq!(spans.get("examples[1].title"), Some(Span::new(16, 5)));
+        assert_eq!(spans.get("examples[1].code.rust"), Some(Span::new(21, 7)));
+        assert_eq!(spans.get("related_topics[1]"), Some(Span::new(25, 1)));
+        assert_eq!(
+            spans.get("outputs.llm_rag.chunk_strategy"),
+            Some(Span::new(29, 5))
+        );
+
+        // Block scalar content and folded continuation lines are not fields
+        assert_eq!(spans.get("with a second line"), None);
+        assert_eq!(spans.get("examples[1].explanation.continues here"), None);
+    }
+
+    #[test]
+    fn test_locate_falls_back_to_parent() {
+        let spans = SpanMap::parse(SOURCE);
+
+        assert_eq!(spans.locate("examples[1].code.nodejs"), Span::new(20, 5));
+        assert_eq!(spans.line("examples[7].title"), 8);
+        assert_eq!(spans.locate("seo.keywords"), Span::new(1, 1));
+    }
+
+    #[test]
+    fn test_path_at_line() {
+        let spans = SpanMap::parse(SOURCE);
+
+        assert_eq!(spans.path_at(9), Some("examples[0].title"));
+        assert_eq!(spans.path_at(13), Some("examples[0].code.rust"));
+        assert_eq!(spans.path_at(29), Some("outputs.llm_rag.chunk_strategy"));
+        assert_eq!(spans.path_at(1), None);
+    }
+
+    #[test]
+    fn test_locate_in_block_and_inline_values() {
+        let spans = SpanMap::parse(SOURCE);
+
+        // Line 3 of the Rust snippet, column 14 is the unclosed `(`
+        assert_eq!(
+            spans.locate_in_value("examples[0].code.rust", 3, 14),
+            Span::new(14, 22)
+        );
+        // Inline values start after the key (and opening quote)
+        assert_eq!(
+            spans.locate_in_value("examples[0].code.python", 1, 1),
+            Span::new(15, 16)
+        );
+        assert_eq!(spans.locate_in_value("title", 1, 1), Span::new(3, 8));
+        // Fields without a located value fall back to the key
+        assert_eq!(
+            spans.locate_in_value("examples[0].code.nodejs", 1, 1),
+            Span::new(10, 5)
+        );
+    }
+}
@@ -0,0 +1,777 @@
+//! Language-aware syntax checks for example snippets
+//!
+//! A small tokenizer per language skips string, character and comment content
+//! so only real delimiters are matched, and Python snippets are also checked for
+//! consistent indentation. This is not a parser: it catches the mistakes that
+//! slip into hand-written snippets (unbalanced delimiters, unterminated strings,
+//! broken indentation) without rejecting valid code. The same tokenizers find
+//! the comments [`strip_comments`] removes.
+//!
+//! Positions are 1-based line/column offsets within the snippet.
+
+use crate::runner::Language;
+
+/// First syntax problem found in a snippet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SyntaxError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl SyntaxError {
+    fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+type Result<T> = std::result::Result<T, SyntaxError>;
+
+/// Check a snippet written in `language`
+pub(crate) fn check_syntax(code: &str, language: Language) -> Result<()> {
+    scan(&mut Cursor::new(code), language)
+}
+
+/// Remove the comments of a snippet, keeping strings and line breaks intact
+///
+/// Comments after a syntax error are left in place.
+pub(crate) fn strip_comments(code: &str, language: Language) -> String {
+    let mut cursor = Cursor::new(code);
+    let _ = scan(&mut cursor, language);
+
+    let mut stripped = String::with_capacity(code.len());
+    let mut pos = 0;
+    for &(start, end) in &cursor.comments {
+        stripped.extend(&cursor.chars[pos..start]);
+        stripped.extend(cursor.chars[start..end].iter().filter(|c| **c == '\n'));
+        pos = end;
+    }
+    stripped.extend(&cursor.chars[pos..]);
+    stripped
+}
+
+fn scan(cursor: &mut Cursor, language: Language) -> Result<()> {
+    match language {
+        Language::Rust => check_rust(cursor),
+        Language::Python => check_python(cursor),
+        Language::NodeJs => check_javascript(cursor),
+    }
+}
+
+/// Character cursor tracking line and column
+struct Cursor {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    column: usize,
+
+    /// Character ranges of the comments passed so far
+    comments: Vec<(usize, usize)>,
+}
+
+impl Cursor {
+    fn new(code: &str) -> Self {
+        Self {
+            chars: code.chars().collect(),
+            pos: 0,
+            line: 1,
+            column: 1,
+            comments: Vec::new(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        s.chars()
+            .enumerate()
+            .all(|(i, c)| self.peek_at(i) == Some(c))
+    }
+
+    /// Whether the character just before the cursor is `c`
+    fn follows(&self, c: char) -> bool {
+        self.pos.checked_sub(1).and_then(|p| self.chars.get(p)) == Some(&c)
+    }
+
+    fn position(&self) -> (usize, usize) {
+        (self.line, self.column)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn bump_n(&mut self, n: usize) {
+        for _ in 0..n {
+            self.bump();
+        }
+    }
+
+    /// Skip a comment to the end of the line, leaving the newline in place
+    fn line_comment(&mut self) {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c != '\n') {
+            self.bump();
+        }
+        self.comments.push((start, self.pos));
+    }
+
+    fn identifier(&mut self) -> String {
+        let mut ident = String::new();
+        while let Some(c) = self.peek().filter(|c| is_ident_char(*c)) {
+            ident.push(c);
+            self.bump();
+        }
+        ident
+    }
+
+    /// Consume a quoted literal up to the closing `quote`, honouring backslash escapes
+    ///
+    /// The opening quote must already be consumed. With `multiline` false an
+    /// unescaped newline ends the literal with an error.
+    fn quoted(
+        &mut self,
+        quote: char,
+        multiline: bool,
+        start: (usize, usize),
+        what: &str,
+    ) -> Result<()> {
+        loop {
+            match self.bump() {
+                Some('\\') => {
+                    self.bump();
+                }
+                Some(c) if c == quote => return Ok(()),
+                Some('\n') if !multiline => {
+                    return Err(SyntaxError::new(
+                        start.0,
+                        start.1,
+                        format!("Unterminated {}", what),
+                    ));
+                }
+                Some(_) => {}
+                None => {
+                    return Err(SyntaxError::new(
+                        start.0,
+                        start.1,
+                        format!("Unterminated {}", what),
+                    ))
+                }
+            }
+        }
+    }
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Stack of open delimiters
+#[derive(Default)]
+struct Delimiters {
+    stack: Vec<(char, usize, usize)>,
+}
+
+impl Delimiters {
+    fn open(&mut self, c: char, (line, column): (usize, usize)) {
+        self.stack.push((c, line, column));
+    }
+
+    fn top(&self) -> Option<char> {
+        self.stack.last().map(|(c, ..)| *c)
+    }
+
+    fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    fn close(&mut self, c: char, (line, column): (usize, usize)) -> Result<()> {
+        let (expected, name) = match c {
+            ')' => ('(', "parenthesis"),
+            ']' => ('[', "bracket"),
+            _ => ('{', "brace"),
+        };
+
+        match self.stack.pop() {
+            Some((open, ..)) if open == expected => Ok(()),
+            Some((open, open_line, open_column)) => {
+                Err(SyntaxError::new(
+                    line,
+                    column,
+                    format!(
+                    "Mismatched closing {} '{}': '{}' opened at line {}, column {} is still open",
+                    name,
+                    c,
+                    if open == '$' { "${".to_string() } else { open.to_string() },
+                    open_line,
+                    open_column
+                ),
+                ))
+            }
+            None => Err(SyntaxError::new(
+                line,
+                column,
+                format!("Unmatched closing {}", name),
+            )),
+        }
+    }
+
+    fn finish(mut self) -> Result<()> {
+        match self.stack.pop() {
+            Some(('$', line, column)) => Err(SyntaxError::new(
+                line,
+                column,
+                "Unclosed '${' in template literal",
+            )),
+            Some((open, line, column)) => Err(SyntaxError::new(
+                line,
+                column,
+                format!("Unclosed delimiter: {:?}", open),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+fn check_rust(cursor: &mut Cursor) -> Result<()> {
+    let mut delimiters = Delimiters::default();
+
+    while let Some(c) = cursor.peek() {
+        let start = cursor.position();
+
+        if cursor.starts_with("//") {
+            cursor.line_comment();
+        } else if cursor.starts_with("/*") {
+            // Block comments nest in Rust
+            let from = cursor.pos;
+            cursor.bump_n(2);
+            let mut depth = 1;
+            while depth > 0 {
+                if cursor.starts_with("/*") {
+                    cursor.bump_n(2);
+                    depth += 1;
+                } else if cursor.starts_with("*/") {
+                    cursor.bump_n(2);
+                    depth -= 1;
+                } else if cursor.bump().is_none() {
+                    return Err(SyntaxError::new(
+                        start.0,
+                        start.1,
+                        "Unterminated block comment",
+                    ));
+                }
+            }
+            cursor.comments.push((from, cursor.pos));
+        } else if c == '"' {
+            cursor.bump();
+            cursor.quoted('"', true, start, "string literal")?;
+        } else if c == '\'' {
+            // Char literal ('a', '\n', '\u{1F600}') or lifetime/label ('a, 'static)
+            if cursor.peek_at(1) == Some('\\') {
+                cursor.bump();
+                cursor.quoted('\'', false, start, "character literal")?;
+            } else if cursor.peek_at(2) == Some('\'') {
+                cursor.bump_n(3);
+            } else {
+                cursor.bump();
+            }
+        } else if is_ident_start(c) {
+            let ident = cursor.identifier();
+            let raw = ident == "r" || ident == "br" || ident == "cr";
+            if raw && matches!(cursor.peek(), Some('"' | '#')) {
+                rust_raw_string(cursor, start)?;
+            } else if (ident == "b" || ident == "c") && cursor.peek() == Some('"') {
+                cursor.bump();
+                cursor.quoted('"', true, start, "string literal")?;
+            } else if ident == "b" && cursor.peek() == Some('\'') {
+                cursor.bump();
+                cursor.quoted('\'', false, start, "byte literal")?;
+            }
+        } else if c.is_ascii_digit() {
+            // Numbers may contain letters (`1e5`, `2_u32`) but never quotes
+            cursor.identifier();
+        } else {
+            match c {
+                '(' | '[' | '{' => delimiters.open(c, start),
+                ')' | ']' | '}' => delimiters.close(c, start)?,
+                _ => {}
+            }
+            cursor.bump();
+        }
+    }
+
+    delimiters.finish()
+}
+
+/// Raw string after its `r` prefix: `r"..."`, `r#"..."#`; `r#ident` is a raw identifier
+fn rust_raw_string(cursor: &mut Cursor, start: (usize, usize)) -> Result<()> {
+    let mut hashes = 0;
+    while cursor.peek_at(hashes) == Some('#') {
+        hashes += 1;
+    }
+    if cursor.peek_at(hashes) != Some('"') {
+        cursor.bump_n(hashes);
+        cursor.identifier();
+        return Ok(());
+    }
+    cursor.bump_n(hashes + 1);
+
+    let terminator: String = std::iter::once('"')
+        .chain(std::iter::repeat_n('#', hashes))
+        .collect();
+    while !cursor.starts_with(&terminator) {
+        if cursor.bump().is_none() {
+            return Err(SyntaxError::new(
+                start.0,
+                start.1,
+                "Unterminated raw string literal",
+            ));
+        }
+    }
+    cursor.bump_n(terminator.chars().count());
+    Ok(())
+}
+
+/// Indentation state of a Python snippet
+struct Indentation {
+    levels: Vec<usize>,
+    style: Option<char>,
+    expect_block: bool,
+}
+
+impl Indentation {
+    /// Check the indentation of a logical line starting at `line`
+    fn check(&mut self, indent: &str, line: usize) -> Result<()> {
+        let column = indent.chars().count() + 1;
+        for c in indent.chars() {
+            match self.style {
+                Some(style) if style != c => {
+                    return Err(SyntaxError::new(
+                        line,
+                        column,
+                        "Inconsistent use of tabs and spaces in indentation",
+                    ));
+                }
+                _ => self.style = Some(c),
+            }
+        }
+
+        let width = indent.chars().count();
+        let current = self.levels.last().copied().unwrap_or(0);
+        if std::mem::take(&mut self.expect_block) {
+            if width <= current {
+                return Err(SyntaxError::new(
+                    line,
+                    column,
+                    "Expected an indented block after ':'",
+                ));
+            }
+            self.levels.push(width);
+        } else if width > current {
+            return Err(SyntaxError::new(line, column, "Unexpected indent"));
+        } else if width < current {
+            while self.levels.last().is_some_and(|level| *level > width) {
+                self.levels.pop();
+            }
+            if self.levels.last().copied().unwrap_or(0) != width {
+                return Err(SyntaxError::new(
+                    line,
+                    column,
+                    "Unindent does not match any outer indentation level",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_python(cursor: &mut Cursor) -> Result<()> {
+    let mut delimiters = Delimiters::default();
+    let mut indentation = Indentation {
+        levels: vec![0],
+        style: None,
+        expect_block: false,
+    };
+    let mut at_line_start = true;
+    // Last character of code (not comment) on the current logical line
+    let mut last = None;
+
+    while let Some(c) = cursor.peek() {
+        let start = cursor.position();
+
+        if at_line_start {
+            at_line_start = false;
+            let continued = delimiters.depth() > 0 || last == Some('\\');
+            let mut indent = String::new();
+            while let Some(ws) = cursor.peek().filter(|c| *c == ' ' || *c == '\t') {
+                indent.push(ws);
+                cursor.bump();
+            }
+            let blank = matches!(cursor.peek(), None | Some('\n' | '#'));
+            if !continued && !blank {
+                indentation.check(&indent, start.0)?;
+            }
+            continue;
+        }
+
+        if c == '\n' {
+            cursor.bump();
+            at_line_start = true;
+            if delimiters.depth() == 0 && last != Some('\\') {
+                if last.is_some() {
+                    indentation.expect_block = last == Some(':');
+                }
+                last = None;
+            }
+            continue;
+        }
+
+        if c == '#' {
+            cursor.line_comment();
+        } else if c == '"' || c == '\'' {
+            python_string(cursor, start)?;
+            last = Some(c);
+        } else if is_ident_start(c) {
+            let ident = cursor.identifier();
+            let prefix = ident.len() <= 2 && ident.chars().all(|c| "rRbBuUfF".contains(c));
+            if prefix && matches!(cursor.peek(), Some('"' | '\'')) {
+                python_string(cursor, start)?;
+            }
+            last = Some('a');
+        } else {
+            match c {
+                '(' | '[' | '{' => delimiters.open(c, start),
+                ')' | ']' | '}' => delimiters.close(c, start)?,
+                _ => {}
+            }
+            cursor.bump();
+            if !c.is_whitespace() {
+                last = Some(c);
+            }
+        }
+    }
+
+    if indentation.expect_block || last == Some(':') && delimiters.depth() == 0 {
+        let (line, column) = cursor.position();
+        return Err(SyntaxError::new(
+            line,
+            column,
+            "Expected an indented block after ':'",
+        ));
+    }
+    delimiters.finish()
+}
+
+/// Python string at its opening quote (single, double or triple quoted)
+fn python_string(cursor: &mut Cursor, start: (usize, usize)) -> Result<()> {
+    let quote = cursor.peek().unwrap_or('"');
+    let triple: String = std::iter::repeat_n(quote, 3).collect();
+
+    if cursor.starts_with(&triple) {
+        cursor.bump_n(3);
+        while !cursor.starts_with(&triple) {
+            match cursor.bump() {
+                Some('\\') => {
+                    cursor.bump();
+                }
+                Some(_) => {}
+                None => {
+                    return Err(SyntaxError::new(
+                        start.0,
+                        start.1,
+                        "Unterminated triple-quoted string",
+                    ))
+                }
+            }
+        }
+        cursor.bump_n(3);
+        Ok(())
+    } else {
+        cursor.bump();
+        cursor.quoted(quote, false, start, "string literal")
+    }
+}
+
+/// Previous significant JavaScript token, used to tell regex literals from division
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Previous {
+    Nothing,
+    Operand,
+    Punct,
+    Keyword,
+}
+
+/// Keywords after which `/` starts a regular expression
+const REGEX_KEYWORDS: &[&str] = &[
+    "return",
+    "typeof",
+    "case",
+    "do",
+    "else",
+    "in",
+    "of",
+    "new",
+    "delete",
+    "void",
+    "throw",
+    "instanceof",
+    "yield",
+    "await",
+];
+
+fn check_javascript(cursor: &mut Cursor) -> Result<()> {
+    let mut delimiters = Delimiters::default();
+    let mut previous = Previous::Nothing;
+
+    while let Some(c) = cursor.peek() {
+        let start = cursor.position();
+
+        if cursor.starts_with("//") {
+            cursor.line_comment();
+        } else if cursor.starts_with("/*") {
+            let from = cursor.pos;
+            cursor.bump_n(2);
+            while !cursor.starts_with("*/") {
+                if cursor.bump().is_none() {
+                    return Err(SyntaxError::new(
+                        start.0,
+                        start.1,
+                        "Unterminated block comment",
+                    ));
+                }
+            }
+            cursor.bump_n(2);
+            cursor.comments.push((from, cursor.pos));
+        } else if c == '"' || c == '\'' {
+            cursor.bump();
+            cursor.quoted(c, false, start, "string literal")?;
+            previous = Previous::Operand;
+        } else if c == '`' {
+            cursor.bump();
+            template(cursor, &mut delimiters, start)?;
+            previous = Previous::Operand;
+        } else if c == '/' && previous != Previous::Operand && !cursor.follows('<') {
+            // `</` closes a JSX tag rather than starting a regex
+            cursor.bump();
+            regex(cursor, start)?;
+            previous = Previous::Operand;
+        } else if is_ident_start(c) || c == '$' {
+            let mut ident = String::new();
+            while let Some(c) = cursor.peek().filter(|c| is_ident_char(*c) || *c == '$') {
+                ident.push(c);
+                cursor.bump();
+            }
+            previous = if REGEX_KEYWORDS.contains(&ident.as_str()) {
+                Previous::Keyword
+            } else {
+                Previous::Operand
+            };
+        } else if c.is_ascii_digit() {
+            cursor.identifier();
+            previous = Previous::Operand;
+        } else {
+            cursor.bump();
+            match c {
+                '(' | '[' | '{' => delimiters.open(c, start),
+                // Closing the `${` of a template literal resumes the template
+                '}' if delimiters.top() == Some('$') => {
+                    delimiters.stack.pop();
+                    template(cursor, &mut delimiters, start)?;
+                }
+                ')' | ']' | '}' => delimiters.close(c, start)?,
+                _ => {}
+            }
+            if !c.is_whitespace() {
+                previous = if matches!(c, ')' | ']' | '}') {
+                    Previous::Operand
+                } else {
+                    Previous::Punct
+                };
+            }
+        }
+    }
+
+    delimiters.finish()
+}
+
+/// Template literal body after the opening backtick or a closing `}` of `${`
+///
+/// Stops after the closing backtick, or after `${` with a `$` marker pushed so
+/// the matching `}` resumes the template.
+fn template(cursor: &mut Cursor, delimiters: &mut Delimiters, start: (usize, usize)) -> Result<()> {
+    loop {
+        let position = cursor.position();
+        if cursor.starts_with("${") {
+            cursor.bump_n(2);
+            delimiters.open('$', position);
+            return Ok(());
+        }
+        match cursor.bump() {
+            Some('\\') => {
+                cursor.bump();
+            }
+            Some('`') => return Ok(()),
+            Some(_) => {}
+            None => {
+                return Err(SyntaxError::new(
+                    start.0,
+                    start.1,
+                    "Unterminated template literal",
+                ))
+            }
+        }
+    }
+}
+
+/// Regular expression literal after its opening `/`
+fn regex(cursor: &mut Cursor, start: (usize, usize)) -> Result<()> {
+    let mut in_class = false;
+    loop {
+        match cursor.bump() {
+            Some('\\') => {
+                cursor.bump();
+            }
+            Some('[') => in_class = true,
+            Some(']') => in_class = false,
+            Some('/') if !in_class => break,
+            Some('\n') | None => {
+                return Err(SyntaxError::new(
+                    start.0,
+                    start.1,
+                    "Unterminated regular expression literal",
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+    // Flags
+    cursor.identifier();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(code: &str, language: Language) -> (usize, usize, String) {
+        let e = check_syntax(code, language).unwrap_err();
+        (e.line, e.column, e.message)
+    }
+
+    #[test]
+    fn test_rust_ignores_strings_and_comments() {
+        let code = "let s = \"x^(2\"; // unbalanced ( in a comment\n/* { nested /* ] */ */\nlet c = '(';\nlet r = r#\"\"{\"#;\nfn f<'a>(x: &'a str) -> &'a str { x }";
+        assert!(check_syntax(code, Language::Rust).is_ok());
+
+        assert_eq!(
+            error("let x = (1, 2;\nlet y = 3;", Language::Rust),
+            (1, 9, "Unclosed delimiter: '('".to_string())
+        );
+        assert_eq!(
+            error("let v = vec![1, 2);", Language::Rust),
+            (
+                1,
+                18,
+                "Mismatched closing parenthesis ')': '[' opened at line 1, column 13 is still open"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            error("let s = \"open;\nlet t = 1;", Language::Rust),
+            (1, 9, "Unterminated string literal".to_string())
+        );
+    }
+
+    #[test]
+    fn test_python_strings_and_indentation() {
+        let code = "s = \"x^(2\"  # (\nif x:\n    y = f'''\n)'''\n    for i in range(3):\n        pass\nelse:\n    z = [1,\n  2]\n";
+        assert!(check_syntax(code, Language::Python).is_ok());
+
+        assert_eq!(
+            error("x = 1\n    y = 2", Language::Python),
+            (2, 5, "Unexpected indent".to_string())
+        );
+        assert_eq!(
+            error("if x:\n    y = 1\n  z = 2", Language::Python),
+            (
+                3,
+                3,
+                "Unindent does not match any outer indentation level".to_string()
+            )
+        );
+        assert_eq!(
+            error("def f():\nreturn 1", Language::Python),
+            (2, 1, "Expected an indented block after ':'".to_string())
+        );
+        assert_eq!(
+            error("if x:\n    a = 1\n\tb = 2", Language::Python),
+            (
+                3,
+                2,
+                "Inconsistent use of tabs and spaces in indentation".to_string()
+            )
+        );
+        assert_eq!(
+            error("s = 'abc\nt = 1", Language::Python),
+            (1, 5, "Unterminated string literal".to_string())
+        );
+    }
+
+    #[test]
+    fn test_strip_comments() {
+        let rust = "let s = \"// kept\"; // dropped\nlet c = '#'; /* a\n /* b */ */ let d = 2;";
+        assert_eq!(
+            strip_comments(rust, Language::Rust),
+            "let s = \"// kept\"; \nlet c = '#'; \n let d = 2;"
+        );
+
+        let python = "s = '# kept'  # dropped\nt = \"\"\"\n# kept\n\"\"\"";
+        assert_eq!(
+            strip_comments(python, Language::Python),
+            "s = '# kept'  \nt = \"\"\"\n# kept\n\"\"\""
+        );
+
+        let js = "const u = `// ${x} kept`; // dropped\nconst r = /\\/\\//; /* dropped */";
+        assert_eq!(
+            strip_comments(js, Language::NodeJs),
+            "const u = `// ${x} kept`; \nconst r = /\\/\\//; "
+        );
+    }
+
+    #[test]
+    fn test_javascript_templates_and_regex() {
+        let code = "const s = `x^(${f({a: 1})}`;\nconst r = /[)(]+\\//g;\nconst d = a / b / (c);\n// )\nconst el = <b>{x}</b>;\n";
+        assert!(check_syntax(code, Language::NodeJs).is_ok());
+
+        assert_eq!(
+            error("const s = `abc ${x + 1;", Language::NodeJs),
+            (1, 16, "Unclosed '${' in template literal".to_string())
+        );
+        assert_eq!(
+            error("f(1));", Language::NodeJs),
+            (1, 5, "Unmatched closing parenthesis".to_string())
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! Unknown key detection
+//!
+//! Schema structs ignore fields they do not declare, so a typo such as
+//! `releated_topics` or `expected_ouput` silently drops content. The parsed YAML
+//! document is walked against the JSON Schema derived from the Rust types, which
+//! knows the valid field names at every level.
+
+use super::{json_schema, SchemaFile};
+use crate::suggest::closest_match;
+use crate::{Diagnostics, KbError, Severity};
+use serde_json::Value as JsonValue;
+use serde_yaml::Value;
+use std::sync::OnceLock;
+
+/// How unknown keys are reported when loading a schema
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Strictness {
+    /// Unknown keys are warnings
+    #[default]
+    Lenient,
+
+    /// Unknown keys are errors
+    Strict,
+}
+
+impl Strictness {
+    /// Severity of an unknown-key diagnostic in this mode
+    pub fn severity(self) -> Severity {
+        match self {
+            Strictness::Lenient => Severity::Warning,
+            Strictness::Strict => Severity::Error,
+        }
+    }
+}
+
+/// A key that no schema field accepts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownKey {
+    /// Field path of the key (e.g., `examples[0].expected_ouput`)
+    pub path: String,
+
+    pub key: String,
+
+    /// Valid field names of the enclosing mapping
+    pub expected: Vec<String>,
+}
+
+impl UnknownKey {
+    /// Closest valid field name, if the key looks like a typo
+    pub fn suggestion(&self) -> Option<&str> {
+        closest_match(&self.key, self.expected.iter().map(String::as_str))
+    }
+}
+
+/// Find every key in a parsed schema document that no field accepts
+pub fn find_unknown_keys(document: &Value) -> Vec<UnknownKey> {
+    static SCHEMA: OnceLock<JsonValue> = OnceLock::new();
+    let root = SCHEMA.get_or_init(json_schema);
+
+    let mut unknown = Vec::new();
+    walk(document, root, root, "", &mut unknown);
+    unknown
+}
+
+/// Report the unknown keys of a parsed schema file
+pub fn check_unknown_keys(file: &SchemaFile, severity: Severity) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+
+    for unknown in &file.unknown_keys {
+        let suggestion = match unknown.suggestion() {
+            Some(name) => format!("Did you mean '{}'?", name),
+            None => format!(
+                "Remove it; valid fields here are: {}",
+                unknown.expected.join(", ")
+            ),
+        };
+        diagnostics.push(
+            severity,
+            KbError::validation(
+                &file.path,
+                file.spans.locate(&unknown.path),
+                format!("Unknown field '{}' is ignored", unknown.path),
+                suggestion,
+            ),
+        );
+    }
+
+    diagnostics
+}
+
+fn walk(
+    node: &Value,
+    schema: &JsonValue,
+    root: &JsonValue,
+    path: &str,
+    unknown: &mut Vec<UnknownKey>,
+) {
+    let schema = resolve(schema, root);
+
+    if let Some(branches) = schema
+        .get("anyOf")
+        .or_else(|| schema.get("oneOf"))
+        .and_then(JsonValue::as_array)
+    {
+        if let Some(branch) = pick_branch(node, branches, root) {
+            walk(node, branch, root, path, unknown);
+        }
+        return;
+    }
+
+    match node {
+        Value::Mapping(mapping) => {
+            let Some(properties) = schema.get("properties").and_then(JsonValue::as_object) else {
+                return;
+            };
+            for (key, value) in mapping {
+                let Some(key) = key.as_str() else {
+                    continue;
+                };
+                let child = if path.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                match properties.get(key) {
+                    Some(field) => walk(value, field, root, &child, unknown),
+                    None => unknown.push(UnknownKey {
+                        path: child,
+                        key: key.to_string(),
+                        expected: properties.keys().cloned().collect(),
+                    }),
+                }
+            }
+        }
+        Value::Sequence(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (idx, item) in items.iter().enumerate() {
+                    walk(
+                        item,
+                        item_schema,
+                        root,
+                        &format!("{}[{}]", path, idx),
+                        unknown,
+                    );
+                }
+            }
+        }
+        Value::Tagged(tagged) => walk(&tagged.value, schema, root, path, unknown),
+        _ => {}
+    }
+}
+
+/// Follow a `$ref` into `$defs`
+fn resolve<'a>(schema: &'a JsonValue, root: &'a JsonValue) -> &'a JsonValue {
+    match schema
+        .get("$ref")
+        .and_then(JsonValue::as_str)
+        .and_then(|reference| reference.strip_prefix("#/$defs/"))
+    {
+        Some(name) => &root["$defs"][name],
+        None => schema,
+    }
+}
+
+/// Branch of an `anyOf` the node deserializes as
+///
+/// Mirrors untagged enums: the first object branch whose required fields are all
+/// present wins, falling back to the first object branch. Nested `anyOf`s (an
+/// optional untagged enum) are resolved by the recursive walk.
+fn pick_branch<'a>(
+    node: &Value,
+    branches: &'a [JsonValue],
+    root: &'a JsonValue,
+) -> Option<&'a JsonValue> {
+    match node {
+        Value::Mapping(mapping) => {
+            let objects: Vec<&JsonValue> = branches
+                .iter()
+                .filter(|branch| {
+                    let branch = resolve(branch, root);
+                    ["properties", "anyOf", "oneOf"]
+                        .iter()
+                        .any(|key| branch.get(key).is_some())
+                })
+                .collect();
+            objects
+                .iter()
+                .find(|branch| {
+                    resolve(branch, root)
+                        .get("required")
+                        .and_then(JsonValue::as_array)
+                        .is_none_or(|required| {
+                            required
+                                .iter()
+                                .filter_map(JsonValue::as_str)
+                                .all(|field| mapping.contains_key(field))
+                        })
+                })
+                .or(objects.first())
+                .copied()
+        }
+        Value::Sequence(_) => branches
+            .iter()
+            .find(|branch| resolve(branch, root).get("items").is_some()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unknown_paths(yaml: &str) -> Vec<(String, Option<String>)> {
+        let document: Value = serde_yaml::from_str(yaml).unwrap();
+        find_unknown_keys(&document)
+            .into_iter()
+            .map(|key| {
+                let suggestion = key.suggestion().map(str::to_string);
+                (key.path, suggestion)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reports_typos_with_suggestions() {
+        let unknown = unknown_paths(
+            r#"
+topic: t
+title: T
+description: D
+releated_topics: [a]
+examples:
+  - title: E
+    code:
+      rust: "x"
+    expected_ouput: "1"
+"#,
+        );
+        assert_eq!(
+            unknown,
+            vec![
+                (
+                    "releated_topics".to_string(),
+                    Some("related_topics".to_string())
+                ),
+                (
+                    "examples[0].expected_ouput".to_string(),
+                    Some("expected_output".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_untagged_article_and_free_form_fields() {
+        let unknown = unknown_paths(
+            r#"
+topic: t
+title: T
+description: D
+article:
+  content: "Markdown"
+  sections: []
+performance:
+  benchmarks:
+    anything: goes
+seo:
+  alternate_languages:
+    de: /de/t
+"#,
+        );
+        assert_eq!(unknown, vec![("article.sections".to_string(), None)]);
+    }
+}
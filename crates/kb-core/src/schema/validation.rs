@@ -2,72 +2,110 @@
 ///
 /// This module implements strict validation for schemas to ensure
 /// correctness and consistency across all generated outputs.
-use crate::{KbError, Result, schema::Schema};
+use super::syntax::check_syntax;
+use crate::inventory::{expand_reference, separator, ApiInventory};
+use crate::runner::Language;
+use crate::{
+    schema::{Schema, Span, SpanMap},
+    Diagnostics, KbError, Result,
+};
 use regex::Regex;
 
 /// Validate a schema for correctness and completeness
+///
+/// Returns the first error found; warnings are ignored. Use [`diagnose_schema`]
+/// to get every error and warning at once.
+///
+/// Without source positions every error points at line 1; schemas loaded with
+/// [`Schema::load_from_file`] are validated against their [`SpanMap`].
 pub fn validate_schema(schema: &Schema, file_path: String) -> Result<()> {
-    validate_required_fields(schema, &file_path)?;
-    validate_code_references(schema, &file_path)?;
-    validate_examples(schema, &file_path)?;
-    validate_output_hints(schema, &file_path)?;
+    diagnose_schema(schema, &file_path, &SpanMap::default()).into_result()
+}
 
-    Ok(())
+/// Run every validation check and collect all errors and warnings
+///
+/// `spans` locates each field in the schema file so diagnostics report real
+/// line numbers.
+pub fn diagnose_schema(schema: &Schema, file_path: &str, spans: &SpanMap) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+
+    validate_required_fields(schema, file_path, spans, &mut diagnostics);
+    validate_code_references(schema, file_path, spans, &mut diagnostics);
+    validate_examples(schema, file_path, spans, &mut diagnostics);
+    validate_output_hints(schema, file_path, spans, &mut diagnostics);
+
+    diagnostics
 }
 
 /// Validate required fields are present and non-empty
-fn validate_required_fields(schema: &Schema, file_path: &str) -> Result<()> {
+fn validate_required_fields(
+    schema: &Schema,
+    file_path: &str,
+    spans: &SpanMap,
+    diagnostics: &mut Diagnostics,
+) {
     // Topic must be non-empty and follow dotted notation
     if schema.topic.is_empty() {
-        return Err(KbError::missing_field(
+        diagnostics.error(KbError::missing_field(
             "topic",
             file_path,
-            1,
+            spans.locate("topic"),
             "Add a topic identifier: topic: \"calculus.derivative\"",
         ));
-    }
-
-    let topic_regex = Regex::new(r"^[a-z_][a-z0-9_-]*(\.[a-z_][a-z0-9_-]*)*$").unwrap();
-    if !topic_regex.is_match(&schema.topic) {
-        return Err(KbError::validation(
-            file_path,
-            1,
-            format!("Topic '{}' must follow dotted notation (lowercase, alphanumeric, underscores, hyphens)", schema.topic),
-            "Example: topic: \"calculus.derivative\" or topic: \"algebra.polynomial.factoring\"",
-        ));
+    } else {
+        let topic_regex = Regex::new(r"^[a-z_][a-z0-9_-]*(\.[a-z_][a-z0-9_-]*)*$").unwrap();
+        if !topic_regex.is_match(&schema.topic) {
+            diagnostics.error(KbError::validation(
+                file_path,
+                spans.locate("topic"),
+                format!("Topic '{}' must follow dotted notation (lowercase, alphanumeric, underscores, hyphens)", schema.topic),
+                "Example: topic: \"calculus.derivative\" or topic: \"algebra.polynomial.factoring\"",
+            ));
+        }
     }
 
     // Title must be non-empty
     if schema.title.is_empty() {
-        return Err(KbError::missing_field(
+        diagnostics.error(KbError::missing_field(
             "title",
             file_path,
-            1,
+            spans.locate("title"),
             "Add a human-readable title: title: \"Symbolic Differentiation\"",
         ));
     }
 
     // Description must be non-empty
     if schema.description.is_empty() {
-        return Err(KbError::missing_field(
+        diagnostics.error(KbError::missing_field(
             "description",
             file_path,
-            1,
+            spans.locate("description"),
             "Add a detailed description explaining what this function/concept does",
         ));
     }
 
-    // Examples are optional for article-style schemas (they may have article.content instead)
-    // No longer require examples
-
-    Ok(())
+    // Examples are optional for article-style schemas (they may have article.content instead),
+    // but a schema with neither renders as an almost empty page
+    if schema.examples.is_empty() && schema.article.is_none() {
+        diagnostics.warning(KbError::validation(
+            file_path,
+            Span::default(),
+            "Schema has neither examples nor article content",
+            "Add at least one example or an article so generated pages have a body",
+        ));
+    }
 }
 
 /// Validate code references follow expected patterns
-fn validate_code_references(schema: &Schema, file_path: &str) -> Result<()> {
+fn validate_code_references(
+    schema: &Schema,
+    file_path: &str,
+    spans: &SpanMap,
+    diagnostics: &mut Diagnostics,
+) {
     // Code references are optional - skip if not present
     let Some(code_refs) = &schema.code_refs else {
-        return Ok(());
+        return;
     };
 
     // Rust reference validation is very lenient - just check it's not empty
@@ -78,9 +116,9 @@ fn validate_code_references(schema: &Schema, file_path: &str) -> Result<()> {
     //   - single_module (for top-level crate references)
     // Just check for basic structure and non-empty
     if code_refs.rust.is_empty() {
-        return Err(KbError::validation(
+        diagnostics.error(KbError::validation(
             file_path,
-            1,
+            spans.locate("code_refs.rust"),
             "Rust code reference is empty".to_string(),
             "Example: mathhook_core::calculus::derivative",
         ));
@@ -90,18 +128,73 @@ fn validate_code_references(schema: &Schema, file_path: &str) -> Result<()> {
     // Python and Node.js validation is lenient - just check format if non-empty
     // Skip validation for empty values (they're optional)
     // The code is already flexible with comma-separated, curly braces, etc.
+    // Whether the referenced items exist is checked by `check_code_refs`
+    // against an API inventory, when one is available.
+}
+
+/// Check that every `code_refs` path exists in an API inventory
+///
+/// References are expanded first (`module::{a, b}`, comma-separated lists).
+/// Languages the inventory has no symbols for, and paths into crates or packages
+/// it does not describe, are skipped.
+pub fn check_code_refs(
+    schema: &Schema,
+    file_path: &str,
+    spans: &SpanMap,
+    inventory: &ApiInventory,
+) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    let Some(code_refs) = &schema.code_refs else {
+        return diagnostics;
+    };
+
+    let references = [
+        (Language::Rust, &code_refs.rust),
+        (Language::Python, &code_refs.python),
+        (Language::NodeJs, &code_refs.nodejs),
+    ];
+    for (language, reference) in references {
+        if !inventory.covers(language) {
+            continue;
+        }
+        for path in expand_reference(reference, language) {
+            if !inventory.covers_root(language, &path) || inventory.contains(language, &path) {
+                continue;
+            }
+
+            let suggestion = inventory.suggest(language, &path).unwrap_or_else(|| {
+                path.split(separator(language))
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            });
+            diagnostics.error(KbError::invalid_code_ref(
+                path.clone(),
+                file_path,
+                spans.locate(&format!("code_refs.{}", language.key())),
+                suggestion,
+            ));
+        }
+    }
 
-    Ok(())
+    diagnostics
 }
 
 /// Validate code examples are present in all languages
-fn validate_examples(schema: &Schema, file_path: &str) -> Result<()> {
+fn validate_examples(
+    schema: &Schema,
+    file_path: &str,
+    spans: &SpanMap,
+    diagnostics: &mut Diagnostics,
+) {
     for (idx, example) in schema.examples.iter().enumerate() {
+        let path = format!("examples[{}]", idx);
+
         // Title must be non-empty
         if example.title.is_empty() {
-            return Err(KbError::validation(
+            diagnostics.error(KbError::validation(
                 file_path,
-                1,
+                spans.locate(&format!("{}.title", path)),
                 format!("Example {} has empty title", idx + 1),
                 "Add a descriptive title: title: \"Power Rule\"",
             ));
@@ -115,129 +208,69 @@ fn validate_examples(schema: &Schema, file_path: &str) -> Result<()> {
         let has_nodejs = !example.code.nodejs.trim().is_empty();
 
         if !has_rust && !has_python && !has_nodejs {
-            return Err(KbError::validation(
+            diagnostics.error(KbError::validation(
                 file_path,
-                1,
+                spans.locate(&format!("{}.code", path)),
                 format!("Example '{}' has no code in any language", example.title),
                 "Add code in at least one language (Rust, Python, or JavaScript)",
             ));
         }
 
-        // Basic syntax validation (check for balanced braces/brackets/parens)
-        // Only validate syntax if code is present
-        if has_rust {
-            validate_code_syntax(&example.code.rust, "Rust", &example.title, file_path)?;
-        }
-        if has_python {
-            validate_code_syntax(&example.code.python, "Python", &example.title, file_path)?;
-        }
-        if has_nodejs {
-            validate_code_syntax(&example.code.nodejs, "JavaScript", &example.title, file_path)?;
-        }
-    }
-
-    Ok(())
-}
-
-/// Basic syntax validation (balanced brackets)
-fn validate_code_syntax(code: &str, language: &str, _example_title: &str, file_path: &str) -> Result<()> {
-    let mut stack = Vec::new();
-
-    for ch in code.chars() {
-        match ch {
-            '(' | '[' | '{' => stack.push(ch),
-            ')' => {
-                if stack.pop() != Some('(') {
-                    return Err(KbError::CodeSyntaxError {
-                        language: language.to_string(),
-                        file: file_path.to_string(),
-                        line: 1,
-                        error: "Unmatched closing parenthesis".to_string(),
-                        code: code.to_string(),
-                    });
+        // Syntax validation, only for languages with code
+        for language in Language::ALL {
+            let code = language.snippet(&example.code);
+            if !code.trim().is_empty() {
+                let field = format!("{}.code.{}", path, language.key());
+                if let Err(e) = validate_code_syntax(code, language, &field, file_path, spans) {
+                    diagnostics.error(e);
                 }
             }
-            ']' => {
-                if stack.pop() != Some('[') {
-                    return Err(KbError::CodeSyntaxError {
-                        language: language.to_string(),
-                        file: file_path.to_string(),
-                        line: 1,
-                        error: "Unmatched closing bracket".to_string(),
-                        code: code.to_string(),
-                    });
-                }
-            }
-            '}' => {
-                if stack.pop() != Some('{') {
-                    return Err(KbError::CodeSyntaxError {
-                        language: language.to_string(),
-                        file: file_path.to_string(),
-                        line: 1,
-                        error: "Unmatched closing brace".to_string(),
-                        code: code.to_string(),
-                    });
-                }
-            }
-            _ => {}
         }
     }
+}
 
-    if !stack.is_empty() {
-        return Err(KbError::CodeSyntaxError {
+/// Language-aware syntax check of a snippet (see [`super::syntax`])
+///
+/// `field` is the span path of the snippet (e.g., `examples[0].code.rust`), used
+/// to report the schema file line of the problem.
+fn validate_code_syntax(
+    code: &str,
+    language: Language,
+    field: &str,
+    file_path: &str,
+    spans: &SpanMap,
+) -> Result<()> {
+    check_syntax(code, language).map_err(|e| {
+        let location = spans.locate_in_value(field, e.line, e.column);
+        KbError::CodeSyntaxError {
             language: language.to_string(),
             file: file_path.to_string(),
-            line: 1,
-            error: format!("Unclosed delimiter: {:?}", stack.last().unwrap()),
+            line: location.line,
+            column: location.column,
+            error: format!("{} (snippet line {})", e.message, e.line),
             code: code.to_string(),
-        });
-    }
-
-    Ok(())
+        }
+    })
 }
 
 /// Validate output hints
-fn validate_output_hints(schema: &Schema, file_path: &str) -> Result<()> {
-    // Validate LLM RAG hints if present
+fn validate_output_hints(
+    schema: &Schema,
+    file_path: &str,
+    spans: &SpanMap,
+    diagnostics: &mut Diagnostics,
+) {
+    // Validate LLM RAG hints if present; strategy and priority are checked when deserializing
     if let Some(rag_hints) = &schema.outputs.llm_rag {
-        // Allow standard strategies and any custom strategies (starts with by_)
-        let valid_strategies = ["by_example", "by_section", "fixed_size"];
-        let is_valid = valid_strategies.contains(&rag_hints.chunk_strategy.as_str())
-            || rag_hints.chunk_strategy.starts_with("by_")
-            || !rag_hints.chunk_strategy.is_empty();
-        if !is_valid {
-            return Err(KbError::validation(
-                file_path,
-                1,
-                format!("Invalid chunk_strategy '{}'", rag_hints.chunk_strategy),
-                format!("Valid strategies: {}", valid_strategies.join(", ")),
-            ));
-        }
-
         if rag_hints.max_chunk_size == 0 || rag_hints.max_chunk_size > 8192 {
-            return Err(KbError::validation(
+            diagnostics.error(KbError::validation(
                 file_path,
-                1,
+                spans.locate("outputs.llm_rag.max_chunk_size"),
                 format!("Invalid max_chunk_size {}", rag_hints.max_chunk_size),
                 "Chunk size must be between 1 and 8192 tokens",
             ));
         }
-
-        let valid_priorities = ["high", "medium", "low"];
-        if !valid_priorities.contains(&rag_hints.embedding_priority.as_str()) {
-            return Err(KbError::validation(
-                file_path,
-                1,
-                format!(
-                    "Invalid embedding_priority '{}'",
-                    rag_hints.embedding_priority
-                ),
-                format!("Valid priorities: {}", valid_priorities.join(", ")),
-            ));
-        }
     }
-
-    Ok(())
 }
 
 #[cfg(test)]
@@ -266,6 +299,7 @@ mod tests {
                     nodejs: "const f = expr('x^3');".to_string(),
                 },
                 expected_output: None,
+                doctest: Default::default(),
             }],
             article: None,
             use_cases: vec![],
@@ -324,6 +358,7 @@ mod tests {
                     nodejs: "test()".to_string(),
                 },
                 expected_output: None,
+                doctest: Default::default(),
             }],
             article: None,
             use_cases: vec![],
@@ -361,6 +396,7 @@ mod tests {
                     nodejs: "const x = [1, 2, 3];".to_string(),
                 },
                 expected_output: None,
+                doctest: Default::default(),
             }],
             article: None,
             use_cases: vec![],
@@ -374,6 +410,157 @@ mod tests {
 
         let result = validate_schema(&schema, "test.yaml".to_string());
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("Unclosed delimiter"));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Unclosed delimiter"));
+    }
+
+    #[test]
+    fn test_diagnose_collects_every_error() {
+        let schema = Schema {
+            topic: "Not Dotted".to_string(),
+            title: "".to_string(),
+            description: "".to_string(),
+            mathematical_definition: None,
+            code_refs: None,
+            examples: vec![Example {
+                title: "Example".to_string(),
+                explanation: "Test".to_string(),
+                code: CodeSnippets {
+                    rust: "let x = (1;".to_string(),
+                    python: "x = [1".to_string(),
+                    nodejs: "".to_string(),
+                },
+                expected_output: None,
+                doctest: Default::default(),
+            }],
+            article: None,
+            use_cases: vec![],
+            related_topics: vec![],
+            performance: None,
+            interactive_playground: None,
+            outputs: OutputHints::default(),
+            metadata: None,
+            seo: None,
+        };
+
+        let diagnostics = diagnose_schema(&schema, "test.yaml", &SpanMap::default());
+        assert_eq!(diagnostics.error_count(), 5);
+        assert_eq!(diagnostics.warning_count(), 0);
+
+        let messages: Vec<String> = diagnostics.errors().map(|e| e.to_string()).collect();
+        assert!(messages[0].contains("dotted notation"));
+        assert!(messages[1].contains("'title'"));
+        assert!(messages[2].contains("'description'"));
+        assert!(messages[3].contains("Rust"));
+        assert!(messages[4].contains("Python"));
+    }
+
+    #[test]
+    fn test_warnings_do_not_fail_validation() {
+        let schema = Schema {
+            topic: "test.function".to_string(),
+            title: "Test".to_string(),
+            description: "Test description".to_string(),
+            mathematical_definition: None,
+            code_refs: None,
+            examples: vec![],
+            article: None,
+            use_cases: vec![],
+            related_topics: vec![],
+            performance: None,
+            interactive_playground: None,
+            outputs: OutputHints {
+                llm_rag: Some(LlmRagHints {
+                    chunk_strategy: RagChunkStrategy::BySection,
+                    max_chunk_size: 512,
+                    embedding_priority: EmbeddingPriority::High,
+                }),
+                ..OutputHints::default()
+            },
+            metadata: None,
+            seo: None,
+        };
+
+        let diagnostics = diagnose_schema(&schema, "test.yaml", &SpanMap::default());
+        assert!(!diagnostics.has_errors());
+        assert_eq!(diagnostics.warning_count(), 1);
+        assert!(diagnostics
+            .warnings()
+            .any(|w| w.to_string().contains("neither examples nor article")));
+        assert!(validate_schema(&schema, "test.yaml".to_string()).is_ok());
+    }
+
+    #[test]
+    fn test_errors_report_source_lines() {
+        let yaml = r#"topic: "test.function"
+title: ""
+description: "Test description"
+
+examples:
+  - title: "Balanced"
+    code:
+      rust: "let x = (1, 2);"
+  - title: "Unbalanced"
+    code:
+      rust: |
+        let x = symbol!(x);
+
+        let f = expr!(x ^ 3;
+      python: "f = x ** 3"
+"#;
+        let schema: Schema = serde_yaml::from_str(yaml).unwrap();
+        let spans = SpanMap::parse(yaml);
+
+        let diagnostics = diagnose_schema(&schema, "test.yaml", &spans);
+        let lines: Vec<usize> = diagnostics
+            .errors()
+            .map(|e| match e {
+                KbError::MissingField { line, .. } | KbError::CodeSyntaxError { line, .. } => *line,
+                other => panic!("unexpected error: {}", other),
+            })
+            .collect();
+        assert_eq!(lines, vec![2, 14]);
+    }
+
+    #[test]
+    fn test_code_refs_against_inventory() {
+        let yaml = r#"topic: "calculus.derivative"
+title: "Derivatives"
+description: "Test"
+code_refs:
+  rust: "mathhook_core::calculus::{derivative, integrat}, other_crate::thing"
+  python: "mathhook.calculus.derivative"
+"#;
+        let schema: Schema = serde_yaml::from_str(yaml).unwrap();
+        let mut inventory = ApiInventory::new();
+        inventory.extend(
+            Language::Rust,
+            [
+                "mathhook_core",
+                "mathhook_core::calculus",
+                "mathhook_core::calculus::derivative",
+                "mathhook_core::calculus::integrate",
+            ]
+            .map(String::from),
+        );
+
+        let diagnostics = check_code_refs(&schema, "test.yaml", &SpanMap::parse(yaml), &inventory);
+        let errors: Vec<_> = diagnostics.errors().collect();
+        assert_eq!(errors.len(), 1);
+        match errors[0] {
+            KbError::InvalidCodeRef {
+                reference,
+                line,
+                suggestion,
+                ..
+            } => {
+                assert_eq!(reference, "mathhook_core::calculus::integrat");
+                assert_eq!(*line, 5);
+                assert_eq!(suggestion, "mathhook_core::calculus::integrate");
+            }
+            other => panic!("unexpected error: {}", other),
+        }
     }
 }
@@ -0,0 +1,75 @@
+//! "Did you mean ...?" suggestions
+//!
+//! Helpers for pointing authors at the closest valid name when they mistype
+//! a generator, topic, example title or field.
+
+/// Compute the Levenshtein edit distance between two strings
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+    let mut current = vec![0; b_chars.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b_chars.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            current[j + 1] = (previous[j + 1] + 1)
+                .min(current[j] + 1)
+                .min(previous[j] + cost);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b_chars.len()]
+}
+
+/// Find the candidate closest to `name`, if any is close enough to be a plausible typo
+///
+/// A candidate is considered close when its edit distance is at most a third of
+/// the longer string's length (and at least 1), or when one string contains the other.
+pub fn closest_match<'a, I>(name: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let lowered = name.to_lowercase();
+
+    candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let candidate_lower = candidate.to_lowercase();
+            let distance = levenshtein(&lowered, &candidate_lower);
+            let threshold = (lowered.len().max(candidate_lower.len()) / 3).max(1);
+            let contains = !lowered.is_empty()
+                && (candidate_lower.contains(&lowered) || lowered.contains(&candidate_lower));
+
+            if distance <= threshold || contains {
+                Some((distance, candidate))
+            } else {
+                None
+            }
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("", ""), 0);
+        assert_eq!(levenshtein("jupyter", "jupyter"), 0);
+        assert_eq!(levenshtein("jupiter", "jupyter"), 1);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_closest_match() {
+        let names = ["jupyter", "mdbook", "llm-rag", "latex"];
+        assert_eq!(closest_match("jupiter", names), Some("jupyter"));
+        assert_eq!(closest_match("md-book", names), Some("mdbook"));
+        assert_eq!(closest_match("rag", names), Some("llm-rag"));
+        assert_eq!(closest_match("pdf", names), None);
+    }
+}
@@ -0,0 +1,21 @@
+use kb_core::schema::json_schema;
+use std::path::PathBuf;
+
+/// The published JSON Schema must match the Rust types it is derived from
+#[test]
+fn test_published_json_schema_is_up_to_date() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.pop(); // Go to crates/
+    path.pop(); // Go to root
+    path.push("docs/kb-schema.json");
+
+    let published: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(&path).expect("Failed to read docs/kb-schema.json"),
+    )
+    .expect("docs/kb-schema.json is not valid JSON");
+
+    assert!(
+        published == json_schema(),
+        "docs/kb-schema.json is out of date; regenerate it with `kb schema export -o docs/kb-schema.json`"
+    );
+}
@@ -0,0 +1,280 @@
+//! Running `cargo test` on a generated crate
+//!
+//! Compiler errors are read from cargo's JSON messages and test failures from
+//! the libtest output; both are mapped back to schema examples through the
+//! crate's [`SourceMap`].
+
+use crate::generator::{GeneratedCrate, SourceMap};
+use kb_core::{KbError, Result};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::Command;
+
+/// Outcome of `cargo test` on a generated crate
+#[derive(Debug, Default)]
+pub struct TestReport {
+    /// Compile errors and failing tests, mapped to schema examples
+    pub errors: Vec<KbError>,
+
+    /// Tests that passed
+    pub passed: usize,
+
+    /// Tests that failed
+    pub failed: usize,
+
+    /// Tests marked `doctest: ignore`
+    pub ignored: usize,
+
+    /// Cargo errors that could not be mapped to an example (e.g. dependency resolution)
+    pub unmapped: Vec<String>,
+
+    /// Whether cargo exited successfully
+    pub success: bool,
+}
+
+impl GeneratedCrate {
+    /// Run `cargo test` in `dir`, where the crate was written
+    ///
+    /// With `no_run` the tests are only compiled (`cargo test --no-run`).
+    pub fn test(&self, dir: &Path, cargo: &str, no_run: bool) -> Result<TestReport> {
+        let mut command = Command::new(cargo);
+        command.args(["test", "--message-format=json"]);
+        if no_run {
+            command.arg("--no-run");
+        }
+
+        let output = command
+            .current_dir(dir)
+            .output()
+            .map_err(|e| KbError::Other(format!("Failed to run '{}': {}", cargo, e)))?;
+
+        let mut report = parse_output(self.source_map(), &String::from_utf8_lossy(&output.stdout));
+        report.success = output.status.success();
+
+        if !report.success && report.errors.is_empty() && report.unmapped.is_empty() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let errors: Vec<&str> = stderr
+                .lines()
+                .skip_while(|line| !line.starts_with("error"))
+                .collect();
+            report.unmapped.push(if errors.is_empty() {
+                format!("cargo exited with {}", output.status)
+            } else {
+                errors.join("\n")
+            });
+        }
+        Ok(report)
+    }
+}
+
+/// Parse the stdout of `cargo test --message-format=json`
+fn parse_output(map: &SourceMap, stdout: &str) -> TestReport {
+    let mut report = TestReport::default();
+    let mut seen = BTreeSet::new();
+    let mut failures: Vec<(String, Vec<String>)> = Vec::new();
+    let mut current: Option<usize> = None;
+
+    for line in stdout.lines() {
+        if line.starts_with('{') {
+            if let Ok(message) = serde_json::from_str::<serde_json::Value>(line) {
+                compiler_message(map, &message, &mut report, &mut seen);
+            }
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("test ") {
+            if let Some(name) = rest.strip_suffix(" ... ok") {
+                report.passed += usize::from(map.origin_of_test(name).is_some());
+            } else if let Some(name) = rest.strip_suffix(" ... ignored") {
+                report.ignored += usize::from(map.origin_of_test(name).is_some());
+            } else if rest.ends_with(" ... FAILED") {
+                report.failed += 1;
+            }
+        }
+
+        // Captured output of failing tests: "---- calculus::x stdout ----"
+        if let Some(name) = line
+            .strip_prefix("---- ")
+            .and_then(|l| l.strip_suffix(" stdout ----"))
+        {
+            failures.push((name.to_string(), Vec::new()));
+            current = Some(failures.len() - 1);
+        } else if line == "failures:" || line.starts_with("test result:") {
+            current = None;
+        } else if let Some(index) = current {
+            failures[index].1.push(line.to_string());
+        }
+    }
+
+    for (name, output) in failures {
+        let message = output.join("\n").trim().to_string();
+        match map.origin_of_test(&name) {
+            Some(origin) => {
+                // Point at the panicking line when it is inside the snippet
+                let span = panic_location(&message)
+                    .and_then(|(file, line, column)| map.locate(file, line, column))
+                    .filter(|(located, _)| *located == origin)
+                    .map_or(origin.span, |(_, span)| span);
+                report.errors.push(KbError::ExampleExecutionFailed {
+                    language: "Rust".to_string(),
+                    example: origin.example.clone(),
+                    file: origin.file.clone(),
+                    line: span.line,
+                    column: span.column,
+                    message,
+                });
+            }
+            None => report
+                .unmapped
+                .push(format!("test {} failed:\n{}", name, message)),
+        }
+    }
+
+    report
+}
+
+/// File, line and column of a "panicked at src/x.rs:9:5:" message
+fn panic_location(message: &str) -> Option<(&str, usize, usize)> {
+    let rest = &message[message.find("panicked at ")? + "panicked at ".len()..];
+    let location = rest.lines().next()?.trim_end_matches(':');
+    let mut parts = location.rsplitn(3, ':');
+    let column = parts.next()?.parse().ok()?;
+    let line = parts.next()?.parse().ok()?;
+    Some((parts.next()?, line, column))
+}
+
+/// Record an error-level `compiler-message`
+fn compiler_message(
+    map: &SourceMap,
+    message: &serde_json::Value,
+    report: &mut TestReport,
+    seen: &mut BTreeSet<String>,
+) {
+    if message["reason"] != "compiler-message" {
+        return;
+    }
+    let diagnostic = &message["message"];
+    if diagnostic["level"] != "error" {
+        return;
+    }
+
+    let text = diagnostic["message"].as_str().unwrap_or_default();
+    let primary = diagnostic["spans"]
+        .as_array()
+        .and_then(|spans| spans.iter().find(|span| span["is_primary"] == true));
+    let Some(span) = primary else {
+        // Summary messages such as "aborting due to 2 previous errors"
+        return;
+    };
+
+    let file = span["file_name"].as_str().unwrap_or_default();
+    let line = span["line_start"].as_u64().unwrap_or(1) as usize;
+    let column = span["column_start"].as_u64().unwrap_or(1) as usize;
+    let mut text = text.to_string();
+    if let Some(label) = span["label"].as_str() {
+        text = format!("{} ({})", text, label);
+    }
+
+    // cargo reports the same error once per target that includes the file
+    if !seen.insert(format!("{}:{}:{}:{}", file, line, column, text)) {
+        return;
+    }
+
+    match map.locate(file, line, column) {
+        Some((origin, span)) => report.errors.push(KbError::ExampleCompileFailed {
+            example: origin.example.clone(),
+            file: origin.file.clone(),
+            line: span.line,
+            column: span.column,
+            message: text,
+        }),
+        None => report.unmapped.push(
+            diagnostic["rendered"]
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{}:{}:{}: {}", file, line, column, text)),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::tests::sample_crate;
+
+    #[test]
+    fn test_maps_compile_errors_and_failures() {
+        let generated = sample_crate().generate();
+        let module = &generated
+            .files()
+            .iter()
+            .find(|(p, _)| p.ends_with("calculus.rs"))
+            .unwrap()
+            .1;
+        let line = module
+            .lines()
+            .position(|l| l.contains("let x = symbol!(x);"))
+            .unwrap()
+            + 1;
+
+        let compile_error = serde_json::json!({
+            "reason": "compiler-message",
+            "message": {
+                "level": "error",
+                "message": "cannot find macro `symbol` in this scope",
+                "rendered": "error: cannot find macro `symbol`",
+                "spans": [{
+                    "file_name": "src/calculus.rs",
+                    "line_start": line,
+                    "column_start": 13,
+                    "is_primary": true,
+                    "label": null
+                }]
+            }
+        });
+        let stdout = format!(
+            "{}\n{}\nrunning 2 tests\ntest calculus::derivative_power_rule_2 ... ok\n\
+             test calculus::derivative_slow ... ignored\ntest calculus::derivative_power_rule ... FAILED\n\n\
+             failures:\n\n---- calculus::derivative_power_rule stdout ----\n\
+             thread 'calculus::derivative_power_rule' panicked at src/calculus.rs:{}:5:\nboom\n\n\
+             failures:\n    calculus::derivative_power_rule\n\ntest result: FAILED. 1 passed; 1 failed\n",
+            compile_error,
+            compile_error,
+            line + 1
+        );
+
+        let report = parse_output(generated.source_map(), &stdout);
+        assert_eq!((report.passed, report.failed, report.ignored), (1, 1, 1));
+        assert!(report.unmapped.is_empty());
+        assert_eq!(report.errors.len(), 2);
+
+        match &report.errors[0] {
+            KbError::ExampleCompileFailed {
+                example,
+                file,
+                line,
+                column,
+                ..
+            } => {
+                assert_eq!(example, "Power Rule");
+                assert_eq!(file, "schemas/calculus/derivative.yaml");
+                assert_eq!((*line, *column), (9, 17));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        match &report.errors[1] {
+            KbError::ExampleExecutionFailed {
+                example,
+                line,
+                message,
+                ..
+            } => {
+                assert_eq!(example, "Power Rule");
+                // The panic on the `println!` line maps into the snippet
+                assert_eq!(*line, 10);
+                assert!(message.ends_with("boom"));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,571 @@
+//! Test crate generation
+//!
+//! Every Rust example becomes a `#[test]` function in the module of its topic
+//! category. The generator records where each snippet line ends up so compiler
+//! diagnostics can be mapped back to the schema (see [`SourceMap`]).
+
+use kb_core::runner::DEFAULT_RUST_DEPENDENCIES;
+use kb_core::schema::{DoctestMode, SchemaFile, Span};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Indentation of snippet lines inside a `run` test function
+const RUN_INDENT: usize = 4;
+
+/// Indentation of snippet lines inside the nested function of a `no_run` test
+const NO_RUN_INDENT: usize = 8;
+
+/// Module names that would clash with crates in the extern prelude or keywords
+const RESERVED_MODULES: &[&str] = &[
+    "alloc",
+    "core",
+    "crate",
+    "proc_macro",
+    "self",
+    "std",
+    "super",
+    "test",
+    "mod",
+    "type",
+    "fn",
+    "use",
+];
+
+/// Configuration for the generated crate
+#[derive(Debug, Clone)]
+pub struct DoctestConfig {
+    /// Package name of the generated crate
+    pub crate_name: String,
+
+    /// Dependency lines for `[dependencies]` (e.g. `mathhook = { path = "../mathhook" }`)
+    ///
+    /// Defaults to [`DEFAULT_RUST_DEPENDENCIES`], which covers the `mathhook` and
+    /// `mathhook_core` imports used by examples.
+    pub dependencies: Vec<String>,
+}
+
+impl Default for DoctestConfig {
+    fn default() -> Self {
+        Self {
+            crate_name: "mathhook-kb-doctests".to_string(),
+            dependencies: DEFAULT_RUST_DEPENDENCIES.map(str::to_string).to_vec(),
+        }
+    }
+}
+
+/// Schema example a generated test was created from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Origin {
+    /// Schema file path
+    pub file: String,
+
+    /// Schema topic
+    pub topic: String,
+
+    /// Example title
+    pub example: String,
+
+    /// Index of the example in the schema
+    pub index: usize,
+
+    /// Position of the `code.rust` field in the schema file
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+struct TestCase {
+    name: String,
+    mode: DoctestMode,
+    code: String,
+    origin: Origin,
+    /// Schema position of the first column of each snippet line
+    lines: Vec<Span>,
+}
+
+/// Builder collecting Rust examples into a test crate
+#[derive(Debug, Clone, Default)]
+pub struct DoctestCrate {
+    config: DoctestConfig,
+    modules: BTreeMap<String, Vec<TestCase>>,
+}
+
+impl DoctestCrate {
+    /// Create an empty crate
+    pub fn new(config: DoctestConfig) -> Self {
+        Self {
+            config,
+            modules: BTreeMap::new(),
+        }
+    }
+
+    /// Add every Rust example of a schema
+    ///
+    /// Examples without a Rust snippet are skipped.
+    pub fn add_schema(&mut self, file: &SchemaFile) {
+        let schema = &file.schema;
+        let module = module_name(schema.topic.split('.').next().unwrap_or(&schema.topic));
+        let cases = self.modules.entry(module).or_default();
+
+        for (index, example) in schema.examples.iter().enumerate() {
+            let code = example.code.rust.trim_end();
+            if code.trim().is_empty() {
+                continue;
+            }
+
+            let path = format!("examples[{}].code.rust", index);
+            let base = identifier(&format!(
+                "{}_{}",
+                schema
+                    .topic
+                    .split_once('.')
+                    .map_or(schema.topic.as_str(), |(_, rest)| rest),
+                example.title
+            ));
+            let mut name = base.clone();
+            let mut suffix = 2;
+            while cases.iter().any(|case| case.name == name) {
+                name = format!("{}_{}", base, suffix);
+                suffix += 1;
+            }
+
+            cases.push(TestCase {
+                name,
+                mode: example.doctest,
+                code: code.to_string(),
+                origin: Origin {
+                    file: file.path.clone(),
+                    topic: schema.topic.clone(),
+                    example: example.title.clone(),
+                    index,
+                    span: file.spans.locate(&path),
+                },
+                lines: (1..=code.lines().count())
+                    .map(|line| file.spans.locate_in_value(&path, line, 1))
+                    .collect(),
+            });
+        }
+    }
+
+    /// Number of generated tests
+    pub fn len(&self) -> usize {
+        self.modules.values().map(Vec::len).sum()
+    }
+
+    /// Check whether no example was added
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of tests with the given marker
+    pub fn count(&self, mode: DoctestMode) -> usize {
+        self.modules
+            .values()
+            .flatten()
+            .filter(|case| case.mode == mode)
+            .count()
+    }
+
+    /// Render the crate files and the source map
+    pub fn generate(&self) -> GeneratedCrate {
+        let mut files = vec![
+            (PathBuf::from("Cargo.toml"), self.manifest()),
+            (PathBuf::from("src/lib.rs"), self.lib_rs()),
+        ];
+        let mut map = SourceMap::default();
+
+        for (module, cases) in self.modules.iter().filter(|(_, cases)| !cases.is_empty()) {
+            let path = format!("src/{}.rs", module);
+            files.push((
+                PathBuf::from(&path),
+                render_module(module, cases, &path, &mut map),
+            ));
+        }
+
+        GeneratedCrate { files, map }
+    }
+
+    fn manifest(&self) -> String {
+        format!(
+            "[package]\nname = \"{}\"\nversion = \"0.0.0\"\nedition = \"2021\"\npublish = false\n\n\
+             [dependencies]\n{}\n\n# Keep the generated crate out of any enclosing workspace\n[workspace]\n",
+            self.config.crate_name,
+            self.config.dependencies.join("\n")
+        )
+    }
+
+    fn lib_rs(&self) -> String {
+        let mut lib = String::from(
+            "//! Rust examples from the MathHook knowledge base\n//!\n\
+             //! Generated by `kb doctest`; do not edit. Run `cargo test` to compile and\n\
+             //! run every example.\n",
+        );
+        for (module, _) in self.modules.iter().filter(|(_, cases)| !cases.is_empty()) {
+            lib.push_str(&format!("\n#[cfg(test)]\nmod {};\n", module));
+        }
+        lib
+    }
+}
+
+/// Render one category module, recording snippet positions in `map`
+fn render_module(module: &str, cases: &[TestCase], path: &str, map: &mut SourceMap) -> String {
+    let category = cases[0].origin.topic.split('.').next().unwrap_or(module);
+    let mut out = format!(
+        "//! Examples from the `{}` category\n\n#![allow(unused_imports, unused_variables, unused_mut, unreachable_code, clippy::all)]\n",
+        category
+    );
+
+    for case in cases {
+        out.push_str(&format!(
+            "\n// {}: \"{}\" ({})\n#[test]\n",
+            case.origin.topic,
+            case.origin.example.replace('\n', " "),
+            case.origin.file
+        ));
+
+        let (indent, code_start) = match case.mode {
+            DoctestMode::Run => {
+                out.push_str(&format!(
+                    "fn {}() -> Result<(), Box<dyn std::error::Error>> {{\n",
+                    case.name
+                ));
+                let start = line_count(&out) + 1;
+                push_indented(&mut out, &case.code, RUN_INDENT);
+                out.push_str(&format!(
+                    "{}    Ok(())\n}}\n",
+                    main_call(&case.code, RUN_INDENT)
+                ));
+                (RUN_INDENT, Some(start))
+            }
+            DoctestMode::NoRun => {
+                out.push_str(&format!(
+                    "fn {}() {{\n    fn example() -> Result<(), Box<dyn std::error::Error>> {{\n",
+                    case.name
+                ));
+                let start = line_count(&out) + 1;
+                push_indented(&mut out, &case.code, NO_RUN_INDENT);
+                out.push_str(&format!(
+                    "{}        Ok(())\n    }}\n    let _ = example;\n}}\n",
+                    main_call(&case.code, NO_RUN_INDENT)
+                ));
+                (NO_RUN_INDENT, Some(start))
+            }
+            DoctestMode::Ignore => {
+                out.push_str(&format!(
+                    "#[ignore = \"marked `doctest: ignore`\"]\nfn {}() {{\n",
+                    case.name
+                ));
+                for line in case.code.lines() {
+                    out.push_str(&format!("    // {}\n", line).replace("    // \n", "    //\n"));
+                }
+                out.push_str("}\n");
+                (RUN_INDENT, None)
+            }
+        };
+
+        map.tests.push(MappedTest {
+            path: path.to_string(),
+            test: format!("{}::{}", module, case.name),
+            code_start: code_start.unwrap_or(0),
+            indent,
+            origin: case.origin.clone(),
+            lines: if code_start.is_some() {
+                case.lines.clone()
+            } else {
+                Vec::new()
+            },
+        });
+    }
+
+    out
+}
+
+/// Snippets that define their own `main` are called from the test
+fn main_call(code: &str, indent: usize) -> String {
+    let pad = " ".repeat(indent);
+    if !code.contains("fn main(") {
+        String::new()
+    } else if code.contains("fn main() ->") {
+        format!("{}main()?;\n", pad)
+    } else {
+        format!("{}main();\n", pad)
+    }
+}
+
+fn push_indented(out: &mut String, code: &str, indent: usize) {
+    for line in code.lines() {
+        if line.trim().is_empty() {
+            out.push('\n');
+        } else {
+            out.push_str(&format!("{}{}\n", " ".repeat(indent), line));
+        }
+    }
+}
+
+fn line_count(text: &str) -> usize {
+    text.matches('\n').count()
+}
+
+/// Turn free text into a snake_case Rust identifier
+fn identifier(text: &str) -> String {
+    let mut ident = String::new();
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            ident.push(c.to_ascii_lowercase());
+        } else if !ident.ends_with('_') {
+            ident.push('_');
+        }
+    }
+    let ident = ident.trim_matches('_').to_string();
+
+    if ident.is_empty() {
+        "example".to_string()
+    } else if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("example_{}", ident)
+    } else {
+        ident
+    }
+}
+
+/// Module name for a topic category
+fn module_name(category: &str) -> String {
+    let name = identifier(category);
+    if RESERVED_MODULES.contains(&name.as_str()) {
+        format!("{}_examples", name)
+    } else {
+        name
+    }
+}
+
+/// Rendered crate, ready to be written to disk
+#[derive(Debug, Clone)]
+pub struct GeneratedCrate {
+    files: Vec<(PathBuf, String)>,
+    map: SourceMap,
+}
+
+impl GeneratedCrate {
+    /// Generated files, relative to the crate root
+    pub fn files(&self) -> &[(PathBuf, String)] {
+        &self.files
+    }
+
+    /// Mapping from generated code back to schema examples
+    pub fn source_map(&self) -> &SourceMap {
+        &self.map
+    }
+
+    /// Write the crate to `dir`
+    ///
+    /// Category modules left over from a previous run are removed so `cargo
+    /// test` only sees the current examples.
+    pub fn write(&self, dir: &Path) -> kb_core::Result<()> {
+        std::fs::create_dir_all(dir.join("src"))?;
+
+        let current: BTreeSet<PathBuf> =
+            self.files.iter().map(|(path, _)| dir.join(path)).collect();
+        for entry in std::fs::read_dir(dir.join("src"))? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "rs") && !current.contains(&path) {
+                std::fs::remove_file(&path)?;
+            }
+        }
+
+        for (path, content) in &self.files {
+            let path = dir.join(path);
+            // Leave unchanged files alone so cargo does not rebuild them
+            if std::fs::read_to_string(&path).ok().as_deref() != Some(content.as_str()) {
+                std::fs::write(&path, content)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MappedTest {
+    path: String,
+    test: String,
+    /// Generated line of the first snippet line (0 for ignored tests)
+    code_start: usize,
+    indent: usize,
+    origin: Origin,
+    lines: Vec<Span>,
+}
+
+/// Maps generated tests and source positions back to schema examples
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    tests: Vec<MappedTest>,
+}
+
+impl SourceMap {
+    /// Origin of a test by its path in the crate (e.g. `calculus::derivative_power_rule`)
+    pub fn origin_of_test(&self, test: &str) -> Option<&Origin> {
+        self.tests
+            .iter()
+            .find(|t| t.test == test)
+            .map(|t| &t.origin)
+    }
+
+    /// Map a 1-based position in a generated file to the schema
+    ///
+    /// Positions inside a snippet map to the matching schema line and column;
+    /// positions in generated wrapper code of a test map to its `code.rust`
+    /// field. Returns `None` for positions outside any test.
+    pub fn locate(&self, path: &str, line: usize, column: usize) -> Option<(&Origin, Span)> {
+        let path = path.replace('\\', "/");
+        let tests: Vec<&MappedTest> = self
+            .tests
+            .iter()
+            .filter(|t| path == t.path || path.ends_with(&format!("/{}", t.path)))
+            .collect();
+
+        for test in &tests {
+            if test.code_start > 0
+                && line >= test.code_start
+                && line < test.code_start + test.lines.len()
+            {
+                let start = test.lines[line - test.code_start];
+                let column = start.column + column.saturating_sub(1 + test.indent);
+                return Some((&test.origin, Span::new(start.line, column)));
+            }
+        }
+
+        // Wrapper code: attribute to the last test starting before the line
+        tests
+            .iter()
+            .filter(|t| t.code_start > 0 && t.code_start <= line)
+            .max_by_key(|t| t.code_start)
+            .map(|t| (&t.origin, t.origin.span))
+    }
+
+    /// Number of mapped tests
+    pub fn len(&self) -> usize {
+        self.tests.len()
+    }
+
+    /// Check whether the map is empty
+    pub fn is_empty(&self) -> bool {
+        self.tests.is_empty()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use kb_core::schema::SpanMap;
+
+    pub(crate) const SCHEMA: &str = r#"topic: "calculus.derivative"
+title: "Derivatives"
+description: "Differentiation"
+examples:
+  - title: "Power Rule"
+    code:
+      rust: |
+        use mathhook::prelude::*;
+        let x = symbol!(x);
+        println!("{}", x);
+  - title: "Power Rule"
+    doctest: no_run
+    code:
+      rust: |
+        let y = 2;
+  - title: "Slow"
+    doctest: ignore
+    code:
+      rust: |
+        let z = 3;
+  - title: "Python only"
+    code:
+      python: "print(1)"
+"#;
+
+    pub(crate) fn sample_crate() -> DoctestCrate {
+        let file = SchemaFile {
+            path: "schemas/calculus/derivative.yaml".to_string(),
+            schema: serde_yaml::from_str(SCHEMA).unwrap(),
+            spans: SpanMap::parse(SCHEMA),
+            unknown_keys: Vec::new(),
+        };
+        let mut krate = DoctestCrate::new(DoctestConfig::default());
+        krate.add_schema(&file);
+        krate
+    }
+
+    fn file<'a>(generated: &'a GeneratedCrate, path: &str) -> &'a str {
+        &generated
+            .files()
+            .iter()
+            .find(|(p, _)| p == Path::new(path))
+            .unwrap()
+            .1
+    }
+
+    #[test]
+    fn test_identifiers() {
+        assert_eq!(
+            identifier("derivative_Power Rule (x^2)"),
+            "derivative_power_rule_x_2"
+        );
+        assert_eq!(identifier("2D plots"), "example_2d_plots");
+        assert_eq!(module_name("core"), "core_examples");
+        assert_eq!(module_name("getting-started"), "getting_started");
+    }
+
+    #[test]
+    fn test_generated_modules() {
+        let krate = sample_crate();
+        assert_eq!(krate.len(), 3);
+        assert_eq!(krate.count(DoctestMode::NoRun), 1);
+
+        let generated = krate.generate();
+        assert!(file(&generated, "Cargo.toml")
+            .contains("[dependencies]\nmathhook = \"*\"\nmathhook-core = \"*\"\n"));
+        assert!(file(&generated, "src/lib.rs").contains("#[cfg(test)]\nmod calculus;\n"));
+
+        let module = file(&generated, "src/calculus.rs");
+        assert!(module.contains(
+            "#[test]\nfn derivative_power_rule() -> Result<(), Box<dyn std::error::Error>> {\n    use mathhook::prelude::*;\n"
+        ));
+        assert!(module.contains("fn derivative_power_rule_2() {\n    fn example()"));
+        assert!(module.contains("        let y = 2;\n"));
+        assert!(module.contains("#[ignore = \"marked `doctest: ignore`\"]\nfn derivative_slow() {\n    // let z = 3;\n}\n"));
+        assert!(!module.contains("print(1)"));
+    }
+
+    #[test]
+    fn test_source_map() {
+        let generated = sample_crate().generate();
+        let module = file(&generated, "src/calculus.rs");
+        let line = module
+            .lines()
+            .position(|l| l.contains("let x = symbol!(x);"))
+            .unwrap()
+            + 1;
+
+        let map = generated.source_map();
+        let (origin, span) = map.locate("src/calculus.rs", line, 13).unwrap();
+        assert_eq!(origin.example, "Power Rule");
+        assert_eq!(origin.index, 0);
+        // `symbol!` starts at column 9 of the snippet line, indented 8 in the schema
+        assert_eq!(span, Span::new(9, 17));
+
+        let line = module
+            .lines()
+            .position(|l| l.contains("let y = 2;"))
+            .unwrap()
+            + 1;
+        assert_eq!(
+            map.locate("/tmp/out/src/calculus.rs", line, 9).unwrap().1,
+            Span::new(15, 9)
+        );
+
+        assert_eq!(
+            map.origin_of_test("calculus::derivative_slow")
+                .unwrap()
+                .index,
+            2
+        );
+        assert!(map.locate("src/calculus.rs", 1, 1).is_none());
+    }
+}
@@ -0,0 +1,34 @@
+//! # MathHook KB Doctests
+//!
+//! Turns the Rust snippet of every schema example into a Cargo test crate so
+//! `cargo test` compiles each example against the real `mathhook` crate.
+//! The crate depends on `mathhook` and `mathhook-core` by default, matching the
+//! imports examples use; pass other dependency lines via
+//! [`DoctestConfig::dependencies`].
+//!
+//! ## Features
+//!
+//! - One `#[test]` per example, in one module per topic category
+//! - Per-example `doctest: no_run` (compile only) and `doctest: ignore` markers
+//! - Compiler errors and failing tests mapped back to the schema file, example
+//!   title and line
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use kb_doctest::{DoctestConfig, DoctestCrate};
+//! use kb_core::schema::SchemaFile;
+//!
+//! let mut krate = DoctestCrate::new(DoctestConfig::default());
+//! krate.add_schema(&SchemaFile::parse(Path::new("schemas/calculus/derivative.yaml"))?);
+//!
+//! let generated = krate.generate();
+//! generated.write(Path::new("target/kb-doctests"))?;
+//! let report = generated.test(Path::new("target/kb-doctests"), "cargo", false)?;
+//! ```
+
+mod cargo;
+mod generator;
+
+pub use cargo::TestReport;
+pub use generator::{DoctestConfig, DoctestCrate, GeneratedCrate, Origin, SourceMap};
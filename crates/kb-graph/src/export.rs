@@ -0,0 +1,273 @@
+//! Topic graph export formats
+//!
+//! JSON is meant for the docs site; DOT and Mermaid render the same graph with
+//! one cluster per category, dashed hierarchy edges and highlighted orphans.
+
+use crate::graph::{EdgeKind, TopicGraph};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Supported export formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// Nodes, edges, clusters, orphans and dangling references
+    Json,
+
+    /// GraphViz DOT
+    Dot,
+
+    /// Mermaid flowchart
+    Mermaid,
+}
+
+impl GraphFormat {
+    /// All formats, in export order
+    pub const ALL: [GraphFormat; 3] = [GraphFormat::Json, GraphFormat::Dot, GraphFormat::Mermaid];
+
+    /// Parse a format name ("json", "dot", "mermaid")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(Self::Json),
+            "dot" => Some(Self::Dot),
+            "mermaid" => Some(Self::Mermaid),
+            _ => None,
+        }
+    }
+
+    /// Format name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Dot => "dot",
+            Self::Mermaid => "mermaid",
+        }
+    }
+
+    /// File extension for exported files
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Dot => "dot",
+            Self::Mermaid => "mmd",
+        }
+    }
+}
+
+impl TopicGraph {
+    /// Render the graph in the given format
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Json => {
+                serde_json::to_string_pretty(&self.to_json()).expect("graph JSON is serializable")
+            }
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::Mermaid => self.to_mermaid(),
+        }
+    }
+
+    /// JSON document with nodes, edges, clusters, orphans and dangling references
+    pub fn to_json(&self) -> serde_json::Value {
+        let orphans = self.orphans();
+        let nodes: Vec<serde_json::Value> = self
+            .nodes()
+            .map(|node| {
+                serde_json::json!({
+                    "id": node.id,
+                    "title": node.title,
+                    "category": node.category,
+                    "orphan": orphans.contains(&node.id.as_str()),
+                })
+            })
+            .collect();
+        let clusters: serde_json::Map<String, serde_json::Value> = self
+            .clusters()
+            .into_iter()
+            .map(|(category, nodes)| {
+                let ids: Vec<&str> = nodes.iter().map(|n| n.id.as_str()).collect();
+                (category.to_string(), serde_json::json!(ids))
+            })
+            .collect();
+
+        serde_json::json!({
+            "nodes": nodes,
+            "edges": self.edges().collect::<Vec<_>>(),
+            "clusters": clusters,
+            "orphans": orphans,
+            "dangling": self.dangling().collect::<Vec<_>>(),
+        })
+    }
+
+    /// GraphViz DOT with one `cluster_<category>` subgraph per category
+    pub fn to_dot(&self) -> String {
+        let orphans: BTreeSet<&str> = self.orphans().into_iter().collect();
+        let mut dot = String::from("digraph topics {\n");
+        dot.push_str("  rankdir=LR;\n");
+        dot.push_str("  node [shape=box, style=rounded];\n");
+
+        for (category, nodes) in self.clusters() {
+            dot.push_str(&format!(
+                "\n  subgraph \"cluster_{}\" {{\n",
+                escape_dot(category)
+            ));
+            dot.push_str(&format!("    label=\"{}\";\n", escape_dot(category)));
+            for node in nodes {
+                let style = if orphans.contains(node.id.as_str()) {
+                    ", style=\"rounded,dashed\""
+                } else {
+                    ""
+                };
+                dot.push_str(&format!(
+                    "    \"{}\" [label=\"{}\"{}];\n",
+                    escape_dot(&node.id),
+                    escape_dot(&node.title),
+                    style
+                ));
+            }
+            dot.push_str("  }\n");
+        }
+
+        dot.push('\n');
+        for edge in self.edges() {
+            let style = match edge.kind {
+                EdgeKind::Related => "",
+                EdgeKind::Parent => " [style=dashed, arrowhead=none]",
+            };
+            dot.push_str(&format!(
+                "  \"{}\" -> \"{}\"{};\n",
+                escape_dot(&edge.source),
+                escape_dot(&edge.target),
+                style
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Mermaid flowchart with one subgraph per category
+    ///
+    /// Mermaid ids are limited to word characters, so nodes are numbered in
+    /// topic order (`t0`, `t1`, ...) and labelled with their title. Replacing
+    /// dots and dashes instead would merge `ode.first-order` and `ode.first_order`.
+    pub fn to_mermaid(&self) -> String {
+        let ids: BTreeMap<&str, String> = self
+            .nodes()
+            .enumerate()
+            .map(|(index, node)| (node.id.as_str(), format!("t{}", index)))
+            .collect();
+        let mut mermaid = String::from("flowchart LR\n");
+
+        for (index, (category, nodes)) in self.clusters().into_iter().enumerate() {
+            mermaid.push_str(&format!(
+                "  subgraph cluster{}[\"{}\"]\n",
+                index,
+                escape_mermaid(category)
+            ));
+            for node in nodes {
+                mermaid.push_str(&format!(
+                    "    {}[\"{}\"]\n",
+                    ids[node.id.as_str()],
+                    escape_mermaid(&node.title)
+                ));
+            }
+            mermaid.push_str("  end\n");
+        }
+
+        for edge in self.edges() {
+            let arrow = match edge.kind {
+                EdgeKind::Related => "-->",
+                EdgeKind::Parent => "-.-",
+            };
+            mermaid.push_str(&format!(
+                "  {} {} {}\n",
+                ids[edge.source.as_str()],
+                arrow,
+                ids[edge.target.as_str()]
+            ));
+        }
+
+        let orphans = self.orphans();
+        if !orphans.is_empty() {
+            mermaid.push_str("  classDef orphan stroke-dasharray: 5 5\n");
+            let orphan_ids: Vec<&str> = orphans.iter().map(|id| ids[id].as_str()).collect();
+            mermaid.push_str(&format!("  class {} orphan\n", orphan_ids.join(",")));
+        }
+
+        mermaid
+    }
+}
+
+/// Escape a DOT quoted string
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Escape a Mermaid quoted label
+fn escape_mermaid(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::tests::{sample_graph, schema};
+
+    #[test]
+    fn test_format_names() {
+        for format in GraphFormat::ALL {
+            assert_eq!(GraphFormat::from_name(format.name()), Some(format));
+        }
+        assert_eq!(GraphFormat::from_name("svg"), None);
+        assert_eq!(GraphFormat::Mermaid.file_extension(), "mmd");
+    }
+
+    #[test]
+    fn test_json_export() {
+        let json = sample_graph().to_json();
+
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 5);
+        assert_eq!(json["nodes"][0]["id"], "algebra.solve");
+        assert_eq!(json["nodes"][0]["orphan"], true);
+        assert_eq!(json["edges"][0]["kind"], "related");
+        assert_eq!(json["edges"][4]["kind"], "parent");
+        assert_eq!(json["clusters"]["algebra"][0], "algebra.solve");
+        assert_eq!(json["orphans"].as_array().unwrap().len(), 2);
+        assert_eq!(json["dangling"][0]["target"], "core.missing");
+    }
+
+    #[test]
+    fn test_dot_export() {
+        let dot = sample_graph().to_dot();
+
+        assert!(dot.starts_with("digraph topics {\n"));
+        assert!(dot.contains("  subgraph \"cluster_calculus\" {\n    label=\"calculus\";\n"));
+        assert!(dot.contains(
+            "    \"algebra.solve\" [label=\"Equation Solving\", style=\"rounded,dashed\"];\n"
+        ));
+        assert!(dot.contains("  \"calculus.derivative\" -> \"calculus.integral\";\n"));
+        assert!(dot.contains("  \"calculus.limits\" -> \"calculus.limits.one_sided\" [style=dashed, arrowhead=none];\n"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_mermaid_export() {
+        let mermaid = sample_graph().to_mermaid();
+
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.contains("  subgraph cluster1[\"calculus\"]\n"));
+        assert!(mermaid.contains("    t4[\"One-Sided Limits\"]\n"));
+        assert!(mermaid.contains("  t1 --> t2\n"));
+        assert!(mermaid.contains("  t3 -.- t4\n"));
+        assert!(mermaid.contains("  class t0,t4 orphan\n"));
+    }
+
+    #[test]
+    fn test_mermaid_ids_do_not_collide() {
+        let graph = TopicGraph::from_schemas(&[
+            schema("ode.first-order", "First-Order ODEs", &["ode.first_order"]),
+            schema("ode.first_order", "First Order", &[]),
+        ]);
+        let mermaid = graph.to_mermaid();
+
+        assert!(mermaid.contains("    t0[\"First-Order ODEs\"]\n    t1[\"First Order\"]\n"));
+        assert!(mermaid.contains("  t0 --> t1\n"));
+    }
+}
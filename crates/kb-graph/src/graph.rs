@@ -0,0 +1,274 @@
+//! Topic graph construction
+//!
+//! Nodes are schema topics; edges come from `related_topics` and from the dotted
+//! topic hierarchy.
+
+use kb_core::Schema;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A topic in the graph
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Node {
+    /// Topic identifier (e.g., "calculus.derivative")
+    pub id: String,
+
+    /// Human-readable title
+    pub title: String,
+
+    /// Category (first segment of the topic)
+    pub category: String,
+}
+
+/// How two topics are connected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    /// `source` lists `target` in its `related_topics`
+    Related,
+
+    /// `target` is nested under `source` in the dotted topic hierarchy
+    Parent,
+}
+
+/// A directed edge between two topics
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Edge {
+    pub source: String,
+    pub target: String,
+    pub kind: EdgeKind,
+}
+
+/// A `related_topics` entry that does not name an existing topic
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct DanglingReference {
+    pub source: String,
+    pub target: String,
+}
+
+/// Knowledge graph of all schema topics
+#[derive(Debug, Clone, Default)]
+pub struct TopicGraph {
+    nodes: BTreeMap<String, Node>,
+    edges: BTreeSet<Edge>,
+    dangling: BTreeSet<DanglingReference>,
+}
+
+impl TopicGraph {
+    /// Build the graph from a set of schemas
+    ///
+    /// Duplicate topics keep the first schema. References to unknown topics are
+    /// not turned into edges; they are available from [`TopicGraph::dangling`].
+    pub fn from_schemas(schemas: &[Schema]) -> Self {
+        let mut graph = Self::default();
+
+        for schema in schemas {
+            graph
+                .nodes
+                .entry(schema.topic.clone())
+                .or_insert_with(|| Node {
+                    id: schema.topic.clone(),
+                    title: schema.title.clone(),
+                    category: category_of(&schema.topic).to_string(),
+                });
+        }
+
+        for schema in schemas {
+            for related in &schema.related_topics {
+                if related == &schema.topic {
+                    continue;
+                }
+                if graph.nodes.contains_key(related) {
+                    graph.edges.insert(Edge {
+                        source: schema.topic.clone(),
+                        target: related.clone(),
+                        kind: EdgeKind::Related,
+                    });
+                } else {
+                    graph.dangling.insert(DanglingReference {
+                        source: schema.topic.clone(),
+                        target: related.clone(),
+                    });
+                }
+            }
+        }
+
+        // Link each topic to its nearest existing ancestor in the dotted hierarchy
+        let ids: Vec<String> = graph.nodes.keys().cloned().collect();
+        for id in &ids {
+            let mut ancestor = id.as_str();
+            while let Some((parent, _)) = ancestor.rsplit_once('.') {
+                if graph.nodes.contains_key(parent) {
+                    graph.edges.insert(Edge {
+                        source: parent.to_string(),
+                        target: id.clone(),
+                        kind: EdgeKind::Parent,
+                    });
+                    break;
+                }
+                ancestor = parent;
+            }
+        }
+
+        graph
+    }
+
+    /// All topics, sorted by id
+    pub fn nodes(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.values()
+    }
+
+    /// All edges, sorted by source, target and kind
+    pub fn edges(&self) -> impl Iterator<Item = &Edge> {
+        self.edges.iter()
+    }
+
+    /// References to topics that do not exist
+    pub fn dangling(&self) -> impl Iterator<Item = &DanglingReference> {
+        self.dangling.iter()
+    }
+
+    /// Look up a topic
+    pub fn node(&self, id: &str) -> Option<&Node> {
+        self.nodes.get(id)
+    }
+
+    /// Topics that no other topic links to through `related_topics`
+    pub fn orphans(&self) -> Vec<&str> {
+        let linked: BTreeSet<&str> = self
+            .edges
+            .iter()
+            .filter(|edge| edge.kind == EdgeKind::Related)
+            .map(|edge| edge.target.as_str())
+            .collect();
+
+        self.nodes
+            .keys()
+            .map(String::as_str)
+            .filter(|id| !linked.contains(id))
+            .collect()
+    }
+
+    /// Topics grouped by category
+    pub fn clusters(&self) -> BTreeMap<&str, Vec<&Node>> {
+        let mut clusters: BTreeMap<&str, Vec<&Node>> = BTreeMap::new();
+        for node in self.nodes.values() {
+            clusters
+                .entry(node.category.as_str())
+                .or_default()
+                .push(node);
+        }
+        clusters
+    }
+
+    /// Number of topics
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Check whether the graph has no topics
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+fn category_of(topic: &str) -> &str {
+    topic.split('.').next().unwrap_or(topic)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn schema(topic: &str, title: &str, related: &[&str]) -> Schema {
+        let yaml = format!(
+            "topic: \"{}\"\ntitle: \"{}\"\ndescription: \"Test\"\nrelated_topics: [{}]\n",
+            topic,
+            title,
+            related
+                .iter()
+                .map(|r| format!("\"{}\"", r))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        serde_yaml::from_str(&yaml).unwrap()
+    }
+
+    pub(crate) fn sample_graph() -> TopicGraph {
+        TopicGraph::from_schemas(&[
+            schema(
+                "calculus.derivative",
+                "Derivatives",
+                &["calculus.integral", "calculus.limits"],
+            ),
+            schema("calculus.integral", "Integrals", &["calculus.derivative"]),
+            schema("calculus.limits", "Limits", &[]),
+            schema(
+                "calculus.limits.one_sided",
+                "One-Sided Limits",
+                &["core.missing"],
+            ),
+            schema(
+                "algebra.solve",
+                "Equation Solving",
+                &["calculus.derivative"],
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_edges() {
+        let graph = sample_graph();
+        let edges: Vec<(&str, &str, EdgeKind)> = graph
+            .edges()
+            .map(|e| (e.source.as_str(), e.target.as_str(), e.kind))
+            .collect();
+
+        assert_eq!(
+            edges,
+            vec![
+                ("algebra.solve", "calculus.derivative", EdgeKind::Related),
+                (
+                    "calculus.derivative",
+                    "calculus.integral",
+                    EdgeKind::Related
+                ),
+                ("calculus.derivative", "calculus.limits", EdgeKind::Related),
+                (
+                    "calculus.integral",
+                    "calculus.derivative",
+                    EdgeKind::Related
+                ),
+                (
+                    "calculus.limits",
+                    "calculus.limits.one_sided",
+                    EdgeKind::Parent
+                ),
+            ]
+        );
+        assert_eq!(
+            graph.dangling().collect::<Vec<_>>(),
+            vec![&DanglingReference {
+                source: "calculus.limits.one_sided".to_string(),
+                target: "core.missing".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_orphans_and_clusters() {
+        let graph = sample_graph();
+
+        assert_eq!(
+            graph.orphans(),
+            vec!["algebra.solve", "calculus.limits.one_sided"]
+        );
+
+        let clusters = graph.clusters();
+        assert_eq!(
+            clusters.keys().copied().collect::<Vec<_>>(),
+            vec!["algebra", "calculus"]
+        );
+        assert_eq!(clusters["calculus"].len(), 4);
+    }
+}
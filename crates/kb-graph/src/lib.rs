@@ -0,0 +1,31 @@
+//! # MathHook KB Topic Graph
+//!
+//! Builds the knowledge graph formed by schema topics and exports it for the
+//! docs site topic map.
+//!
+//! ## Features
+//!
+//! - Nodes for every schema topic, clustered by category (first topic segment)
+//! - `related_topics` edges and dotted-hierarchy edges (`calculus.limits` ->
+//!   `calculus.limits.one_sided`)
+//! - Orphan detection (topics nobody links to) and dangling references
+//! - Export as JSON (nodes + edges), GraphViz DOT and Mermaid
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use kb_graph::{GraphFormat, TopicGraph};
+//! use kb_core::Schema;
+//!
+//! let schemas = Schema::load_from_directory(Path::new("schemas/"))?;
+//! let graph = TopicGraph::from_schemas(&schemas);
+//!
+//! std::fs::write("topic-graph.json", graph.render(GraphFormat::Json))?;
+//! println!("Orphans: {:?}", graph.orphans());
+//! ```
+
+mod export;
+mod graph;
+
+pub use export::GraphFormat;
+pub use graph::{DanglingReference, Edge, EdgeKind, Node, TopicGraph};
@@ -6,6 +6,9 @@
 use kb_core::generator::OutputGenerator;
 use kb_core::{Result, Schema};
 
+/// Output format version; bump when the serialized schema layout changes
+const FORMAT_VERSION: &str = "2";
+
 /// JSON schema data generator
 pub struct JsonGenerator;
 
@@ -31,6 +34,14 @@ impl OutputGenerator for JsonGenerator {
         "json"
     }
 
+    fn description(&self) -> &str {
+        "Schema data for Vue site (.json)"
+    }
+
+    fn version(&self) -> &str {
+        FORMAT_VERSION
+    }
+
     fn generate(&self, schema: &Schema) -> Result<String> {
         // Serialize the schema directly to JSON
         let json = serde_json::to_string_pretty(schema)?;
@@ -82,6 +93,7 @@ mod tests {
                     nodejs: "const f = x**2;".to_string(),
                 },
                 expected_output: Some("2*x".to_string()),
+                doctest: Default::default(),
             }],
             article: None,
             use_cases: vec![],
@@ -1,28 +1,90 @@
-use crate::notebook::Notebook;
+use crate::kernel::Kernel;
+use crate::notebook::{CellType, Notebook};
 use crate::templates::TemplateManager;
+use kb_core::generator::execute::NotebookExecutor;
 use kb_core::generator::OutputGenerator;
 /// Jupyter notebook generator implementation
 ///
 /// Implements the OutputGenerator trait to generate Jupyter notebooks from KB schemas.
+use kb_core::schema::Example;
 use kb_core::{Result, Schema};
 use std::collections::HashMap;
 
+/// Output format version; bump whenever the generated notebook cells or metadata change
+const FORMAT_VERSION: &str = "3";
+
+/// Format version of notebooks whose outputs were filled in by an executor
+const EXECUTED_FORMAT_VERSION: &str = "3+executed";
+
 /// Jupyter notebook generator
 pub struct JupyterGenerator {
     templates: TemplateManager,
+    kernel: Kernel,
+    executor: Option<NotebookExecutor>,
 }
 
 impl JupyterGenerator {
-    /// Create a new Jupyter generator
+    /// Create a new Jupyter generator for the Python kernel
     pub fn new() -> Self {
+        Self::with_kernel(Kernel::Python)
+    }
+
+    /// Create a Jupyter generator whose notebooks run on the given kernel
+    pub fn with_kernel(kernel: Kernel) -> Self {
         Self {
             templates: TemplateManager::new().expect("Failed to initialize templates"),
+            kernel,
+            executor: None,
         }
     }
 
+    /// Execute each notebook after generating it, storing the cell outputs
+    pub fn with_executor(mut self, executor: NotebookExecutor) -> Self {
+        self.executor = Some(executor);
+        self
+    }
+
+    pub fn kernel(&self) -> Kernel {
+        self.kernel
+    }
+
+    /// Add an example's explanation and, if it has code for the kernel, a code cell
+    ///
+    /// The index of the code cell is recorded in `example_cells`.
+    fn add_example<'a>(
+        &self,
+        notebook: &mut Notebook,
+        example: &'a Example,
+        example_cells: &mut Vec<(usize, &'a Example)>,
+    ) -> Result<()> {
+        let code = self.kernel.code(&example.code);
+        let example_md = self.templates.render_example(
+            &example.title,
+            &example.explanation,
+            self.kernel.fence(),
+            self.kernel.language(),
+            code,
+            example.expected_output.as_ref(),
+        )?;
+        notebook.add_markdown(example_md);
+        if !code.trim().is_empty() {
+            notebook.add_code(code.to_string());
+            example_cells.push((notebook.cells.len() - 1, example));
+        }
+        Ok(())
+    }
+
     /// Generate notebook from schema (internal implementation)
-    fn generate_notebook(&self, schema: &Schema) -> Result<Notebook> {
+    ///
+    /// Also returns the index of each example's code cell.
+    fn generate_notebook<'a>(
+        &self,
+        schema: &'a Schema,
+    ) -> Result<(Notebook, Vec<(usize, &'a Example)>)> {
         let mut notebook = Notebook::new();
+        let mut example_cells = Vec::new();
+        notebook.metadata.kernelspec = Some(self.kernel.kernel_spec());
+        notebook.metadata.language_info = Some(self.kernel.language_info());
 
         // Add header
         let header = self
@@ -30,6 +92,10 @@ impl JupyterGenerator {
             .render_header(&schema.title, &schema.description)?;
         notebook.add_markdown(header);
 
+        if let Some(setup) = self.kernel.setup_cell() {
+            notebook.add_code(setup.to_string());
+        }
+
         // If schema has article content, use rich article format
         if let Some(article) = &schema.article {
             use kb_core::schema::Article;
@@ -80,18 +146,10 @@ impl JupyterGenerator {
                         // Add code examples from section
                         for example_ref in &section.code_examples {
                             // Find the example in schema.examples
-                            if let Some(example) = schema.examples.iter().find(|e| e.title == *example_ref)
+                            if let Some(example) =
+                                schema.examples.iter().find(|e| e.title == *example_ref)
                             {
-                                let example_md = self.templates.render_example(
-                                    &example.title,
-                                    &example.explanation,
-                                    &example.code.python,
-                                    example.expected_output.as_ref(),
-                                )?;
-                                notebook.add_markdown(example_md);
-
-                                // Add interactive code cell
-                                notebook.add_code(example.code.python.clone());
+                                self.add_example(&mut notebook, example, &mut example_cells)?;
                             }
                         }
                     }
@@ -186,14 +244,7 @@ impl JupyterGenerator {
             // Add code examples
             notebook.add_markdown("## Examples\n".to_string());
             for example in &schema.examples {
-                let example_md = self.templates.render_example(
-                    &example.title,
-                    &example.explanation,
-                    &example.code.python,
-                    example.expected_output.as_ref(),
-                )?;
-                notebook.add_markdown(example_md);
-                notebook.add_code(example.code.python.clone());
+                self.add_example(&mut notebook, example, &mut example_cells)?;
             }
 
             // Add use cases if present
@@ -215,7 +266,7 @@ impl JupyterGenerator {
             }
         }
 
-        Ok(notebook)
+        Ok((notebook, example_cells))
     }
 }
 
@@ -227,15 +278,44 @@ impl Default for JupyterGenerator {
 
 impl OutputGenerator for JupyterGenerator {
     fn name(&self) -> &str {
-        "jupyter"
+        self.kernel.generator_name()
     }
 
     fn file_extension(&self) -> &str {
         "ipynb"
     }
 
+    fn description(&self) -> &str {
+        match self.kernel {
+            Kernel::Python => "Interactive Jupyter notebooks (.ipynb)",
+            Kernel::Rust => "Interactive Jupyter notebooks for the evcxr Rust kernel (.ipynb)",
+            Kernel::JavaScript => {
+                "Interactive Jupyter notebooks for the IJavascript kernel (.ipynb)"
+            }
+        }
+    }
+
+    fn version(&self) -> &str {
+        match self.executor {
+            Some(_) => EXECUTED_FORMAT_VERSION,
+            None => FORMAT_VERSION,
+        }
+    }
+
     fn generate(&self, schema: &Schema) -> Result<String> {
-        let notebook = self.generate_notebook(schema)?;
+        let (mut notebook, example_cells) = self.generate_notebook(schema)?;
+
+        if let Some(executor) = &self.executor {
+            let executed = executor.execute(&serde_json::to_value(&notebook)?)?;
+            executor.check(&executed, &example_cells)?;
+            for (cell, result) in notebook.cells.iter_mut().zip(executed) {
+                if cell.cell_type == CellType::Code {
+                    cell.outputs = Some(result.outputs);
+                    cell.execution_count = Some(result.execution_count);
+                }
+            }
+        }
+
         let json = notebook.to_json()?;
         Ok(json)
     }
@@ -289,7 +369,10 @@ impl OutputGenerator for JupyterGenerator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(unix)]
+    use kb_core::generator::execute::fake_jupyter;
     use kb_core::schema::*;
+    use serde_json::{json, Value};
 
     fn create_simple_schema() -> Schema {
         Schema {
@@ -311,6 +394,7 @@ mod tests {
                     nodejs: "const x = 2;".to_string(),
                 },
                 expected_output: Some("2".to_string()),
+                doctest: Default::default(),
             }],
             article: None,
             use_cases: vec![],
@@ -360,6 +444,89 @@ mod tests {
         assert!(validation.is_err());
     }
 
+    #[test]
+    fn test_kernels_use_their_language() {
+        let schema = create_simple_schema();
+        let notebook = |kernel| {
+            let output = JupyterGenerator::with_kernel(kernel)
+                .generate(&schema)
+                .unwrap();
+            serde_json::from_str::<serde_json::Value>(&output).unwrap()
+        };
+        let code_cells = |notebook: &serde_json::Value| -> Vec<String> {
+            notebook["cells"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|cell| cell["cell_type"] == "code")
+                .map(|cell| {
+                    cell["source"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|l| l.as_str().unwrap())
+                        .collect()
+                })
+                .collect()
+        };
+
+        let python = notebook(Kernel::Python);
+        assert_eq!(python["metadata"]["kernelspec"]["name"], "python3");
+        assert_eq!(code_cells(&python), vec!["x = 2\n"]);
+
+        let rust = notebook(Kernel::Rust);
+        assert_eq!(rust["metadata"]["kernelspec"]["name"], "rust");
+        assert_eq!(rust["metadata"]["language_info"]["file_extension"], ".rs");
+        assert_eq!(
+            code_cells(&rust),
+            vec![":dep mathhook\nuse mathhook::prelude::*;\n", "let x = 2;\n"]
+        );
+
+        let js = notebook(Kernel::JavaScript);
+        assert_eq!(js["metadata"]["kernelspec"]["name"], "javascript");
+        assert_eq!(js["metadata"]["language_info"]["name"], "javascript");
+        assert_eq!(code_cells(&js), vec!["const x = 2;\n"]);
+        assert!(js.to_string().contains("```javascript"));
+
+        // An example without a snippet in the kernel's language gets a note instead of code
+        let mut schema = schema;
+        schema.examples[0].code.rust.clear();
+        let rust = JupyterGenerator::with_kernel(Kernel::Rust)
+            .generate(&schema)
+            .unwrap();
+        let rust: serde_json::Value = serde_json::from_str(&rust).unwrap();
+        assert_eq!(
+            code_cells(&rust),
+            vec![":dep mathhook\nuse mathhook::prelude::*;\n"]
+        );
+        assert!(rust
+            .to_string()
+            .contains("*No Rust version of this example.*"));
+        assert!(!rust.to_string().contains("**Code:**"));
+
+        let names: Vec<_> = Kernel::ALL
+            .iter()
+            .map(|kernel| JupyterGenerator::with_kernel(*kernel).name().to_string())
+            .collect();
+        assert_eq!(names, vec!["jupyter", "jupyter-rust", "jupyter-js"]);
+    }
+
+    #[test]
+    fn test_rust_notebook_is_reproducible() {
+        let schema = create_simple_schema();
+        let first = JupyterGenerator::with_kernel(Kernel::Rust)
+            .generate(&schema)
+            .unwrap();
+        let second = JupyterGenerator::with_kernel(Kernel::Rust)
+            .generate(&schema)
+            .unwrap();
+        assert_eq!(first, second);
+
+        // language_info keys beyond the named fields are written sorted
+        let codemirror = first.find("\"codemirror_mode\"").unwrap();
+        assert!(codemirror < first.find("\"pygment_lexer\"").unwrap());
+    }
+
     #[test]
     fn test_generate_with_multiple_examples() {
         let generator = JupyterGenerator::new();
@@ -373,6 +540,7 @@ mod tests {
                 nodejs: "const y = 3;".to_string(),
             },
             expected_output: None,
+            doctest: Default::default(),
         });
 
         let result = generator.generate(&schema);
@@ -382,4 +550,58 @@ mod tests {
         assert!(output.contains("Simple Example"));
         assert!(output.contains("Second Example"));
     }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_execute_fills_code_cells() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut schema = create_simple_schema();
+        schema.examples[0].expected_output = Some("ran 2".to_string());
+
+        let plain = JupyterGenerator::with_kernel(Kernel::Rust)
+            .generate(&schema)
+            .unwrap();
+        let plain: Value = serde_json::from_str(&plain).unwrap();
+        let cells = plain["cells"].as_array().unwrap().clone();
+
+        let generator = JupyterGenerator::with_kernel(Kernel::Rust)
+            .with_executor(fake_jupyter(dir.path(), cells));
+        assert_eq!(generator.version(), EXECUTED_FORMAT_VERSION);
+        let output: Value = serde_json::from_str(&generator.generate(&schema).unwrap()).unwrap();
+
+        let input: Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.path().join("input.ipynb")).unwrap())
+                .unwrap();
+        assert_eq!(input["cells"], plain["cells"]);
+
+        let code: Vec<_> = output["cells"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|cell| cell["cell_type"] == "code")
+            .map(|cell| {
+                let text = &cell["outputs"][0]["text"];
+                (
+                    cell["source"][0].clone(),
+                    cell["execution_count"].clone(),
+                    text.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            code,
+            vec![
+                (json!(":dep mathhook\n"), json!(1), json!(["ran 1\n"])),
+                (json!("let x = 2;\n"), json!(2), json!(["ran 2\n"])),
+            ]
+        );
+
+        // The example's expected output is checked against what the kernel printed
+        schema.examples[0].expected_output = Some("2".to_string());
+        let err = generator.generate(&schema).unwrap_err();
+        assert!(matches!(
+            err,
+            kb_core::KbError::NotebookOutputMismatch { cell, .. } if output["cells"][cell]["source"][0] == "let x = 2;\n"
+        ));
+    }
 }
@@ -0,0 +1,112 @@
+/// Notebook kernels
+///
+/// The same schema can be rendered for each language its examples are written
+/// in. The kernel decides which snippet fills the code cells, the notebook's
+/// `kernelspec`/`language_info` metadata and any setup cell the kernel needs.
+use crate::notebook::{KernelSpec, LanguageInfo};
+use kb_core::schema::CodeSnippets;
+use std::collections::BTreeMap;
+
+/// Jupyter kernel a notebook is written for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Kernel {
+    /// IPython (`python3`)
+    #[default]
+    Python,
+
+    /// evcxr Rust kernel
+    Rust,
+
+    /// IJavascript (Node.js) kernel
+    JavaScript,
+}
+
+impl Kernel {
+    /// Every supported kernel
+    pub const ALL: [Kernel; 3] = [Kernel::Python, Kernel::Rust, Kernel::JavaScript];
+
+    /// Name of the generator producing notebooks for this kernel
+    pub fn generator_name(self) -> &'static str {
+        match self {
+            Kernel::Python => "jupyter",
+            Kernel::Rust => "jupyter-rust",
+            Kernel::JavaScript => "jupyter-js",
+        }
+    }
+
+    /// Human-readable language name
+    pub fn language(self) -> &'static str {
+        match self {
+            Kernel::Python => "Python",
+            Kernel::Rust => "Rust",
+            Kernel::JavaScript => "JavaScript",
+        }
+    }
+
+    /// Language tag for fenced code blocks in markdown cells
+    pub fn fence(self) -> &'static str {
+        match self {
+            Kernel::Python => "python",
+            Kernel::Rust => "rust",
+            Kernel::JavaScript => "javascript",
+        }
+    }
+
+    /// The example snippet written in this kernel's language
+    pub fn code(self, snippets: &CodeSnippets) -> &str {
+        match self {
+            Kernel::Python => &snippets.python,
+            Kernel::Rust => &snippets.rust,
+            Kernel::JavaScript => &snippets.nodejs,
+        }
+    }
+
+    /// Code cell run before any example, if the kernel needs one
+    ///
+    /// evcxr has no access to the crate until it is declared with `:dep`.
+    pub fn setup_cell(self) -> Option<&'static str> {
+        match self {
+            Kernel::Rust => Some(":dep mathhook\nuse mathhook::prelude::*;"),
+            Kernel::Python | Kernel::JavaScript => None,
+        }
+    }
+
+    pub fn kernel_spec(self) -> KernelSpec {
+        match self {
+            Kernel::Python => KernelSpec::default(),
+            Kernel::Rust => KernelSpec {
+                display_name: "Rust".to_string(),
+                language: "rust".to_string(),
+                name: "rust".to_string(),
+            },
+            Kernel::JavaScript => KernelSpec {
+                display_name: "JavaScript (Node.js)".to_string(),
+                language: "javascript".to_string(),
+                name: "javascript".to_string(),
+            },
+        }
+    }
+
+    pub fn language_info(self) -> LanguageInfo {
+        match self {
+            Kernel::Python => LanguageInfo::default(),
+            Kernel::Rust => LanguageInfo {
+                name: "Rust".to_string(),
+                version: String::new(),
+                mimetype: Some("text/rust".to_string()),
+                file_extension: Some(".rs".to_string()),
+                extra: BTreeMap::from([
+                    ("codemirror_mode".to_string(), "rust".into()),
+                    ("pygment_lexer".to_string(), "rust".into()),
+                ]),
+            },
+            Kernel::JavaScript => LanguageInfo {
+                name: "javascript".to_string(),
+                version: String::new(),
+                mimetype: Some("application/javascript".to_string()),
+                file_extension: Some(".js".to_string()),
+                extra: BTreeMap::new(),
+            },
+        }
+    }
+}
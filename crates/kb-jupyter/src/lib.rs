@@ -2,9 +2,12 @@
 ///
 /// This module generates interactive Jupyter notebooks from KB schemas, with special
 /// support for article-style content featuring tutorial-focused narratives.
+/// Notebooks can target the Python, evcxr Rust or IJavascript kernel.
 mod generator;
+mod kernel;
 mod notebook;
 mod templates;
 
 pub use generator::JupyterGenerator;
-pub use notebook::{Cell, CellType, Notebook};
+pub use kernel::Kernel;
+pub use notebook::{Cell, CellType, KernelSpec, LanguageInfo, Notebook, NotebookMetadata};
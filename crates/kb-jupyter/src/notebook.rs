@@ -4,7 +4,7 @@
 /// Reference: https://nbformat.readthedocs.io/en/latest/format_description.html
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 /// A Jupyter notebook
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,7 +68,7 @@ pub struct NotebookMetadata {
     pub language_info: Option<LanguageInfo>,
 
     #[serde(flatten)]
-    pub extra: HashMap<String, Value>,
+    pub extra: BTreeMap<String, Value>,
 }
 
 /// Kernel specification
@@ -102,7 +102,7 @@ pub struct LanguageInfo {
     pub file_extension: Option<String>,
 
     #[serde(flatten)]
-    pub extra: HashMap<String, Value>,
+    pub extra: BTreeMap<String, Value>,
 }
 
 impl Default for LanguageInfo {
@@ -112,7 +112,7 @@ impl Default for LanguageInfo {
             version: "3.10.0".to_string(),
             mimetype: Some("text/x-python".to_string()),
             file_extension: Some(".py".to_string()),
-            extra: HashMap::new(),
+            extra: BTreeMap::new(),
         }
     }
 }
@@ -179,7 +179,7 @@ pub enum CellType {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct CellMetadata {
     #[serde(flatten)]
-    pub extra: HashMap<String, Value>,
+    pub extra: BTreeMap<String, Value>,
 }
 
 #[cfg(test)]
@@ -71,16 +71,23 @@ impl TemplateManager {
     }
 
     /// Render a code example
+    ///
+    /// `language` tags the code fence; `language_name` is shown instead of the
+    /// code when the example has no snippet in that language.
     pub fn render_example(
         &self,
         title: &str,
         explanation: &str,
+        language: &str,
+        language_name: &str,
         code: &str,
         output: Option<&String>,
     ) -> Result<String, tera::Error> {
         let mut context = Context::new();
         context.insert("title", title);
         context.insert("explanation", explanation);
+        context.insert("language", language);
+        context.insert("language_name", language_name);
         context.insert("code", code);
         context.insert("output", &output);
         self.render("example", &context)
@@ -187,8 +194,9 @@ const EXAMPLE_TEMPLATE: &str = r#"### Example: {{ title }}
 
 {{ explanation }}
 
+{% if code | trim -%}
 **Code:**
-```python
+```{{ language }}
 {{ code }}
 ```
 
@@ -198,6 +206,9 @@ const EXAMPLE_TEMPLATE: &str = r#"### Example: {{ title }}
 {{ output }}
 ```
 {% endif -%}
+{% else -%}
+*No {{ language_name }} version of this example.*
+{% endif -%}
 "#;
 
 const SIDEBAR_TEMPLATE: &str = r#"{% if sidebar_type == "tip" -%}
@@ -308,14 +319,29 @@ mod tests {
         let result = manager.render_example(
             "Power Rule",
             "This demonstrates the power rule",
+            "python",
+            "Python",
             "x**2",
             Some(&"2*x".to_string()),
         );
         assert!(result.is_ok());
         let rendered = result.unwrap();
         assert!(rendered.contains("Power Rule"));
-        assert!(rendered.contains("x**2"));
+        assert!(rendered.contains("```python\nx**2"));
         assert!(rendered.contains("2*x"));
+
+        let missing = manager
+            .render_example(
+                "Power Rule",
+                "",
+                "rust",
+                "Rust",
+                "",
+                Some(&"2*x".to_string()),
+            )
+            .unwrap();
+        assert!(!missing.contains("```"));
+        assert!(missing.contains("*No Rust version of this example.*"));
     }
 
     #[test]
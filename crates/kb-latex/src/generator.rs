@@ -7,6 +7,9 @@ use kb_core::generator::OutputGenerator;
 use kb_core::{Result, Schema};
 use tera::{Context, Tera};
 
+/// Output format version; bump whenever `LATEX_TEMPLATE` changes
+const FORMAT_VERSION: &str = "1";
+
 /// LaTeX documentation generator
 pub struct LatexGenerator {
     tera: Tera,
@@ -108,6 +111,14 @@ impl OutputGenerator for LatexGenerator {
         "tex"
     }
 
+    fn description(&self) -> &str {
+        "LaTeX documentation (.tex)"
+    }
+
+    fn version(&self) -> &str {
+        FORMAT_VERSION
+    }
+
     fn generate(&self, schema: &Schema) -> Result<String> {
         self.generate_latex(schema)
     }
@@ -245,6 +256,7 @@ mod tests {
                     nodejs: "const f = x**2;".to_string(),
                 },
                 expected_output: Some("2*x".to_string()),
+                doctest: Default::default(),
             }],
             article: None,
             use_cases: vec![],
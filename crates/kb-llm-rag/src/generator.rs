@@ -3,14 +3,18 @@ use kb_core::generator::OutputGenerator;
 /// LLM-RAG generator implementation
 ///
 /// Generates markdown optimized for vector embedding and retrieval by LLMs.
+use kb_core::schema::{EmbeddingPriority, RagChunkStrategy};
 use kb_core::{Result, Schema};
 
+/// Output format version; bump whenever chunk layout or metadata changes
+const FORMAT_VERSION: &str = "1";
+
 /// LLM-RAG optimized markdown generator
 pub struct LlmRagGenerator {
     chunk_strategy: ChunkStrategy,
     #[allow(dead_code)]
     max_chunk_size: usize,
-    embedding_priority: String,
+    embedding_priority: EmbeddingPriority,
 }
 
 impl LlmRagGenerator {
@@ -19,32 +23,45 @@ impl LlmRagGenerator {
         Self {
             chunk_strategy: ChunkStrategy::ByExample,
             max_chunk_size: 512,
-            embedding_priority: "medium".to_string(),
+            embedding_priority: EmbeddingPriority::default(),
         }
     }
 
     /// Create generator from schema hints
     pub fn from_schema(schema: &Schema) -> Self {
         if let Some(hints) = &schema.outputs.llm_rag {
-            let strategy = match hints.chunk_strategy.as_str() {
-                "by_section" => ChunkStrategy::BySection,
-                "fixed_size" => ChunkStrategy::FixedSize {
+            let strategy = match hints.chunk_strategy {
+                RagChunkStrategy::ByExample => ChunkStrategy::ByExample,
+                RagChunkStrategy::BySection => ChunkStrategy::BySection,
+                RagChunkStrategy::FixedSize => ChunkStrategy::FixedSize {
                     size: hints.max_chunk_size,
                     overlap: hints.max_chunk_size / 4,
                 },
-                _ => ChunkStrategy::ByExample,
             };
 
             Self {
                 chunk_strategy: strategy,
                 max_chunk_size: hints.max_chunk_size,
-                embedding_priority: hints.embedding_priority.clone(),
+                embedding_priority: hints.embedding_priority,
             }
         } else {
             Self::new()
         }
     }
 
+    /// Generate output using this generator's chunking strategy
+    fn generate_with_strategy(&self, schema: &Schema) -> Result<String> {
+        match &self.chunk_strategy {
+            ChunkStrategy::ByExample => self.generate_by_example(schema),
+            ChunkStrategy::BySection => self.generate_by_section(schema),
+            ChunkStrategy::FixedSize { .. } => {
+                // For now, fallback to by_example for fixed_size
+                // Could implement fixed-size chunking later
+                self.generate_by_example(schema)
+            }
+        }
+    }
+
     /// Generate a single chunk of markdown
     fn generate_chunk(&self, metadata: &ChunkMetadata, content: String, schema: &Schema) -> String {
         let mut output = String::new();
@@ -127,7 +144,7 @@ impl LlmRagGenerator {
                 total_chunks,
             )
             .with_keywords(keywords)
-            .with_priority(self.embedding_priority.clone());
+            .with_priority(self.embedding_priority.as_str().to_string());
 
             let mut chunk_content = String::new();
             chunk_content.push_str(&format!("## {}\n\n", example.title));
@@ -269,7 +286,7 @@ impl LlmRagGenerator {
                             idx + 1,
                             total_chunks + 1,
                         )
-                        .with_priority(self.embedding_priority.clone());
+                        .with_priority(self.embedding_priority.as_str().to_string());
 
                         let mut section_content = String::new();
                         section_content.push_str(&format!("## {}\n\n", section.title));
@@ -303,16 +320,29 @@ impl OutputGenerator for LlmRagGenerator {
         "md"
     }
 
+    fn description(&self) -> &str {
+        "LLM-optimized RAG markdown (.rag.md)"
+    }
+
+    fn version(&self) -> &str {
+        FORMAT_VERSION
+    }
+
+    fn get_output_filename(&self, schema: &Schema) -> String {
+        format!(
+            "{}.rag.{}",
+            schema.topic.replace('.', "-"),
+            self.file_extension()
+        )
+    }
+
     fn generate(&self, schema: &Schema) -> Result<String> {
-        match &self.chunk_strategy {
-            ChunkStrategy::ByExample => self.generate_by_example(schema),
-            ChunkStrategy::BySection => self.generate_by_section(schema),
-            ChunkStrategy::FixedSize { .. } => {
-                // For now, fallback to by_example for fixed_size
-                // Could implement fixed-size chunking later
-                self.generate_by_example(schema)
-            }
+        // Schema hints take precedence over the generator's own settings
+        if schema.outputs.llm_rag.is_some() {
+            return Self::from_schema(schema).generate_with_strategy(schema);
         }
+
+        self.generate_with_strategy(schema)
     }
 
     fn validate_output(&self, output: &str) -> Result<()> {
@@ -369,6 +399,7 @@ mod tests {
                     nodejs: "const x = 2;".to_string(),
                 },
                 expected_output: Some("2".to_string()),
+                doctest: Default::default(),
             }],
             article: None,
             use_cases: vec![],
@@ -415,8 +446,8 @@ mod tests {
         println!("Generated output:\n{}", output);
 
         let validation = generator.validate_output(&output);
-        if validation.is_err() {
-            println!("Validation error: {:?}", validation.as_ref().unwrap_err());
+        if let Err(e) = &validation {
+            println!("Validation error: {:?}", e);
         }
         assert!(validation.is_ok());
     }
@@ -433,13 +464,14 @@ mod tests {
     fn test_from_schema_with_hints() {
         let mut schema = create_test_schema();
         schema.outputs.llm_rag = Some(LlmRagHints {
-            chunk_strategy: "by_section".to_string(),
+            chunk_strategy: RagChunkStrategy::BySection,
             max_chunk_size: 1024,
-            embedding_priority: "high".to_string(),
+            embedding_priority: EmbeddingPriority::High,
         });
 
         let generator = LlmRagGenerator::from_schema(&schema);
-        assert_eq!(generator.embedding_priority, "high");
+        assert_eq!(generator.chunk_strategy, ChunkStrategy::BySection);
+        assert_eq!(generator.embedding_priority, EmbeddingPriority::High);
         assert_eq!(generator.max_chunk_size, 1024);
     }
 
@@ -0,0 +1,523 @@
+//! mdBook project files
+//!
+//! Pages are written to `<category>/<topic-slug>.md`. The manifest collects them
+//! during a build and produces the files `mdbook build` needs around them: a
+//! `SUMMARY.md` whose chapter tree follows the dotted topics, a `book.toml`, an
+//! introduction and an index page per category.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Settings written to the generated `book.toml`
+///
+/// The defaults are those of the published MathHook book.
+#[derive(Debug, Clone)]
+pub struct BookConfig {
+    pub title: String,
+    pub authors: Vec<String>,
+    pub description: String,
+    pub language: String,
+
+    /// Repository linked from the menu bar
+    pub git_repository_url: Option<String>,
+
+    /// Target of each page's edit link; mdBook replaces `{path}` with the page path
+    pub edit_url_template: Option<String>,
+
+    /// Stylesheet written to `custom.css` and loaded on every page
+    pub custom_css: Option<String>,
+
+    /// Render math with the `mdbook-katex` preprocessor instead of mdBook's built-in MathJax
+    ///
+    /// Off by default, so the book builds without extra preprocessors.
+    pub katex: bool,
+}
+
+impl Default for BookConfig {
+    fn default() -> Self {
+        Self {
+            title: "MathHook Knowledge Base".to_string(),
+            authors: vec!["MathHook Contributors".to_string()],
+            description: "Documentation for the MathHook symbolic mathematics library".to_string(),
+            language: "en".to_string(),
+            git_repository_url: Some("https://github.com/AhmedMashour/mathhook".to_string()),
+            edit_url_template: Some(
+                "https://github.com/AhmedMashour/mathhook-kb/edit/master/outputs/mdbook/{path}"
+                    .to_string(),
+            ),
+            custom_css: Some(CUSTOM_CSS.to_string()),
+            katex: false,
+        }
+    }
+}
+
+/// Path of a topic's page relative to the book root (e.g., `calculus/calculus-derivative.md`)
+pub fn page_path(topic: &str) -> String {
+    let category = topic.split('.').next().unwrap_or("misc");
+    format!("{}/{}.md", category, topic.replace('.', "-"))
+}
+
+/// A page in the book
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageEntry {
+    pub topic: String,
+    pub title: String,
+
+    /// Path relative to the book root
+    pub path: String,
+}
+
+/// Every page of a generated book, keyed by topic
+#[derive(Debug, Clone, Default)]
+pub struct BookManifest {
+    pub config: BookConfig,
+    pages: BTreeMap<String, PageEntry>,
+}
+
+/// A node of the dotted topic hierarchy
+#[derive(Default)]
+struct ChapterNode<'a> {
+    page: Option<&'a PageEntry>,
+    children: BTreeMap<&'a str, ChapterNode<'a>>,
+}
+
+impl BookManifest {
+    /// Create an empty manifest with the given config
+    pub fn new(config: BookConfig) -> Self {
+        Self {
+            config,
+            pages: BTreeMap::new(),
+        }
+    }
+
+    /// Add a generated page
+    pub fn add(&mut self, topic: &str, title: &str, filename: &str) {
+        let category = topic.split('.').next().unwrap_or("misc");
+        self.pages.insert(
+            topic.to_string(),
+            PageEntry {
+                topic: topic.to_string(),
+                title: title.to_string(),
+                path: format!("{}/{}", category, filename),
+            },
+        );
+    }
+
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    /// Categories in alphabetical order
+    pub fn sorted_categories(&self) -> Vec<&str> {
+        let mut categories: Vec<&str> = self
+            .pages
+            .keys()
+            .filter_map(|topic| topic.split('.').next())
+            .collect();
+        categories.dedup();
+        categories
+    }
+
+    /// Generate `SUMMARY.md`
+    ///
+    /// Each category is a chapter pointing at its index page. Below it the
+    /// chapters follow the remaining topic segments; a segment without a page of
+    /// its own (e.g., `calculus.integrals` when only `calculus.integrals.by_parts`
+    /// exists) becomes a draft chapter so its children still nest under it.
+    pub fn generate_summary(&self) -> String {
+        let mut summary = String::from("# Summary\n\n[Introduction](README.md)\n");
+
+        for (category, node) in &self.chapter_tree().children {
+            summary.push_str(&format!("\n# {}\n\n", humanize(category)));
+            summary.push_str(&format!(
+                "- [{}]({}/index.md)\n",
+                escape_link_text(&humanize(category)),
+                category
+            ));
+            if let Some(page) = node.page {
+                summary.push_str(&format!(
+                    "    - [{}]({})\n",
+                    escape_link_text(&page.title),
+                    page.path
+                ));
+            }
+            write_chapters(&mut summary, node, 1);
+        }
+
+        summary
+    }
+
+    /// Generate `book.toml`
+    ///
+    /// The book source is the output directory itself, so `mdbook build` runs
+    /// directly on it; `book/` is skipped by mdBook when copying assets. The
+    /// example code groups need the tabs preprocessor, so it is required; its
+    /// stylesheet and script are written by [`BookManifest::save`].
+    pub fn generate_book_toml(&self) -> String {
+        let config = &self.config;
+        let authors: Vec<String> = config
+            .authors
+            .iter()
+            .map(|author| toml_string(author))
+            .collect();
+        let mut toml = format!(
+            r#"[book]
+title = {}
+authors = [{}]
+description = {}
+language = {}
+src = "."
+
+[build]
+build-dir = "book"
+create-missing = false
+
+[preprocessor.tabs]
+"#,
+            toml_string(&config.title),
+            authors.join(", "),
+            toml_string(&config.description),
+            toml_string(&config.language),
+        );
+
+        if config.katex {
+            toml.push_str("\n[preprocessor.katex]\nafter = [\"links\"]\n");
+        }
+
+        toml.push_str("\n[output.html]\n");
+        toml.push_str(&format!("mathjax-support = {}\n", !config.katex));
+        toml.push_str("default-theme = \"ayu\"\npreferred-dark-theme = \"ayu\"\n");
+        if let Some(url) = &config.git_repository_url {
+            toml.push_str(&format!(
+                "git-repository-url = {}\ngit-repository-icon = \"fa-github\"\n",
+                toml_string(url)
+            ));
+        }
+        if let Some(template) = &config.edit_url_template {
+            toml.push_str(&format!("edit-url-template = {}\n", toml_string(template)));
+        }
+        let mut stylesheets = vec![toml_string(TABS_CSS_PATH)];
+        if config.custom_css.is_some() {
+            stylesheets.push(toml_string("custom.css"));
+        }
+        toml.push_str(&format!("additional-css = [{}]\n", stylesheets.join(", ")));
+        toml.push_str(&format!(
+            "additional-js = [{}]\n",
+            toml_string(TABS_JS_PATH)
+        ));
+
+        toml.push('\n');
+        toml.push_str(HTML_OUTPUT_TOML);
+        toml
+    }
+
+    /// Generate the introduction page (`README.md`)
+    pub fn generate_introduction(&self) -> String {
+        let mut intro = format!(
+            "# {}\n\n{}\n\n## Categories\n\n",
+            self.config.title, self.config.description
+        );
+        for category in self.sorted_categories() {
+            let count = self.category_pages(category).count();
+            intro.push_str(&format!(
+                "- [{}]({}/index.md) - {} page{}\n",
+                escape_link_text(&humanize(category)),
+                category,
+                count,
+                if count == 1 { "" } else { "s" }
+            ));
+        }
+        intro
+    }
+
+    /// Generate the index page of a category, if it has any pages
+    pub fn generate_category_index(&self, category: &str) -> Option<String> {
+        let mut pages = self.category_pages(category).peekable();
+        pages.peek()?;
+
+        let mut index = format!("# {}\n\n", humanize(category));
+        for page in pages {
+            let filename = page.path.rsplit('/').next().unwrap_or(&page.path);
+            index.push_str(&format!(
+                "- [{}]({}) - `{}`\n",
+                escape_link_text(&page.title),
+                filename,
+                page.topic
+            ));
+        }
+        Some(index)
+    }
+
+    /// Write `SUMMARY.md`, `book.toml`, `README.md`, the theme assets and the category index pages
+    ///
+    /// Returns the written paths.
+    pub fn save(&self, book_dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+        let mut files = vec![
+            (book_dir.join("SUMMARY.md"), self.generate_summary()),
+            (book_dir.join("book.toml"), self.generate_book_toml()),
+            (book_dir.join("README.md"), self.generate_introduction()),
+            (book_dir.join(TABS_CSS_PATH), TABS_CSS.to_string()),
+            (book_dir.join(TABS_JS_PATH), TABS_JS.to_string()),
+        ];
+        if let Some(css) = &self.config.custom_css {
+            files.push((book_dir.join("custom.css"), css.clone()));
+        }
+        for category in self.sorted_categories() {
+            if let Some(index) = self.generate_category_index(category) {
+                files.push((book_dir.join(category).join("index.md"), index));
+            }
+        }
+
+        for (path, content) in &files {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, content)?;
+        }
+        Ok(files.into_iter().map(|(path, _)| path).collect())
+    }
+
+    fn category_pages<'a>(&'a self, category: &'a str) -> impl Iterator<Item = &'a PageEntry> + 'a {
+        self.pages
+            .values()
+            .filter(move |page| page.topic.split('.').next() == Some(category))
+    }
+
+    fn chapter_tree(&self) -> ChapterNode<'_> {
+        let mut root = ChapterNode::default();
+        for page in self.pages.values() {
+            let mut node = &mut root;
+            for segment in page.topic.split('.') {
+                node = node.children.entry(segment).or_default();
+            }
+            node.page = Some(page);
+        }
+        root
+    }
+}
+
+/// Write the children of `node` as nested list items
+fn write_chapters(summary: &mut String, node: &ChapterNode<'_>, depth: usize) {
+    for (segment, child) in &node.children {
+        let indent = "    ".repeat(depth);
+        match child.page {
+            Some(page) => summary.push_str(&format!(
+                "{}- [{}]({})\n",
+                indent,
+                escape_link_text(&page.title),
+                page.path
+            )),
+            None => summary.push_str(&format!(
+                "{}- [{}]()\n",
+                indent,
+                escape_link_text(&humanize(segment))
+            )),
+        }
+        write_chapters(summary, child, depth + 1);
+    }
+}
+
+/// Turn a topic segment into a title (e.g., `linear_algebra` -> `Linear Algebra`)
+fn humanize(segment: &str) -> String {
+    segment
+        .split(['_', '-'])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn escape_link_text(text: &str) -> String {
+    text.replace('[', "\\[").replace(']', "\\]")
+}
+
+/// Quote a TOML basic string (JSON string escapes are valid TOML)
+fn toml_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// Where the tab group assets are written, relative to the book root
+const TABS_CSS_PATH: &str = "theme/tabs.css";
+const TABS_JS_PATH: &str = "theme/tabs.js";
+
+/// Styles for the tab groups emitted by mdbook-tabs (as `mdbook-tabs install` writes them)
+const TABS_CSS: &str = r#".mdbook-tabs {
+  display: flex;
+}
+
+.mdbook-tab {
+  background-color: var(--table-alternate-bg);
+  padding: 0.5rem 1rem;
+  cursor: pointer;
+  border: none;
+  font-size: 1.6rem;
+  line-height: 1.45em;
+}
+
+.mdbook-tab.active {
+  background-color: var(--table-header-bg);
+  font-weight: bold;
+}
+
+.mdbook-tab-content {
+  padding: 1rem 0rem;
+}
+
+.mdbook-tab-content table {
+  margin: unset;
+}
+"#;
+
+/// Tab switching; groups sharing a `global` name switch together and the choice is remembered
+const TABS_JS: &str = r#"(() => {
+  const changeTab = (container, name) => {
+    for (const child of container.children) {
+      if (!(child instanceof HTMLElement)) {
+        continue;
+      }
+      if (child.classList.contains("mdbook-tabs")) {
+        for (const tab of child.children) {
+          if (tab instanceof HTMLElement) {
+            tab.classList.toggle("active", tab.dataset.tabname === name);
+          }
+        }
+      } else if (child.classList.contains("mdbook-tab-content")) {
+        child.classList.toggle("hidden", child.dataset.tabname !== name);
+      }
+    }
+  };
+
+  const globalContainers = (global) =>
+    document.querySelectorAll(`.mdbook-tabs-container[data-tabglobal="${CSS.escape(global)}"]`);
+
+  document.addEventListener("DOMContentLoaded", () => {
+    for (const tab of document.querySelectorAll(".mdbook-tab")) {
+      tab.addEventListener("click", () => {
+        const container = tab.parentElement && tab.parentElement.parentElement;
+        if (!container) {
+          return;
+        }
+        const name = tab.dataset.tabname;
+        const global = container.dataset.tabglobal;
+        if (!global) {
+          changeTab(container, name);
+          return;
+        }
+        localStorage.setItem(`mdbook-tabs-${global}`, name);
+        for (const other of globalContainers(global)) {
+          changeTab(other, name);
+        }
+      });
+    }
+
+    for (const container of document.querySelectorAll(".mdbook-tabs-container[data-tabglobal]")) {
+      const name = localStorage.getItem(`mdbook-tabs-${container.dataset.tabglobal}`);
+      if (name && container.querySelector(`.mdbook-tab[data-tabname="${CSS.escape(name)}"]`)) {
+        changeTab(container, name);
+      }
+    }
+  });
+})();
+"#;
+
+/// Fold, search, print and playground settings of the published book
+const HTML_OUTPUT_TOML: &str = include_str!("../assets/html-output.toml");
+
+/// MathHook theme colours
+const CUSTOM_CSS: &str = include_str!("../assets/custom.css");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> BookManifest {
+        let mut book = BookManifest::new(BookConfig::default());
+        book.add(
+            "calculus.derivative",
+            "Derivatives",
+            "calculus-derivative.md",
+        );
+        book.add(
+            "calculus.integrals.by_parts",
+            "Integration by Parts",
+            "calculus-integrals-by_parts.md",
+        );
+        book.add(
+            "linear_algebra.matrix",
+            "Matrices [dense]",
+            "linear_algebra-matrix.md",
+        );
+        book
+    }
+
+    #[test]
+    fn test_summary_follows_topic_hierarchy() {
+        assert_eq!(
+            manifest().generate_summary(),
+            "# Summary
+
+[Introduction](README.md)
+
+# Calculus
+
+- [Calculus](calculus/index.md)
+    - [Derivatives](calculus/calculus-derivative.md)
+    - [Integrals]()
+        - [Integration by Parts](calculus/calculus-integrals-by_parts.md)
+
+# Linear Algebra
+
+- [Linear Algebra](linear_algebra/index.md)
+    - [Matrices \\[dense\\]](linear_algebra/linear_algebra-matrix.md)
+"
+        );
+    }
+
+    #[test]
+    fn test_book_toml_and_category_index() {
+        let book = manifest();
+        let toml = book.generate_book_toml();
+        assert!(toml.contains("title = \"MathHook Knowledge Base\""));
+        assert!(toml.contains("src = \".\""));
+        assert!(!toml.contains("katex"));
+        assert!(toml.contains("mathjax-support = true"));
+        assert!(toml.contains("edit-url-template = \"https://github.com/AhmedMashour/mathhook-kb/edit/master/outputs/mdbook/{path}\""));
+        assert!(toml.contains("[preprocessor.tabs]\n\n"));
+        assert!(!toml.contains("optional"));
+        assert!(toml.contains("additional-css = [\"theme/tabs.css\", \"custom.css\"]\nadditional-js = [\"theme/tabs.js\"]\n"));
+        assert!(toml.contains("[output.html.playground]\nrunnable = true"));
+
+        let plain = BookManifest::new(BookConfig {
+            git_repository_url: None,
+            edit_url_template: None,
+            custom_css: None,
+            katex: true,
+            ..BookConfig::default()
+        })
+        .generate_book_toml();
+        assert!(plain.contains("[preprocessor.katex]\nafter = [\"links\"]\n"));
+        assert!(plain.contains("mathjax-support = false"));
+        assert!(!plain.contains("git-repository") && !plain.contains("custom.css"));
+        assert!(plain.contains("additional-css = [\"theme/tabs.css\"]"));
+
+        let index = book.generate_category_index("calculus").unwrap();
+        assert!(index.starts_with("# Calculus\n"));
+        assert!(index.contains("- [Derivatives](calculus-derivative.md) - `calculus.derivative`"));
+        assert!(book.generate_category_index("geometry").is_none());
+        assert_eq!(book.sorted_categories(), vec!["calculus", "linear_algebra"]);
+        assert_eq!(
+            page_path("calculus.integrals.by_parts"),
+            "calculus/calculus-integrals-by_parts.md"
+        );
+    }
+}
@@ -1,11 +1,16 @@
+use crate::book::page_path;
 use kb_core::generator::OutputGenerator;
 /// mdBook generator implementation
 ///
 /// Generates markdown files optimized for mdBook static site generation.
 /// Output is reference-style with technical depth and cross-references.
+use kb_core::schema::{ExerciseDifficulty, SidebarType};
 use kb_core::{Result, Schema};
 use tera::{Context, Tera};
 
+/// Output format version; bump whenever the page templates change
+const FORMAT_VERSION: &str = "4";
+
 /// mdBook markdown generator
 pub struct MdBookGenerator {
     tera: Tera,
@@ -57,27 +62,79 @@ impl MdBookGenerator {
                     context.insert("hook", &simple.content);
                     let empty_sections: Vec<tera::Map<String, tera::Value>> = vec![];
                     context.insert("sections", &empty_sections);
+                    context.insert("sidebars", &empty_sections);
                 }
                 Article::Structured(structured) => {
                     context.insert("has_article", &true);
                     context.insert("hook", &structured.introduction.hook);
 
-                    // Add sections
+                    // Add introduction details
+                    let intro = &structured.introduction;
+                    context.insert("learning_objectives", &intro.learning_objectives);
+                    context.insert("prerequisites", &intro.prerequisites);
+                    if let Some(time) = &intro.estimated_time {
+                        context.insert("estimated_time", time);
+                    }
+
+                    // Add sections with their subsections
                     let sections: Vec<_> = structured
                         .sections
                         .iter()
                         .map(|s| {
-                            let mut section_ctx = tera::Map::new();
-                            section_ctx.insert("title".to_string(), tera::Value::String(s.title.clone()));
-                            section_ctx.insert(
-                                "content".to_string(),
-                                tera::Value::String(s.content.clone()),
-                            );
-                            section_ctx
+                            serde_json::json!({
+                                "title": s.title,
+                                "content": s.content,
+                                "math_content": s.math_content,
+                                "code_examples": s.code_examples,
+                                "subsections": s.subsections.iter().map(|sub| {
+                                    serde_json::json!({ "title": sub.title, "content": sub.content })
+                                }).collect::<Vec<_>>(),
+                            })
                         })
                         .collect();
                     context.insert("sections", &sections);
 
+                    // Add sidebars as admonitions
+                    let sidebars: Vec<_> = structured
+                        .sidebars
+                        .iter()
+                        .map(|sidebar| {
+                            let (alert, label) = sidebar_admonition(&sidebar.sidebar_type);
+                            serde_json::json!({
+                                "alert": alert,
+                                "label": label,
+                                "title": sidebar.title,
+                                "content": blockquote(&sidebar.content),
+                            })
+                        })
+                        .collect();
+                    context.insert("sidebars", &sidebars);
+
+                    // Add conclusion with exercises and resources
+                    if let Some(conclusion) = &structured.conclusion {
+                        let exercises: Vec<_> = conclusion
+                            .exercises
+                            .iter()
+                            .map(|exercise| {
+                                serde_json::json!({
+                                    "prompt": exercise.prompt,
+                                    "difficulty": difficulty_label(&exercise.difficulty),
+                                    "hints": exercise.hints.iter().map(|hint| indent(hint)).collect::<Vec<_>>(),
+                                    "solution": exercise.solution.as_deref().map(indent),
+                                })
+                            })
+                            .collect();
+                        context.insert(
+                            "conclusion",
+                            &serde_json::json!({
+                                "summary": conclusion.summary,
+                                "next_steps": conclusion.next_steps,
+                                "further_reading": conclusion.further_reading,
+                                "exercises": exercises,
+                            }),
+                        );
+                    }
+
                     // Add mdBook-specific deep dives if available
                     if let Some(variations) = &structured.variations {
                         if let Some(mdbook) = &variations.mdbook {
@@ -140,6 +197,14 @@ impl MdBookGenerator {
             .collect();
         context.insert("examples", &examples);
 
+        // Rust examples get a playground button unless the schema opts out
+        let runnable = schema
+            .outputs
+            .mdbook
+            .as_ref()
+            .is_none_or(|hints| hints.runnable_code);
+        context.insert("rust_fence", if runnable { "rust" } else { "rust,ignore" });
+
         // Add performance info if available
         if let Some(perf) = &schema.performance {
             context.insert("performance", &perf.complexity);
@@ -147,7 +212,12 @@ impl MdBookGenerator {
 
         // Add related topics
         if !schema.related_topics.is_empty() {
-            context.insert("related_topics", &schema.related_topics);
+            let related: Vec<_> = schema
+                .related_topics
+                .iter()
+                .map(|topic| serde_json::json!({ "topic": topic, "path": page_path(topic) }))
+                .collect();
+            context.insert("related_topics", &related);
         }
 
         // Add SEO metadata if present
@@ -171,7 +241,7 @@ impl MdBookGenerator {
             if let Some(og_img) = &seo.og_image {
                 context.insert("seo_og_image", og_img);
             }
-            context.insert("seo_twitter_card", &seo.twitter_card);
+            context.insert("seo_twitter_card", seo.twitter_card.as_str());
             if let Some(schema_type) = &seo.schema_org_type {
                 context.insert("seo_schema_org_type", schema_type);
             }
@@ -197,6 +267,14 @@ impl OutputGenerator for MdBookGenerator {
         "md"
     }
 
+    fn description(&self) -> &str {
+        "mdBook markdown documentation (.md)"
+    }
+
+    fn version(&self) -> &str {
+        FORMAT_VERSION
+    }
+
     fn generate(&self, schema: &Schema) -> Result<String> {
         self.generate_page(schema)
     }
@@ -222,6 +300,48 @@ impl OutputGenerator for MdBookGenerator {
     }
 }
 
+/// GitHub-style alert (rendered natively by mdBook) and label for a sidebar type
+fn sidebar_admonition(sidebar_type: &SidebarType) -> (&'static str, &'static str) {
+    match sidebar_type {
+        SidebarType::Tip => ("TIP", "Tip"),
+        SidebarType::Warning => ("WARNING", "Warning"),
+        SidebarType::Note => ("NOTE", "Note"),
+        SidebarType::Info => ("NOTE", "Info"),
+        SidebarType::Performance => ("IMPORTANT", "Performance"),
+        SidebarType::BestPractice => ("TIP", "Best Practice"),
+    }
+}
+
+fn difficulty_label(difficulty: &ExerciseDifficulty) -> &'static str {
+    match difficulty {
+        ExerciseDifficulty::Beginner => "🟢 Beginner",
+        ExerciseDifficulty::Intermediate => "🟡 Intermediate",
+        ExerciseDifficulty::Advanced => "🟠 Advanced",
+        ExerciseDifficulty::Expert => "🔴 Expert",
+    }
+}
+
+/// Prefix every line with `>` so multi-line content stays inside a blockquote
+fn blockquote(content: &str) -> String {
+    content
+        .trim_end()
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                ">".to_string()
+            } else {
+                format!("> {}", line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Indent continuation lines so multi-line content stays inside an exercise list item
+fn indent(content: &str) -> String {
+    content.trim_end().replace('\n', "\n   ")
+}
+
 // Embedded templates for mdBook style
 
 const PAGE_TEMPLATE: &str = r#"---
@@ -250,13 +370,30 @@ const PAGE_TEMPLATE: &str = r#"---
 
 {% if has_article %}
 {{ hook }}
-
+{% if estimated_time %}
+**Estimated time**: {{ estimated_time }}
+{% endif -%}
+{% if prerequisites %}
+**Prerequisites**:
+{% for item in prerequisites %}
+- {{ item }}
+{%- endfor %}
+{% endif -%}
+{% if learning_objectives %}
+**What you'll learn**:
+{% for item in learning_objectives %}
+- {{ item }}
+{%- endfor %}
+{% endif -%}
 {% for section in sections %}
-## {{ section.title }}
-
-{{ section.content }}
-{% endfor %}
-
+{% include "section" %}
+{%- endfor %}
+{% for sidebar in sidebars %}
+> [!{{ sidebar.alert }}]
+> **{{ sidebar.label }}: {{ sidebar.title }}**
+>
+{{ sidebar.content }}
+{% endfor -%}
 {% if deep_dives %}
 ---
 
@@ -267,24 +404,64 @@ const PAGE_TEMPLATE: &str = r#"---
 
 {{ dive.content }}
 {% endfor %}
-{% endif %}
-
+{% endif -%}
 {% if implementation_notes %}
 ---
 
 ## Implementation Notes
 
 {{ implementation_notes }}
-{% endif %}
-
+{% endif -%}
 {% if complexity_analysis %}
 ---
 
 ## Complexity Analysis
 
 {{ complexity_analysis }}
-{% endif %}
+{% endif -%}
+{% if conclusion %}
+---
 
+## Conclusion
+
+{{ conclusion.summary }}
+{% if conclusion.next_steps %}
+### Next Steps
+{% for step in conclusion.next_steps %}
+- {{ step }}
+{%- endfor %}
+{% endif -%}
+{% if conclusion.exercises %}
+### Exercises
+{% for exercise in conclusion.exercises %}
+{{ loop.index }}. **{{ exercise.difficulty }}**: {{ exercise.prompt }}
+{%- for hint in exercise.hints %}
+
+   <details>
+   <summary>Hint {{ loop.index }}</summary>
+
+   {{ hint }}
+
+   </details>
+{%- endfor %}
+{%- if exercise.solution %}
+
+   <details>
+   <summary>Solution</summary>
+
+   {{ exercise.solution }}
+
+   </details>
+{%- endif %}
+{% endfor -%}
+{% endif -%}
+{% if conclusion.further_reading %}
+### Further Reading
+{% for resource in conclusion.further_reading %}
+- {% if resource.url %}[{{ resource.title }}]({{ resource.url }}){% else %}**{{ resource.title }}**{% endif %}{% if resource.description %} - {{ resource.description }}{% endif %}
+{%- endfor %}
+{% endif -%}
+{% endif -%}
 {% endif %}
 
 ## Examples
@@ -294,43 +471,7 @@ const PAGE_TEMPLATE: &str = r#"---
 
 {{ example.explanation }}
 
-{% if example.rust %}
-<details>
-<summary><b>Rust</b></summary>
-
-```rust
-{{ example.rust }}
-```
-</details>
-{% endif %}
-
-{% if example.python %}
-<details>
-<summary><b>Python</b></summary>
-
-```python
-{{ example.python }}
-```
-</details>
-{% endif %}
-
-{% if example.nodejs %}
-<details>
-<summary><b>JavaScript</b></summary>
-
-```javascript
-{{ example.nodejs }}
-```
-</details>
-{% endif %}
-
-{% if example.output %}
-**Expected Output:**
-```
-{{ example.output }}
-```
-{% endif %}
-
+{% include "example" %}
 {% endfor %}
 
 {% if performance %}
@@ -348,14 +489,65 @@ const PAGE_TEMPLATE: &str = r#"---
 {% if related_topics %}
 ## See Also
 
-{% for topic in related_topics %}
-- [{{ topic }}](../{{ topic | replace(from=".", to="/") }}.md)
+{% for related in related_topics %}
+- [{{ related.topic }}](../{{ related.path }})
 {% endfor %}
 {% endif %}
 "#;
 
-const SECTION_TEMPLATE: &str = "";
-const EXAMPLE_TEMPLATE: &str = "";
+/// An article section with its math, code and subsections
+const SECTION_TEMPLATE: &str = r#"## {{ section.title }}
+
+{{ section.content }}
+{% if section.math_content %}
+{{ section.math_content }}
+{% endif -%}
+{% for code in section.code_examples %}
+```rust
+{{ code }}
+```
+{% endfor -%}
+{% for subsection in section.subsections %}
+### {{ subsection.title }}
+
+{{ subsection.content }}
+{% endfor -%}
+"#;
+/// Code of one example as a tab group for the mdbook-tabs preprocessor
+///
+/// The shared `global` key keeps every group on the reader's chosen language,
+/// and the preprocessor remembers that choice across pages.
+const EXAMPLE_TEMPLATE: &str = r#"{% raw %}{{#tabs global="language" }}{% endraw %}
+{% if example.rust %}{% raw %}{{#tab name="Rust" }}{% endraw %}
+
+```{{ rust_fence }}
+{{ example.rust }}
+```
+
+{% raw %}{{#endtab }}{% endraw %}
+{% endif %}{% if example.python %}{% raw %}{{#tab name="Python" }}{% endraw %}
+
+```python
+{{ example.python }}
+```
+
+{% raw %}{{#endtab }}{% endraw %}
+{% endif %}{% if example.nodejs %}{% raw %}{{#tab name="JavaScript" }}{% endraw %}
+
+```javascript
+{{ example.nodejs }}
+```
+
+{% raw %}{{#endtab }}{% endraw %}
+{% endif %}{% raw %}{{#endtabs }}{% endraw %}
+
+{% if example.output %}
+**Expected Output:**
+```text
+{{ example.output }}
+```
+{% endif %}
+"#;
 const DEEP_DIVE_TEMPLATE: &str = "";
 
 #[cfg(test)]
@@ -383,6 +575,7 @@ mod tests {
                     nodejs: "const f = x**2;".to_string(),
                 },
                 expected_output: Some("2*x".to_string()),
+                doctest: Default::default(),
             }],
             article: None,
             use_cases: vec![],
@@ -424,6 +617,31 @@ mod tests {
         assert!(output.contains("```python"));
         assert!(output.contains("```javascript"));
         assert!(output.contains("O(n)"));
+        assert!(output.contains("- [test.related](../test/test-related.md)"));
+    }
+
+    #[test]
+    fn test_examples_render_as_tab_group() {
+        let generator = MdBookGenerator::new().unwrap();
+        let mut schema = create_test_schema();
+        schema.examples[0].code.nodejs = String::new();
+
+        let output = generator.generate(&schema).unwrap();
+        let tabs = output.find("{{#tabs global=\"language\" }}").unwrap();
+        let python = output.find("{{#tab name=\"Python\" }}").unwrap();
+        let end = output.find("{{#endtabs }}").unwrap();
+        let expected = output.find("**Expected Output:**").unwrap();
+        assert!(tabs < python && python < end && end < expected);
+        assert!(!output.contains("{{#tab name=\"JavaScript\" }}"));
+        assert!(output.contains("```rust\nlet f"));
+
+        schema.outputs.mdbook = Some(MdBookHints {
+            include_mathematical_proof: false,
+            include_implementation_details: false,
+            runnable_code: false,
+        });
+        let output = generator.generate(&schema).unwrap();
+        assert!(output.contains("```rust,ignore\nlet f"));
     }
 
     #[test]
@@ -2,6 +2,10 @@
 ///
 /// This module generates markdown files suitable for mdBook, a static site generator
 /// for technical documentation. Output is reference-style with deep technical detail.
+/// `BookManifest` adds the `SUMMARY.md` and `book.toml` that make the output a
+/// complete book.
+mod book;
 mod generator;
 
+pub use book::{page_path, BookConfig, BookManifest, PageEntry};
 pub use generator::MdBookGenerator;
@@ -0,0 +1,204 @@
+use kb_core::generator::OutputGenerator;
+/// Snapshot tests for the article constructs rendered by the mdBook generator
+///
+/// Each test renders a schema whose article uses one construct and compares the
+/// article part of the page with `tests/snapshots/<name>.md`. Run with
+/// `UPDATE_SNAPSHOTS=1` to rewrite the snapshots after an intended change.
+use kb_core::schema::*;
+use kb_core::Schema;
+use kb_mdbook::MdBookGenerator;
+use std::path::PathBuf;
+
+fn schema_with_article(article: StructuredArticle) -> Schema {
+    Schema {
+        topic: "calculus.derivative".to_string(),
+        title: "Derivatives".to_string(),
+        description: "Symbolic differentiation".to_string(),
+        mathematical_definition: None,
+        code_refs: None,
+        examples: vec![],
+        article: Some(Article::Structured(Box::new(article))),
+        use_cases: vec![],
+        related_topics: vec![],
+        performance: None,
+        interactive_playground: None,
+        outputs: OutputHints::default(),
+        metadata: None,
+        seo: None,
+    }
+}
+
+fn article() -> StructuredArticle {
+    StructuredArticle {
+        introduction: Introduction {
+            hook: "Derivatives measure change.".to_string(),
+            learning_objectives: vec![],
+            prerequisites: vec![],
+            estimated_time: None,
+        },
+        sections: vec![],
+        conclusion: None,
+        sidebars: vec![],
+        variations: None,
+    }
+}
+
+fn conclusion() -> Conclusion {
+    Conclusion {
+        summary: "Differentiation is mechanical.".to_string(),
+        next_steps: vec![],
+        further_reading: vec![],
+        exercises: vec![],
+    }
+}
+
+/// Render the article part of the page (between the description and the examples)
+fn render_article(article: StructuredArticle) -> String {
+    let output = MdBookGenerator::new()
+        .unwrap()
+        .generate(&schema_with_article(article))
+        .unwrap();
+    let start = output.find("Symbolic differentiation").unwrap() + "Symbolic differentiation".len();
+    let end = output.find("## Examples").unwrap();
+    format!("{}\n", output[start..end].trim())
+}
+
+fn assert_snapshot(name: &str, actual: &str) {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/snapshots");
+    path.push(format!("{}.md", name));
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "Missing snapshot {}; run with UPDATE_SNAPSHOTS=1",
+            path.display()
+        )
+    });
+    assert_eq!(
+        expected, actual,
+        "Snapshot {} changed; run with UPDATE_SNAPSHOTS=1 to accept",
+        name
+    );
+}
+
+#[test]
+fn test_introduction_snapshot() {
+    let mut article = article();
+    article.introduction.estimated_time = Some("15 minutes".to_string());
+    article.introduction.prerequisites = vec!["Limits".to_string()];
+    article.introduction.learning_objectives = vec![
+        "Differentiate polynomials".to_string(),
+        "Apply the chain rule".to_string(),
+    ];
+    assert_snapshot("introduction", &render_article(article));
+}
+
+#[test]
+fn test_sections_and_subsections_snapshot() {
+    let mut article = article();
+    article.sections = vec![Section {
+        title: "Rules".to_string(),
+        content: "Derivatives follow a few rules.".to_string(),
+        subsections: vec![
+            SubSection {
+                title: "Power Rule".to_string(),
+                content: "Bring the exponent down.".to_string(),
+            },
+            SubSection {
+                title: "Chain Rule".to_string(),
+                content: "Differentiate the outer function first.".to_string(),
+            },
+        ],
+        code_examples: vec!["let df = f.derivative(&x);".to_string()],
+        math_content: Some("$$\\frac{d}{dx} x^n = n x^{n-1}$$".to_string()),
+    }];
+    assert_snapshot("sections", &render_article(article));
+}
+
+#[test]
+fn test_sidebars_snapshot() {
+    let mut article = article();
+    article.sidebars = [
+        SidebarType::Tip,
+        SidebarType::Warning,
+        SidebarType::Note,
+        SidebarType::Info,
+        SidebarType::Performance,
+        SidebarType::BestPractice,
+    ]
+    .into_iter()
+    .map(|sidebar_type| Sidebar {
+        title: format!("{:?} title", sidebar_type),
+        content: "First line.\n\nSecond paragraph.".to_string(),
+        sidebar_type,
+    })
+    .collect();
+    assert_snapshot("sidebars", &render_article(article));
+}
+
+#[test]
+fn test_exercises_snapshot() {
+    let mut conclusion = conclusion();
+    conclusion.next_steps = vec!["Try integrals".to_string()];
+    conclusion.exercises = vec![
+        Exercise {
+            prompt: "Differentiate x^2".to_string(),
+            difficulty: ExerciseDifficulty::Beginner,
+            hints: vec!["Use the power rule".to_string()],
+            solution: Some("2*x".to_string()),
+        },
+        Exercise {
+            prompt: "Differentiate sin(x^2)".to_string(),
+            difficulty: ExerciseDifficulty::Intermediate,
+            hints: vec![
+                "Use the chain rule".to_string(),
+                "The inner function is x^2".to_string(),
+            ],
+            solution: Some("2*x*cos(x^2)".to_string()),
+        },
+        Exercise {
+            prompt: "Differentiate x^x".to_string(),
+            difficulty: ExerciseDifficulty::Advanced,
+            hints: vec![],
+            solution: Some("Take logarithms first:\nx^x * (ln(x) + 1)".to_string()),
+        },
+        Exercise {
+            prompt: "Prove the product rule".to_string(),
+            difficulty: ExerciseDifficulty::Expert,
+            hints: vec![],
+            solution: None,
+        },
+    ];
+    let mut article = article();
+    article.conclusion = Some(conclusion);
+    assert_snapshot("exercises", &render_article(article));
+}
+
+#[test]
+fn test_further_reading_snapshot() {
+    let mut conclusion = conclusion();
+    conclusion.further_reading = vec![
+        Resource {
+            title: "Calculus".to_string(),
+            url: Some("https://example.com/calculus".to_string()),
+            description: Some("An introductory text".to_string()),
+        },
+        Resource {
+            title: "SymPy docs".to_string(),
+            url: Some("https://docs.sympy.org".to_string()),
+            description: None,
+        },
+        Resource {
+            title: "Lecture notes".to_string(),
+            url: None,
+            description: Some("Chapter 3".to_string()),
+        },
+    ];
+    let mut article = article();
+    article.conclusion = Some(conclusion);
+    assert_snapshot("further_reading", &render_article(article));
+}
@@ -282,7 +282,7 @@ impl SitemapGenerator {
         // Use SEO metadata if available
         if let Some(seo) = &schema.seo {
             entry.priority = Some(seo.priority);
-            entry.changefreq = Some(seo.change_frequency.clone());
+            entry.changefreq = Some(seo.change_frequency.as_str().to_string());
         } else {
             entry.priority = Some(self.config.default_priority);
             entry.changefreq = Some(self.config.default_change_freq.clone());
@@ -6,6 +6,9 @@ use kb_core::generator::OutputGenerator;
 use kb_core::{Result, Schema};
 use tera::{Context, Tera};
 
+/// Output format version; bump whenever the component template changes
+const FORMAT_VERSION: &str = "1";
+
 /// Vue SSR site generator
 pub struct VueGenerator {
     tera: Tera,
@@ -92,6 +95,14 @@ impl OutputGenerator for VueGenerator {
         "vue"
     }
 
+    fn description(&self) -> &str {
+        "Vue SSR site components (.vue)"
+    }
+
+    fn version(&self) -> &str {
+        FORMAT_VERSION
+    }
+
     fn generate(&self, schema: &Schema) -> Result<String> {
         self.generate_component(schema)
     }
@@ -275,6 +286,7 @@ mod tests {
                     nodejs: "const f = x**2;".to_string(),
                 },
                 expected_output: Some("2*x".to_string()),
+                doctest: Default::default(),
             }],
             article: None,
             use_cases: vec![],